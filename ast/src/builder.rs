@@ -1,13 +1,15 @@
-use super::repo::{check_revs_files, Repo};
-use crate::lang::graphs::Graph;
+use super::repo::{check_revs_changes, Repo};
+use crate::gat::FileChange;
+use crate::lang::graphs::{Edge, Graph};
 use crate::lang::{asg::NodeData, graphs::NodeType};
-use crate::lang::{ArrayGraph, BTreeMapGraph, Node};
-use crate::utils::create_node_key;
+use crate::lang::{features, ArrayGraph, BTreeMapGraph, Node};
+use crate::utils::{create_node_key, get_parse_threads};
 use anyhow::{Ok, Result};
 use git_url_parse::GitUrl;
 use lsp::{git::get_commit_hash, strip_root, Cmd as LspCmd, DidOpen};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::fs;
 use tracing::{debug, info};
 
@@ -30,7 +32,7 @@ impl Repo {
             }
         }
 
-        let mut graph = G::new();
+        let mut graph = G::try_new()?;
 
         println!("Root: {:?}", self.root);
         let commit_hash = get_commit_hash(&self.root.to_str().unwrap()).await?;
@@ -178,6 +180,12 @@ impl Repo {
             }
         }
 
+        let threads = get_parse_threads();
+        info!("=> parsing {} files on {} threads...", filez.len(), threads);
+        par_files(&filez, threads, |filename, code| {
+            self.lang.cache_tree(code, filename)
+        })?;
+
         i = 0;
         let pkg_files = filez.iter().filter(|(f, _)| {
             self.lang
@@ -206,9 +214,10 @@ impl Repo {
 
         i = 0;
         info!("=> get_imports...");
-        for (filename, code) in &filez {
-            let imports = self.lang.get_imports::<G>(&code, &filename)?;
-
+        let all_imports = par_files(&filez, threads, |filename, code| {
+            self.lang.get_imports::<G>(code, filename)
+        })?;
+        for imports in all_imports {
             let import_section = combine_imports(imports);
             if !import_section.is_empty() {
                 i += 1;
@@ -226,9 +235,10 @@ impl Repo {
 
         i = 0;
         info!("=> get_varables...");
-        for (filename, code) in &filez {
-            let variables = self.lang.get_varables::<G>(&code, &filename)?;
-
+        let all_variables = par_files(&filez, threads, |filename, code| {
+            self.lang.get_varables::<G>(code, filename)
+        })?;
+        for variables in all_variables {
             i += variables.len();
             for variable in variables {
                 graph.add_node_with_parent(
@@ -243,6 +253,7 @@ impl Repo {
 
         i = 0;
         info!("=> get_classes...");
+        // sequential: associations resolve against classes added from earlier files
         for (filename, code) in &filez {
             let qo = self
                 .lang
@@ -268,19 +279,21 @@ impl Repo {
         graph.class_includes();
 
         info!("=> get_instances...");
-        for (filename, code) in &filez {
+        let all_instances = par_files(&filez, threads, |filename, code| {
             let q = self.lang.lang().instance_definition_query();
-            let instances =
-                self.lang
-                    .get_query_opt::<G>(q, &code, &filename, NodeType::Instance)?;
-
+            self.lang
+                .get_query_opt::<G>(q, code, filename, NodeType::Instance)
+        })?;
+        for instances in all_instances {
             graph.add_instances(instances);
         }
 
         i = 0;
         info!("=> get_traits...");
-        for (filename, code) in &filez {
-            let traits = self.lang.get_traits::<G>(&code, &filename)?;
+        let all_traits = par_files(&filez, threads, |filename, code| {
+            self.lang.get_traits::<G>(code, filename)
+        })?;
+        for traits in all_traits {
             i += traits.len();
 
             for tr in traits {
@@ -289,18 +302,61 @@ impl Repo {
         }
         info!("=> got {} traits", i);
 
+        i = 0;
+        info!("=> get_interfaces...");
+        let all_interfaces = par_files(&filez, threads, |filename, code| {
+            self.lang.get_interfaces::<G>(code, filename)
+        })?;
+        for interfaces in all_interfaces {
+            i += interfaces.len();
+            for iface in interfaces {
+                graph.add_node_with_parent(
+                    NodeType::Interface,
+                    iface.clone(),
+                    NodeType::File,
+                    &iface.file,
+                );
+            }
+        }
+        info!("=> got {} interfaces", i);
+        // classes are all in by now, link them to what they implement
+        let separator = self.lang.lang().qualified_separator();
+        for class in graph.find_nodes_by_type(NodeType::Class) {
+            if let Some(implements) = class.meta.get("implements") {
+                for name in implements.split(',').map(|n| n.trim()) {
+                    if let Some(iface) = implemented_interface(&graph, &class, name, separator) {
+                        graph.add_edge(Edge::implements(&class, &iface));
+                    }
+                }
+            }
+        }
+
+        i = 0;
+        info!("=> get_enums...");
+        let all_enums = par_files(&filez, threads, |filename, code| {
+            self.lang.get_enums::<G>(code, filename)
+        })?;
+        for enums in all_enums {
+            i += enums.len();
+            for en in enums {
+                graph.add_node_with_parent(NodeType::Enum, en.clone(), NodeType::File, &en.file);
+            }
+        }
+        info!("=> got {} enums", i);
+
         i = 0;
         info!("=> get_structs...");
-        for (filename, code) in &filez {
+        let all_structs = par_files(&filez, threads, |filename, code| {
             if let Some(dmf) = self.lang.lang().data_model_path_filter() {
                 if !filename.contains(&dmf) {
-                    continue;
+                    return Ok(Vec::new());
                 }
             }
             let q = self.lang.lang().data_model_query();
-            let structs = self
-                .lang
-                .get_query_opt::<G>(q, &code, &filename, NodeType::DataModel)?;
+            self.lang
+                .get_query_opt::<G>(q, code, filename, NodeType::DataModel)
+        })?;
+        for structs in all_structs {
             i += structs.len();
 
             for st in &structs {
@@ -312,27 +368,112 @@ impl Repo {
                 );
             }
         }
+        if let Some(q) = self.lang.lang().data_model_registry_query() {
+            let all_registered = par_files(&filez, threads, |filename, code| {
+                self.lang
+                    .get_query_opt::<G>(Some(q.clone()), code, filename, NodeType::DataModel)
+            })?;
+            let registered = all_registered
+                .into_iter()
+                .flatten()
+                .map(|dm| dm.name)
+                .collect::<HashSet<_>>();
+            let models = graph.find_nodes_by_type(NodeType::DataModel);
+            for class in graph.find_nodes_by_type(NodeType::Class) {
+                if registered.contains(&class.name)
+                    && !models
+                        .iter()
+                        .any(|m| m.name == class.name && m.file == class.file)
+                {
+                    i += 1;
+                    graph.add_node_with_parent(
+                        NodeType::DataModel,
+                        class.clone(),
+                        NodeType::File,
+                        &class.file,
+                    );
+                }
+            }
+        }
         info!("=> got {} data models", i);
 
+        i = 0;
+        info!("=> get_fields...");
+        let all_fields = par_files(&filez, threads, |filename, code| {
+            self.lang.get_fields::<G>(code, filename)
+        })?;
+        // data models first, so a go struct's fields go to its data model
+        // and not to a class of the same name
+        let mut owner_names = HashSet::new();
+        let mut owners: HashMap<String, Vec<(NodeType, NodeData)>> = HashMap::new();
+        for nt in [NodeType::DataModel, NodeType::Class, NodeType::Interface] {
+            for n in graph.find_nodes_by_type(nt.clone()) {
+                if owner_names.insert((n.file.clone(), n.name.clone())) {
+                    owners
+                        .entry(n.file.clone())
+                        .or_default()
+                        .push((nt.clone(), n));
+                }
+            }
+        }
+        for fields in all_fields {
+            let mut seen = HashSet::new();
+            for mut field in fields {
+                // one attr_accessor can declare several fields
+                if !seen.insert((field.start_byte, field.name.clone())) {
+                    continue;
+                }
+                // the innermost class or data model around the field
+                let owner = owners
+                    .get(&field.file)
+                    .into_iter()
+                    .flatten()
+                    .filter(|(_, o)| {
+                        o.start_byte <= field.start_byte && field.end_byte <= o.end_byte
+                    })
+                    .min_by_key(|(_, o)| o.end_byte - o.start_byte);
+                if let Some((owner_type, owner)) = owner {
+                    if let Some(q) = owner.qualified_name() {
+                        field.add_qualified_name(&format!("{}{}{}", q, separator, field.name));
+                    }
+                    i += 1;
+                    graph.add_node(NodeType::Field, field.clone());
+                    graph.add_edge(Edge::contains(
+                        owner_type.clone(),
+                        owner,
+                        NodeType::Field,
+                        &field,
+                    ));
+                }
+            }
+        }
+        info!("=> got {} fields", i);
+
         // this also adds requests and data models inside
         i = 0;
         info!("=> get_functions_and_tests...");
-        for (filename, code) in &filez {
+        let all_funcs = par_files(&filez, threads, |filename, code| {
             let (funcs, tests) =
                 self.lang
-                    .get_functions_and_tests(&code, &filename, &graph, &self.lsp_tx)?;
+                    .get_functions_and_tests(code, filename, &graph, &self.lsp_tx)?;
+            let mut var_edges = Vec::new();
+            for func in &funcs {
+                let func_node = &func.0;
+                var_edges.extend(self.lang.collect_var_call_in_function(
+                    func_node,
+                    &graph,
+                    &self.lsp_tx,
+                ));
+            }
+            Ok((funcs, tests, var_edges))
+        })?;
+        for (funcs, tests, var_edges) in all_funcs {
             i += funcs.len();
 
-            graph.add_functions(funcs.clone());
+            graph.add_functions(funcs);
 
-            for func in &funcs {
-                let func_node = &func.0;
-                let var_edges =
-                    self.lang
-                        .collect_var_call_in_function(func_node, &graph, &self.lsp_tx);
-                for edge in var_edges {
-                    graph.add_edge(edge);
-                }
+            for edge in var_edges {
+                graph.add_edge(edge);
             }
             i += tests.len();
 
@@ -350,14 +491,15 @@ impl Repo {
         // frontend "pages" (react-router-dom etc)
         i = 0;
         info!("=> get_pages");
-        for (filename, code) in &filez {
-            if self.lang.lang().is_router_file(&filename, &code) {
-                let pages = self
-                    .lang
-                    .get_pages(&code, &filename, &self.lsp_tx, &graph)?;
-                i += pages.len();
-                graph.add_pages(pages);
+        let all_pages = par_files(&filez, threads, |filename, code| {
+            if !self.lang.lang().is_router_file(filename, code) {
+                return Ok(Vec::new());
             }
+            self.lang.get_pages(code, filename, &self.lsp_tx, &graph)
+        })?;
+        for pages in all_pages {
+            i += pages.len();
+            graph.add_pages(pages);
         }
         info!("=> got {} pages", i);
 
@@ -387,19 +529,20 @@ impl Repo {
         // these are more subjective queries (with regex)
         i = 0;
         info!("=> get_endpoints...");
-        for (filename, code) in &filez {
+        let all_endpoints = par_files(&filez, threads, |filename, code| {
             if let Some(epf) = self.lang.lang().endpoint_path_filter() {
                 if !filename.contains(&epf) {
-                    continue;
+                    return Ok(Vec::new());
                 }
             }
-            if self.lang.lang().is_test_file(&filename) {
-                continue;
+            if self.lang.lang().is_test_file(filename) {
+                return Ok(Vec::new());
             }
             debug!("get_endpoints in {:?}", filename);
-            let endpoints =
-                self.lang
-                    .collect_endpoints(&code, &filename, Some(&graph), &self.lsp_tx)?;
+            self.lang
+                .collect_endpoints(code, filename, Some(&graph), &self.lsp_tx)
+        })?;
+        for endpoints in all_endpoints {
             i += endpoints.len();
 
             graph.add_endpoints(endpoints);
@@ -407,14 +550,15 @@ impl Repo {
         info!("=> got {} endpoints", i);
 
         info!("=> get_endpoint_groups...");
-        for (filename, code) in &filez {
-            if self.lang.lang().is_test_file(&filename) {
-                continue;
+        let all_endpoint_groups = par_files(&filez, threads, |filename, code| {
+            if self.lang.lang().is_test_file(filename) {
+                return Ok(Vec::new());
             }
             let q = self.lang.lang().endpoint_group_find();
-            let endpoint_groups =
-                self.lang
-                    .get_query_opt::<G>(q, &code, &filename, NodeType::Endpoint)?;
+            self.lang
+                .get_query_opt::<G>(q, code, filename, NodeType::Endpoint)
+        })?;
+        for endpoint_groups in all_endpoint_groups {
             let _ = graph.process_endpoint_groups(endpoint_groups, &self.lang);
         }
 
@@ -426,16 +570,19 @@ impl Repo {
 
         i = 0;
         info!("=> get_import_edges...");
-        for (filename, code) in &filez {
+        let all_import_edges = par_files(&filez, threads, |filename, code| {
             if let Some(import_query) = self.lang.lang().imports_query() {
                 let q = self.lang.q(&import_query, &NodeType::Import);
-                let import_edges =
-                    self.lang
-                        .collect_import_edges(&q, &code, &filename, &graph, &self.lsp_tx)?;
-                for edge in import_edges {
-                    graph.add_edge(edge);
-                    i += 1;
-                }
+                self.lang
+                    .collect_import_edges(&q, code, filename, &graph, &self.lsp_tx)
+            } else {
+                Ok(Vec::new())
+            }
+        })?;
+        for import_edges in all_import_edges {
+            for edge in import_edges {
+                graph.add_edge(edge);
+                i += 1;
             }
         }
         info!("=> got {} import edges", i);
@@ -443,13 +590,13 @@ impl Repo {
         i = 0;
         if self.lang.lang().use_integration_test_finder() {
             info!("=> get_integration_tests...");
-            for (filename, code) in &filez {
-                if !self.lang.lang().is_test_file(&filename) {
-                    continue;
+            let all_int_tests = par_files(&filez, threads, |filename, code| {
+                if !self.lang.lang().is_test_file(filename) {
+                    return Ok(Vec::new());
                 }
-                let int_tests = self
-                    .lang
-                    .collect_integration_tests(code, filename, &graph)?;
+                self.lang.collect_integration_tests(code, filename, &graph)
+            })?;
+            for int_tests in all_int_tests {
                 i += int_tests.len();
                 for (nd, tt, edge_opt) in int_tests {
                     graph.add_test_node(nd, tt, edge_opt);
@@ -464,11 +611,15 @@ impl Repo {
         } else {
             i = 0;
             info!("=> get_function_calls...");
-            for (filename, code) in &filez {
-                let all_calls = self
-                    .lang
-                    .get_function_calls(&code, &filename, &graph, &self.lsp_tx)
-                    .await?;
+            let calls_by_file = par_files(&filez, threads, |filename, code| {
+                futures::executor::block_on(self.lang.get_function_calls(
+                    code,
+                    filename,
+                    &graph,
+                    &self.lsp_tx,
+                ))
+            })?;
+            for all_calls in calls_by_file {
                 i += all_calls.0.len();
                 graph.add_calls(all_calls);
             }
@@ -482,28 +633,30 @@ impl Repo {
                 graph.filter_out_nodes_without_children(parent_type, child_type, child_meta_key);
             });
 
+        self.lang.clear_trees();
+
         // filter by revs
         graph = filter_by_revs(&self.root.to_str().unwrap(), self.revs.clone(), graph);
 
         // prefix the "file" of each node and edge with the root
         graph.prefix_paths(&self.root_less_tmp());
 
+        if let Some(fconfig) = self.read_config_file().and_then(|c| c.features) {
+            info!("=> detect_features...");
+            features::detect_features(&mut graph, &self.root_less_tmp(), &fconfig)?;
+        }
+
         println!("done!");
         let (num_of_nodes, num_of_edges) = graph.get_graph_size();
         println!(
             "Returning Graph with {} nodes and {} edges",
             num_of_nodes, num_of_edges
         );
+        graph.check_writes()?;
         Ok(graph)
     }
     fn root_less_tmp(&self) -> String {
-        let mut ret = self.root.display().to_string();
-        if ret.starts_with("/tmp/") {
-            ret.drain(0..5);
-            ret
-        } else {
-            ret
-        }
+        root_less_tmp(&self.root.display().to_string())
     }
     fn prepare_file_data(&self, path: &str, code: &str) -> NodeData {
         let mut file_data = NodeData::in_file(path);
@@ -528,14 +681,140 @@ impl Repo {
     }
 }
 
+// the prefix given to the "file" of every node
+pub fn root_less_tmp(root: &str) -> String {
+    let mut ret = root.to_string();
+    if ret.starts_with("/tmp/") {
+        ret.drain(0..5);
+        ret
+    } else {
+        ret
+    }
+}
+
+// the interface a class names in its implements clause. with several of
+// that name, only one in the class's own file, one it imports, or one in
+// the same module counts; anything still ambiguous gets no edge
+fn implemented_interface<G: Graph>(
+    graph: &G,
+    class: &NodeData,
+    name: &str,
+    separator: &str,
+) -> Option<NodeData> {
+    let candidates = graph.find_nodes_by_name(NodeType::Interface, name);
+    if candidates.len() <= 1 {
+        return candidates.into_iter().next();
+    }
+    let same_file = candidates
+        .iter()
+        .filter(|i| i.file == class.file)
+        .collect::<Vec<_>>();
+    if same_file.len() == 1 {
+        return Some(same_file[0].clone());
+    }
+    let import_lines = graph
+        .find_nodes_by_file_ends_with(NodeType::Import, &class.file)
+        .into_iter()
+        .filter(|i| i.file == class.file)
+        .flat_map(|i| {
+            i.body
+                .lines()
+                .filter(|l| l.contains(name))
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let imported = candidates
+        .iter()
+        .filter(|i| {
+            let stem = file_stem(&i.file);
+            import_lines.iter().any(|l| l.contains(stem))
+        })
+        .collect::<Vec<_>>();
+    if imported.len() == 1 {
+        return Some(imported[0].clone());
+    }
+    let module = |q: &String| q.rsplit_once(separator).map(|(m, _)| m.to_string());
+    let class_module = class.qualified_name().and_then(module)?;
+    let same_module = candidates
+        .iter()
+        .filter(|i| i.qualified_name().and_then(module).as_ref() == Some(&class_module))
+        .collect::<Vec<_>>();
+    if same_module.len() == 1 {
+        return Some(same_module[0].clone());
+    }
+    None
+}
+
+// "a/b/model.ts" => "model"
+fn file_stem(file: &str) -> &str {
+    let last = file.rsplit('/').next().unwrap_or(file);
+    last.split_once('.').map(|(stem, _)| stem).unwrap_or(last)
+}
+
 fn filter_by_revs<G: Graph>(root: &str, revs: Vec<String>, graph: G) -> G {
     if revs.is_empty() {
         return graph;
     }
-    match check_revs_files(root, revs) {
-        Some(final_filter) => graph.create_filtered_graph(&final_filter),
-        None => graph,
+    let changes = match check_revs_changes(root, revs) {
+        Some(changes) => changes,
+        None => return graph,
+    };
+    let mut final_filter = Vec::new();
+    for change in &changes {
+        match change {
+            FileChange::Added(path) | FileChange::Modified(path) => {
+                final_filter.push(path.clone());
+            }
+            // only the new path is in the working tree
+            FileChange::Renamed { from, to } => {
+                debug!("renamed {} -> {}", from, to);
+                final_filter.push(to.clone());
+            }
+            // nothing left to parse
+            FileChange::Deleted(path) => {
+                debug!("deleted {}", path);
+            }
+        }
+    }
+    graph.create_filtered_graph(&final_filter)
+}
+
+// runs `f` on each (file, code) across a pool of worker threads.
+// results come back in the same order as `filez`, so merging into the graph stays deterministic
+fn par_files<T, F>(filez: &[(String, String)], threads: usize, f: F) -> Result<Vec<T>>
+where
+    T: Send,
+    F: Fn(&str, &str) -> Result<T> + Sync,
+{
+    if threads <= 1 || filez.len() <= 1 {
+        return filez.iter().map(|(file, code)| f(file, code)).collect();
     }
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<T>)> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..threads.min(filez.len()))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        if idx >= filez.len() {
+                            break;
+                        }
+                        let (file, code) = &filez[idx];
+                        done.push((idx, f(file, code)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("parse worker panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, res)| res).collect()
 }
 
 // (file, code)
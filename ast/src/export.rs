@@ -0,0 +1,604 @@
+use crate::lang::asg::NodeData;
+use crate::lang::graphs::{ArrayGraph, Edge, EdgeType, Node, NodeRef, NodeType};
+use crate::lang::metrics::METRIC_KEYS;
+use crate::lang::{BTreeMapGraph, Graph};
+use crate::utils::{create_node_key, create_node_key_from_ref};
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::{debug, info};
+
+pub const NODE_TYPES: [NodeType; 21] = [
+    NodeType::Repository,
+    NodeType::Language,
+    NodeType::Directory,
+    NodeType::File,
+    NodeType::Import,
+    NodeType::Library,
+    NodeType::Class,
+    NodeType::Trait,
+    NodeType::Instance,
+    NodeType::Function,
+    NodeType::Test,
+    NodeType::E2eTest,
+    NodeType::Endpoint,
+    NodeType::Request,
+    NodeType::DataModel,
+    NodeType::Feature,
+    NodeType::Page,
+    NodeType::Var,
+    NodeType::Field,
+    NodeType::Enum,
+    NodeType::Interface,
+];
+
+pub const EDGE_TYPES: [EdgeType; 12] = [
+    EdgeType::Calls,
+    EdgeType::Uses,
+    EdgeType::Operand,
+    EdgeType::ArgOf,
+    EdgeType::Contains,
+    EdgeType::Imports,
+    EdgeType::Of,
+    EdgeType::Handler,
+    EdgeType::Includes,
+    EdgeType::Renders,
+    EdgeType::ParentOf,
+    EdgeType::Implements,
+];
+
+// jsonl writes "{path}-nodes.jsonl" and "{path}-edges.jsonl", csv writes a
+// directory of files, the rest write `path` itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+    Jsonl,
+    Json,
+    GraphMl,
+    Dot { cluster_by_directory: bool },
+    Cypher,
+    Csv,
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "jsonl" => Ok(ExportFormat::Jsonl),
+            "json" => Ok(ExportFormat::Json),
+            "graphml" => Ok(ExportFormat::GraphMl),
+            "dot" => Ok(ExportFormat::Dot {
+                cluster_by_directory: false,
+            }),
+            "cypher" => Ok(ExportFormat::Cypher),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(anyhow!("unknown output format {}", s)),
+        }
+    }
+}
+
+impl ExportFormat {
+    // the path when none is given, next to the other examples
+    pub fn default_path(&self, name: &str) -> PathBuf {
+        let file = match self {
+            ExportFormat::Jsonl => name.to_string(),
+            ExportFormat::Json => format!("{}.json", name),
+            ExportFormat::GraphMl => format!("{}.graphml", name),
+            ExportFormat::Dot { .. } => format!("{}.dot", name),
+            ExportFormat::Cypher => format!("{}.cypher", name),
+            ExportFormat::Csv => format!("{}-csv", name),
+        };
+        Path::new("ast/examples").join(file)
+    }
+}
+
+// every node and edge in the graph. ArrayGraph edges are as stored, so they
+// can point at nodes that were never added (like calls to unresolved functions)
+pub fn graph_contents<G: Graph + 'static>(graph: &G) -> (Vec<Node>, Vec<Edge>) {
+    if let Some(array_graph) = (graph as &dyn Any).downcast_ref::<ArrayGraph>() {
+        return (array_graph.nodes.clone(), array_graph.edges.clone());
+    }
+    if let Some(btreemap_graph) = (graph as &dyn Any).downcast_ref::<BTreeMapGraph>() {
+        let nodes = btreemap_graph.nodes.values().cloned().collect();
+        return (nodes, btreemap_graph.to_array_graph_edges());
+    }
+    // anything else only has the trait to go through
+    let mut nodes = Vec::new();
+    for nt in NODE_TYPES.iter() {
+        for nd in graph.find_nodes_by_type(nt.clone()) {
+            nodes.push(Node::new(nt.clone(), nd));
+        }
+    }
+    (nodes, graph.get_edges())
+}
+
+// the node keys at both ends of each edge. An end that doesn't match a key
+// exactly falls back to the same type and name in a file ending with its
+// file; edges with an end that still isn't found are left out
+pub fn keyed_edges<'a>(nodes: &[Node], edges: &'a [Edge]) -> Vec<(String, String, &'a Edge)> {
+    let keys = nodes.iter().map(create_node_key).collect::<HashSet<_>>();
+    let mut by_name: BTreeMap<(NodeType, &str), Vec<(&str, String)>> = BTreeMap::new();
+    for node in nodes {
+        by_name
+            .entry((node.node_type.clone(), node.node_data.name.as_str()))
+            .or_default()
+            .push((node.node_data.file.as_str(), create_node_key(node)));
+    }
+    let resolve = |node_ref: &NodeRef| {
+        let key = create_node_key_from_ref(node_ref);
+        if keys.contains(&key) {
+            return Some(key);
+        }
+        by_name
+            .get(&(node_ref.node_type.clone(), node_ref.node_data.name.as_str()))?
+            .iter()
+            .find(|(file, _)| file.ends_with(node_ref.node_data.file.as_str()))
+            .map(|(_, key)| key.clone())
+    };
+    let keyed = edges
+        .iter()
+        .filter_map(|e| Some((resolve(&e.source)?, resolve(&e.target)?, e)))
+        .collect::<Vec<_>>();
+    if keyed.len() < edges.len() {
+        debug!("left out {} dangling edges", edges.len() - keyed.len());
+    }
+    keyed
+}
+
+// the properties a node gets in Neo4j, all as strings
+pub fn node_properties(nd: &NodeData) -> Vec<(String, String)> {
+    let mut props = vec![
+        ("name".to_string(), nd.name.clone()),
+        ("file".to_string(), nd.file.clone()),
+        ("start".to_string(), nd.start.to_string()),
+        ("end".to_string(), nd.end.to_string()),
+        ("start_col".to_string(), nd.start_col.to_string()),
+        ("end_col".to_string(), nd.end_col.to_string()),
+        ("start_byte".to_string(), nd.start_byte.to_string()),
+        ("end_byte".to_string(), nd.end_byte.to_string()),
+        ("body".to_string(), nd.body.clone()),
+    ];
+    if let Some(data_type) = &nd.data_type {
+        props.push(("data_type".to_string(), data_type.clone()));
+    }
+    if let Some(docs) = &nd.docs {
+        props.push(("docs".to_string(), docs.clone()));
+    }
+    if let Some(hash) = &nd.hash {
+        props.push(("hash".to_string(), hash.clone()));
+    }
+    if let Some(qualified_name) = nd.qualified_name() {
+        props.push(("qualified_name".to_string(), qualified_name.clone()));
+    }
+    props.push((
+        "meta".to_string(),
+        serde_json::to_string(&nd.meta).unwrap_or_default(),
+    ));
+    for key in METRIC_KEYS {
+        if let Some(value) = nd.meta.get(key) {
+            props.push((key.to_string(), value.clone()));
+        }
+    }
+    props
+}
+
+// XML 1.0 has no place for most control characters
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => (),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+const GRAPHML_NODE_KEYS: [&str; 8] = [
+    "label",
+    "node_type",
+    "name",
+    "file",
+    "start",
+    "end",
+    "body",
+    "meta",
+];
+
+// GraphML for Gephi and yEd, with the node key as the id
+pub fn to_graphml(nodes: &[Node], edges: &[Edge]) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for key in GRAPHML_NODE_KEYS {
+        out.push_str(&format!(
+            "  <key id=\"{}\" for=\"node\" attr.name=\"{}\" attr.type=\"string\"/>\n",
+            key, key
+        ));
+    }
+    out.push_str(
+        "  <key id=\"edge_type\" for=\"edge\" attr.name=\"edge_type\" attr.type=\"string\"/>\n",
+    );
+    out.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+    // ids must be unique, and two nodes can share a key
+    let mut ids = HashSet::new();
+    for node in nodes {
+        let id = create_node_key(node);
+        if !ids.insert(id.clone()) {
+            continue;
+        }
+        let nd = &node.node_data;
+        let values = [
+            nd.name.clone(),
+            node.node_type.to_string(),
+            nd.name.clone(),
+            nd.file.clone(),
+            nd.start.to_string(),
+            nd.end.to_string(),
+            nd.body.clone(),
+            serde_json::to_string(&nd.meta).unwrap_or_default(),
+        ];
+        out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&id)));
+        for (key, value) in GRAPHML_NODE_KEYS.iter().zip(values) {
+            out.push_str(&format!(
+                "      <data key=\"{}\">{}</data>\n",
+                key,
+                xml_escape(&value)
+            ));
+        }
+        out.push_str("    </node>\n");
+    }
+    for (i, (source, target, edge)) in keyed_edges(nodes, edges).into_iter().enumerate() {
+        out.push_str(&format!(
+            "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n      <data key=\"edge_type\">{}</data>\n    </edge>\n",
+            i,
+            xml_escape(&source),
+            xml_escape(&target),
+            edge.edge.to_string()
+        ));
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+pub fn dot_shape(node_type: &NodeType) -> &'static str {
+    match node_type {
+        NodeType::Repository => "box3d",
+        NodeType::Language => "doubleoctagon",
+        NodeType::Directory => "folder",
+        NodeType::File => "note",
+        NodeType::Import => "invhouse",
+        NodeType::Library => "component",
+        NodeType::Class => "box",
+        NodeType::Trait => "house",
+        NodeType::Instance => "oval",
+        NodeType::Function => "ellipse",
+        NodeType::Test => "diamond",
+        NodeType::E2eTest => "Mdiamond",
+        NodeType::Endpoint => "cds",
+        NodeType::Request => "rarrow",
+        NodeType::DataModel => "cylinder",
+        NodeType::Feature => "star",
+        NodeType::Page => "tab",
+        NodeType::Var => "plaintext",
+        NodeType::Field => "underline",
+        NodeType::Enum => "octagon",
+        NodeType::Interface => "hexagon",
+    }
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// Graphviz DOT with a shape per node type, and optionally a cluster per directory
+pub fn to_dot(nodes: &[Node], edges: &[Edge], cluster_by_directory: bool) -> String {
+    let node_line = |node: &Node| {
+        format!(
+            "\"{}\" [label=\"{}\", shape={}];",
+            dot_escape(&create_node_key(node)),
+            dot_escape(&node.node_data.name),
+            dot_shape(&node.node_type)
+        )
+    };
+    let mut out = String::from("digraph G {\n  rankdir=LR;\n");
+    if cluster_by_directory {
+        let mut clusters: BTreeMap<&str, Vec<&Node>> = BTreeMap::new();
+        for node in nodes {
+            let dir = node
+                .node_data
+                .file
+                .rsplit_once('/')
+                .map(|(dir, _)| dir)
+                .unwrap_or_default();
+            clusters.entry(dir).or_default().push(node);
+        }
+        for (i, (dir, members)) in clusters.into_iter().enumerate() {
+            let indent = if dir.is_empty() {
+                "  "
+            } else {
+                out.push_str(&format!(
+                    "  subgraph cluster_{} {{\n    label=\"{}\";\n",
+                    i,
+                    dot_escape(dir)
+                ));
+                "    "
+            };
+            for node in members {
+                out.push_str(&format!("{}{}\n", indent, node_line(node)));
+            }
+            if !dir.is_empty() {
+                out.push_str("  }\n");
+            }
+        }
+    } else {
+        for node in nodes {
+            out.push_str(&format!("  {}\n", node_line(node)));
+        }
+    }
+    for (source, target, edge) in keyed_edges(nodes, edges) {
+        out.push_str(&format!(
+            "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            dot_escape(&source),
+            dot_escape(&target),
+            edge.edge.to_string()
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn cypher_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+// a script of MERGE statements that can be replayed with cypher-shell,
+// keyed the same way as the Neo4j upload
+pub fn to_cypher(nodes: &[Node], edges: &[Edge]) -> String {
+    let mut out = String::new();
+    let key_of = nodes
+        .iter()
+        .map(|n| (create_node_key(n), &n.node_type))
+        .collect::<HashMap<_, _>>();
+    for node in nodes {
+        let props = node_properties(&node.node_data)
+            .into_iter()
+            .map(|(k, v)| format!("n.{} = {}", k, cypher_string(&v)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!(
+            "MERGE (n:{} {{key: {}}}) SET {};\n",
+            node.node_type.to_string(),
+            cypher_string(&create_node_key(node)),
+            props
+        ));
+    }
+    for (source, target, edge) in keyed_edges(nodes, edges) {
+        let mut line = format!(
+            "MATCH (s:{} {{key: {}}}), (t:{} {{key: {}}}) MERGE (s)-[r:{}]->(t)",
+            key_of[&source].to_string(),
+            cypher_string(&source),
+            key_of[&target].to_string(),
+            cypher_string(&target),
+            edge.edge.to_string()
+        );
+        if let Some(cs) = &edge.call_site {
+            line.push_str(&format!(
+                " ON CREATE SET r.call_start = '{}', r.call_end = '{}', r.call_start_col = '{}', r.call_end_col = '{}', r.call_start_byte = '{}', r.call_end_byte = '{}'",
+                cs.start, cs.end, cs.start_col, cs.end_col, cs.start_byte, cs.end_byte
+            ));
+        }
+        line.push_str(";\n");
+        out.push_str(&line);
+    }
+    out
+}
+
+// neo4j-admin reads "" as a quote inside a quoted field
+fn csv_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+// an empty field leaves the property unset on import
+fn write_csv_row<W: Write>(out: &mut W, fields: &[Option<&str>]) -> Result<()> {
+    let line = fields
+        .iter()
+        .map(|f| f.map(csv_quote).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(",");
+    writeln!(out, "{}", line)?;
+    Ok(())
+}
+
+const CALL_SITE_KEYS: [&str; 6] = [
+    "call_start",
+    "call_end",
+    "call_start_col",
+    "call_end_col",
+    "call_start_byte",
+    "call_end_byte",
+];
+
+// the files written by write_csv, in the order they were written
+#[derive(Debug, Clone, Default)]
+pub struct CsvExport {
+    pub nodes: Vec<PathBuf>,
+    pub relationships: Vec<PathBuf>,
+}
+
+impl CsvExport {
+    // bodies span lines, so the import needs --multiline-fields
+    pub fn import_command(&self, database: &str) -> String {
+        let mut cmd = String::from("neo4j-admin database import full --multiline-fields=true");
+        for path in &self.nodes {
+            cmd.push_str(&format!(" --nodes={}", path.display()));
+        }
+        for path in &self.relationships {
+            cmd.push_str(&format!(" --relationships={}", path.display()));
+        }
+        cmd.push(' ');
+        cmd.push_str(database);
+        cmd
+    }
+}
+
+// CSV files for `neo4j-admin database import`: "nodes_{NodeType}.csv" and
+// "relationships_{EDGE_TYPE}.csv" for each type in the graph. Nodes are keyed
+// like the Neo4j upload and every property stays a string, so the usual
+// queries work on the imported database. Like the upload's MERGEs, a repeated
+// node key keeps the last node and a repeated edge keeps the first call site.
+pub fn write_csv(nodes: &[Node], edges: &[Edge], dir: &Path) -> Result<CsvExport> {
+    std::fs::create_dir_all(dir)?;
+    let mut export = CsvExport::default();
+
+    for nt in NODE_TYPES.iter() {
+        let of_type = nodes
+            .iter()
+            .filter(|n| &n.node_type == nt)
+            .map(|n| (create_node_key(n), node_properties(&n.node_data)))
+            .collect::<BTreeMap<_, _>>();
+        if of_type.is_empty() {
+            continue;
+        }
+        let mut columns: Vec<&str> = Vec::new();
+        for props in of_type.values() {
+            for (k, _) in props {
+                if !columns.contains(&k.as_str()) {
+                    columns.push(k);
+                }
+            }
+        }
+        let path = dir.join(format!("nodes_{}.csv", nt.to_string()));
+        let mut out = BufWriter::new(File::create(&path)?);
+        writeln!(out, "key:ID,{},:LABEL", columns.join(","))?;
+        let label = nt.to_string();
+        for (key, props) in &of_type {
+            let props = props
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect::<HashMap<_, _>>();
+            let mut fields = vec![Some(key.as_str())];
+            fields.extend(columns.iter().map(|c| props.get(c).copied()));
+            fields.push(Some(&label));
+            write_csv_row(&mut out, &fields)?;
+        }
+        out.flush()?;
+        export.nodes.push(path);
+    }
+
+    let keyed = keyed_edges(nodes, edges);
+    for et in EDGE_TYPES.iter() {
+        let mut seen = HashSet::new();
+        let of_type = keyed
+            .iter()
+            .filter(|(s, t, e)| &e.edge == et && seen.insert((s, t)))
+            .collect::<Vec<_>>();
+        if of_type.is_empty() {
+            continue;
+        }
+        let rel_type = et.to_string();
+        let with_call_sites = of_type.iter().any(|(_, _, e)| e.call_site.is_some());
+        let path = dir.join(format!("relationships_{}.csv", rel_type));
+        let mut out = BufWriter::new(File::create(&path)?);
+        if with_call_sites {
+            writeln!(out, ":START_ID,:END_ID,:TYPE,{}", CALL_SITE_KEYS.join(","))?;
+        } else {
+            writeln!(out, ":START_ID,:END_ID,:TYPE")?;
+        }
+        for (source, target, edge) in of_type {
+            let call_site = edge.call_site.as_ref().map(|cs| {
+                [
+                    cs.start,
+                    cs.end,
+                    cs.start_col,
+                    cs.end_col,
+                    cs.start_byte,
+                    cs.end_byte,
+                ]
+                .map(|v| v.to_string())
+            });
+            let mut fields = vec![
+                Some(source.as_str()),
+                Some(target.as_str()),
+                Some(&rel_type),
+            ];
+            if with_call_sites {
+                match &call_site {
+                    Some(values) => fields.extend(values.iter().map(|v| Some(v.as_str()))),
+                    None => fields.extend([None; 6]),
+                }
+            }
+            write_csv_row(&mut out, &fields)?;
+        }
+        out.flush()?;
+        export.relationships.push(path);
+    }
+    Ok(export)
+}
+
+pub fn export_graph<G: Graph + Serialize + 'static>(
+    graph: &G,
+    format: &ExportFormat,
+    path: &Path,
+) -> Result<()> {
+    use serde_jsonlines::write_json_lines;
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    match format {
+        ExportFormat::Json => std::fs::write(path, serde_json::to_string_pretty(&graph)?)?,
+        ExportFormat::Jsonl => {
+            let (nodes, edges) = graph_contents(graph);
+            let path = path.display().to_string();
+            let path = path.strip_suffix(".jsonl").unwrap_or(&path);
+            write_json_lines(format!("{}-nodes.jsonl", path), &nodes)?;
+            write_json_lines(format!("{}-edges.jsonl", path), &edges)?;
+        }
+        ExportFormat::GraphMl => {
+            let (nodes, edges) = graph_contents(graph);
+            std::fs::write(path, to_graphml(&nodes, &edges))?;
+        }
+        ExportFormat::Dot {
+            cluster_by_directory,
+        } => {
+            let (nodes, edges) = graph_contents(graph);
+            std::fs::write(path, to_dot(&nodes, &edges, *cluster_by_directory))?;
+        }
+        ExportFormat::Cypher => {
+            let (nodes, edges) = graph_contents(graph);
+            std::fs::write(path, to_cypher(&nodes, &edges))?;
+        }
+        ExportFormat::Csv => {
+            let (nodes, edges) = graph_contents(graph);
+            let export = write_csv(&nodes, &edges, path)?;
+            info!("import with: {}", export.import_command("neo4j"));
+        }
+    }
+    info!("exported graph to {}", path.display());
+    Ok(())
+}
@@ -1,34 +1,68 @@
 use anyhow::Result;
-use git2::{DiffOptions, Repository};
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, Repository};
 
-pub fn get_changed_files(repo_path: &str, old_rev: &str, new_rev: &str) -> Result<Vec<String>> {
-    // Open the repository
-    let repo = Repository::open(repo_path)?;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    Added(String),
+    Modified(String),
+    Deleted(String),
+    Renamed { from: String, to: String },
+}
 
-    // Look up the two commits
-    let old_commit = repo.revparse_single(old_rev)?.peel_to_commit()?;
-    let new_commit = repo.revparse_single(new_rev)?.peel_to_commit()?;
+impl FileChange {
+    // the path in the new revision, if the file still exists
+    pub fn current_path(&self) -> Option<&str> {
+        match self {
+            FileChange::Added(path) | FileChange::Modified(path) => Some(path),
+            FileChange::Renamed { to, .. } => Some(to),
+            FileChange::Deleted(_) => None,
+        }
+    }
+}
 
-    // Get the trees for both commits
-    let old_tree = old_commit.tree()?;
-    let new_tree = new_commit.tree()?;
+// lines touched in the new revision of a file, as 0-based inclusive rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedLines {
+    pub file: String,
+    pub ranges: Vec<(usize, usize)>,
+}
 
-    // Create diff options
-    let mut diff_opts = DiffOptions::new();
+impl ChangedLines {
+    pub fn overlaps(&self, start: usize, end: usize) -> bool {
+        self.ranges.iter().any(|(s, e)| start <= *e && end >= *s)
+    }
+}
 
-    // Get the diff between the two trees
-    let diff = repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut diff_opts))?;
+pub fn get_file_changes(repo_path: &str, old_rev: &str, new_rev: &str) -> Result<Vec<FileChange>> {
+    let repo = Repository::open(repo_path)?;
+    let diff = diff_revs(&repo, old_rev, new_rev)?;
 
-    // Collect changed files
-    let mut changed_files = Vec::new();
+    let mut changes = Vec::new();
 
     // Iterate through diff deltas
     diff.foreach(
         &mut |delta, _| {
-            if let Some(new_file) = delta.new_file().path() {
-                if let Some(path_str) = new_file.to_str() {
-                    changed_files.push(path_str.to_string());
+            let old_path = delta
+                .old_file()
+                .path()
+                .and_then(|p| p.to_str())
+                .map(|p| p.to_string());
+            let new_path = delta
+                .new_file()
+                .path()
+                .and_then(|p| p.to_str())
+                .map(|p| p.to_string());
+            let change = match (delta.status(), old_path, new_path) {
+                (Delta::Added, _, Some(path)) | (Delta::Copied, _, Some(path)) => {
+                    Some(FileChange::Added(path))
                 }
+                (Delta::Deleted, Some(path), _) => Some(FileChange::Deleted(path)),
+                (Delta::Renamed, Some(from), Some(to)) => Some(FileChange::Renamed { from, to }),
+                (_, _, Some(path)) => Some(FileChange::Modified(path)),
+                _ => None,
+            };
+            if let Some(change) = change {
+                changes.push(change);
             }
             true
         },
@@ -37,5 +71,85 @@ pub fn get_changed_files(repo_path: &str, old_rev: &str, new_rev: &str) -> Resul
         None,
     )?;
 
-    Ok(changed_files)
+    Ok(changes)
+}
+
+// whether `rev` is the commit checked out in `repo_path`
+pub fn is_head(repo_path: &str, rev: &str) -> Result<bool> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+    let head = repo.head()?.peel_to_commit()?;
+    Ok(commit.id() == head.id())
+}
+
+// the changed hunks of every file that still exists in the new revision
+pub fn get_changed_lines(
+    repo_path: &str,
+    old_rev: &str,
+    new_rev: &str,
+) -> Result<Vec<ChangedLines>> {
+    let repo = Repository::open(repo_path)?;
+    let diff = diff_revs(&repo, old_rev, new_rev)?;
+
+    let mut changed: Vec<ChangedLines> = Vec::new();
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        Some(&mut |delta, hunk| {
+            if delta.status() == Delta::Deleted {
+                return true;
+            }
+            let Some(file) = delta.new_file().path().and_then(|p| p.to_str()) else {
+                return true;
+            };
+            // a pure deletion has no new lines, so mark the line it happened after
+            let start = (hunk.new_start() as usize).saturating_sub(1);
+            let end = start + (hunk.new_lines() as usize).saturating_sub(1);
+            match changed.last_mut() {
+                Some(last) if last.file == file => last.ranges.push((start, end)),
+                _ => changed.push(ChangedLines {
+                    file: file.to_string(),
+                    ranges: vec![(start, end)],
+                }),
+            }
+            true
+        }),
+        None,
+    )?;
+
+    Ok(changed)
+}
+
+fn diff_revs<'a>(repo: &'a Repository, old_rev: &str, new_rev: &str) -> Result<Diff<'a>> {
+    // Look up the two commits
+    let old_commit = repo.revparse_single(old_rev)?.peel_to_commit()?;
+    let new_commit = repo.revparse_single(new_rev)?.peel_to_commit()?;
+
+    // Get the trees for both commits
+    let old_tree = old_commit.tree()?;
+    let new_tree = new_commit.tree()?;
+
+    // Create diff options, without context lines so hunks only cover what changed
+    let mut diff_opts = DiffOptions::new();
+    diff_opts.context_lines(0);
+
+    // Get the diff between the two trees
+    let mut diff =
+        repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut diff_opts))?;
+
+    // Pair up deleted and added files into renames
+    let mut find_opts = DiffFindOptions::new();
+    find_opts.renames(true);
+    diff.find_similar(Some(&mut find_opts))?;
+
+    Ok(diff)
+}
+
+// files that exist in the new revision and need to be parsed again
+pub fn get_changed_files(repo_path: &str, old_rev: &str, new_rev: &str) -> Result<Vec<String>> {
+    let changes = get_file_changes(repo_path, old_rev, new_rev)?;
+    Ok(changes
+        .iter()
+        .filter_map(|c| c.current_path().map(|p| p.to_string()))
+        .collect())
 }
@@ -1,4 +1,7 @@
 use anyhow::{Context, Result};
+use ast::lang::dead_code::{find_dead_code, DeadCodeConfig};
+use ast::lang::dependencies::{analyze_dependencies, LayerConfig};
+use ast::lang::impact::impact_from_revs;
 use ast::repo::Repo;
 use ast::utils::{logger, print_json};
 use std::env;
@@ -19,6 +22,29 @@ export REPO_PATH=/Users/evanfeenstra/code/sphinx2/tribes-workspace/sphinx-tribes
 
 export REPO_PATH=/Users/evanfeenstra/code/sphinx2/stakgraph/ast/examples/senza-lnd
 
+export GRAPH_DB=ast/examples/tribes.db
+cargo run --bin index --features sled
+
+export IMPACT_REVS=main,HEAD
+cargo run --bin index
+
+export DEAD_CODE=table
+export DEAD_CODE_ENTRY_POINTS=main,run
+export DEAD_CODE_EXPORTED=true
+cargo run --bin index
+
+export DEPENDENCIES=table
+cargo run --bin index
+
+export OUTPUT_FORMAT=dot
+export DOT_CLUSTER=true
+export OUTPUT_PATH=/tmp/tribes.dot
+cargo run --bin index
+
+export OUTPUT_FORMAT=csv
+export OUTPUT_PATH=/tmp/tribes-csv
+cargo run --bin index
+
 */
 
 #[tokio::main]
@@ -53,7 +79,7 @@ async fn main() -> Result<()> {
 
     let name = env::var("OUTPUT_NAME").unwrap_or_else(|_| {
         repo_urls
-            .unwrap_or_else(|| repo_path.context("no REPO_PATH").unwrap())
+            .unwrap_or_else(|| repo_path.clone().context("no REPO_PATH").unwrap())
             .split('/')
             .last()
             .unwrap()
@@ -62,10 +88,81 @@ async fn main() -> Result<()> {
     });
     println!("{}", name);
 
+    // what a diff affects, measured against the full graph of the checkout
+    if let Some(impact_revs) = env_not_empty("IMPACT_REVS") {
+        let repo_path = repo_path
+            .as_deref()
+            .context("IMPACT_REVS needs a REPO_PATH")?;
+        let revs = impact_revs.split(',').map(|s| s.to_string()).collect();
+        let report = impact_from_revs(repo_path, revs).await?;
+        let path = format!("ast/examples/{}-impact.json", name);
+        std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+        println!("wrote impact to {}", path);
+        for file in report.test_files() {
+            println!("{}", file);
+        }
+        return Ok(());
+    }
+
+    // functions, classes and data models nothing reaches
+    if let Some(format) = env_not_empty("DEAD_CODE") {
+        let mut config = DeadCodeConfig::default();
+        if let Some(names) = env_not_empty("DEAD_CODE_ENTRY_POINTS") {
+            config
+                .functions
+                .extend(names.split(',').map(|s| s.trim().to_string()));
+        }
+        config.exported = env_not_empty("DEAD_CODE_EXPORTED").is_some_and(|v| v == "true");
+        let graph = repos.build_graphs().await?;
+        let report = find_dead_code(&graph, &config);
+        if format == "table" {
+            print!("{}", report.to_table());
+        } else {
+            let path = format!("ast/examples/{}-dead-code.json", name);
+            std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+            println!("wrote dead code report to {}", path);
+        }
+        return Ok(());
+    }
+
+    // import cycles, fan-in/fan-out and the layer rules from each .ast.json
+    if let Some(format) = env_not_empty("DEPENDENCIES") {
+        let mut config = LayerConfig::default();
+        for repo in &repos.0 {
+            if let Some(layers) = repo.read_config_file().and_then(|c| c.layers) {
+                config.layers.extend(layers.layers);
+                config.rules.extend(layers.rules);
+            }
+        }
+        let graph = repos.build_graphs().await?;
+        let report = analyze_dependencies(&graph, &config);
+        if format == "table" {
+            print!("{}", report.to_table());
+        } else {
+            let path = format!("ast/examples/{}-dependencies.json", name);
+            std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+            println!("wrote dependencies to {}", path);
+        }
+        return Ok(());
+    }
+
+    // persist to an embedded store that can be reopened with SledGraph::open
+    #[cfg(feature = "sled")]
+    if let Some(db_path) = env_not_empty("GRAPH_DB") {
+        let graph = repos
+            .build_graphs_inner::<ast::lang::graphs::SledGraph>()
+            .await?;
+        graph.save(&db_path)?;
+        println!("saved graph to {}", db_path);
+        return Ok(());
+    }
+
     //let graph = repos.build_graphs_btree().await?;
     let graph = repos.build_graphs().await?;
 
-    if std::env::var("OUTPUT_FORMAT")
+    if let Some(path) = env_not_empty("OUTPUT_PATH") {
+        println!("writing to {}", path);
+    } else if std::env::var("OUTPUT_FORMAT")
         .unwrap_or_else(|_| "jsonl".to_string())
         .as_str()
         == "jsonl"
@@ -1,3 +1,4 @@
+use crate::lang::metrics::{FunctionMetrics, METRIC_KEYS};
 use crate::lang::NodeType;
 use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
@@ -18,6 +19,8 @@ pub struct NodeKeys {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verb: Option<String>,
     pub start: usize,
+    #[serde(default)]
+    pub start_col: usize,
 }
 
 impl NodeKeys {
@@ -27,8 +30,15 @@ impl NodeKeys {
             file: file.to_string(),
             verb: None,
             start: start,
+            start_col: 0,
         }
     }
+    // keyed at the start of a tree-sitter node
+    pub fn at_node(name: &str, file: &str, node: &tree_sitter::Node) -> Self {
+        let mut keys = Self::new(name, file, node.start_position().row);
+        keys.start_col = node.start_position().column;
+        keys
+    }
     pub fn is_empty(&self) -> bool {
         self == &Self::default()
     }
@@ -40,6 +50,7 @@ impl From<&NodeData> for NodeKeys {
             file: d.file.clone(),
             verb: d.meta.get("verb").map(|s| s.to_string()),
             start: d.start,
+            start_col: d.start_col,
         }
     }
 }
@@ -51,6 +62,14 @@ pub struct NodeData {
     pub body: String,
     pub start: usize,
     pub end: usize,
+    #[serde(default)]
+    pub start_col: usize,
+    #[serde(default)]
+    pub end_col: usize,
+    #[serde(default)]
+    pub start_byte: usize,
+    #[serde(default)]
+    pub end_byte: usize,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub docs: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -67,7 +86,7 @@ impl Serialize for NodeData {
     where
         S: Serializer,
     {
-        let mut named_fields_len = 5;
+        let mut named_fields_len = 9;
         if let Some(_) = &self.data_type {
             named_fields_len += 1;
         }
@@ -83,6 +102,10 @@ impl Serialize for NodeData {
         map.serialize_entry("body", &self.body)?;
         map.serialize_entry("start", &self.start)?;
         map.serialize_entry("end", &self.end)?;
+        map.serialize_entry("start_col", &self.start_col)?;
+        map.serialize_entry("end_col", &self.end_col)?;
+        map.serialize_entry("start_byte", &self.start_byte)?;
+        map.serialize_entry("end_byte", &self.end_byte)?;
         if let Some(data_type) = &self.data_type {
             map.serialize_entry("data_type", data_type)?;
         }
@@ -114,6 +137,15 @@ impl NodeData {
             ..Default::default()
         }
     }
+    // rows, columns and byte offsets of the tree-sitter node
+    pub fn set_range(&mut self, node: &tree_sitter::Node) {
+        self.start = node.start_position().row;
+        self.end = node.end_position().row;
+        self.start_col = node.start_position().column;
+        self.end_col = node.end_position().column;
+        self.start_byte = node.start_byte();
+        self.end_byte = node.end_byte();
+    }
     pub fn add_verb(&mut self, verb: &str) {
         self.meta
             .insert("verb".to_string(), verb.to_string().to_uppercase());
@@ -143,13 +175,52 @@ impl NodeData {
         self.meta
             .insert("function_type".to_string(), function_type.to_string());
     }
+    pub fn add_test_type(&mut self, test_type: &str) {
+        self.meta
+            .insert("test_type".to_string(), test_type.to_string());
+    }
     pub fn add_parent(&mut self, parent: &str) {
         self.meta.insert("parent".to_string(), parent.to_string());
     }
+    pub fn add_qualified_name(&mut self, qualified_name: &str) {
+        self.meta
+            .insert("qualified_name".to_string(), qualified_name.to_string());
+    }
+    pub fn qualified_name(&self) -> Option<&String> {
+        self.meta.get("qualified_name")
+    }
+    pub fn add_visibility(&mut self, visibility: &str) {
+        self.meta
+            .insert("visibility".to_string(), visibility.to_string());
+    }
+    pub fn add_default_value(&mut self, value: &str) {
+        self.meta
+            .insert("default_value".to_string(), value.to_string());
+    }
+    pub fn add_annotations(&mut self, annotations: &[String]) {
+        self.meta
+            .insert("annotations".to_string(), annotations.join("\n"));
+    }
     pub fn add_includes(&mut self, modules: &str) {
         self.meta
             .insert("includes".to_string(), modules.to_string());
     }
+    // comma separated, one capture per implemented interface
+    pub fn add_implements(&mut self, interface: &str) {
+        let implements = match self.meta.get("implements") {
+            Some(i) => format!("{},{}", i, interface),
+            None => interface.to_string(),
+        };
+        self.meta.insert("implements".to_string(), implements);
+    }
+    pub fn add_variants(&mut self, variants: &[String]) {
+        self.meta.insert("variants".to_string(), variants.join(","));
+    }
+    pub fn add_metrics(&mut self, metrics: &FunctionMetrics) {
+        for (key, value) in METRIC_KEYS.iter().zip(metrics.values()) {
+            self.meta.insert(key.to_string(), value.to_string());
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -164,9 +235,45 @@ pub struct Calls {
     pub target: NodeKeys,
     pub call_start: usize,
     pub call_end: usize,
+    pub call_start_col: usize,
+    pub call_end_col: usize,
+    pub call_start_byte: usize,
+    pub call_end_byte: usize,
     pub operand: Option<String>,
 }
 
+// where a call happens inside its caller
+#[derive(Clone, Debug, Serialize, Deserialize, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub struct CallSite {
+    pub start: usize,
+    pub end: usize,
+    pub start_col: usize,
+    pub end_col: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl Calls {
+    pub fn set_call_range(&mut self, node: &tree_sitter::Node) {
+        self.call_start = node.start_position().row;
+        self.call_end = node.end_position().row;
+        self.call_start_col = node.start_position().column;
+        self.call_end_col = node.end_position().column;
+        self.call_start_byte = node.start_byte();
+        self.call_end_byte = node.end_byte();
+    }
+    pub fn call_site(&self) -> CallSite {
+        CallSite {
+            start: self.call_start,
+            end: self.call_end,
+            start_col: self.call_start_col,
+            end_col: self.call_end_col,
+            start_byte: self.call_start_byte,
+            end_byte: self.call_end_byte,
+        }
+    }
+}
+
 impl FromStr for NodeType {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -184,6 +291,9 @@ impl FromStr for NodeType {
             "Datamodel" => Ok(NodeType::DataModel),
             "Feature" => Ok(NodeType::Feature),
             "Page" => Ok(NodeType::Page),
+            "Field" => Ok(NodeType::Field),
+            "Enum" => Ok(NodeType::Enum),
+            "Interface" => Ok(NodeType::Interface),
             _ => Err(anyhow::anyhow!("Invalid NodeType string: {}", s)),
         }
     }
@@ -209,6 +319,9 @@ impl ToString for NodeType {
             NodeType::Feature => "Feature".to_string(),
             NodeType::Page => "Page".to_string(),
             NodeType::Var => "Var".to_string(),
+            NodeType::Field => "Field".to_string(),
+            NodeType::Enum => "Enum".to_string(),
+            NodeType::Interface => "Interface".to_string(),
         }
     }
 }
@@ -0,0 +1,229 @@
+use crate::lang::graphs::{node_id, Direction, EdgeType, Graph, NodeId, NodeRef, NodeType};
+use crate::lang::{Node, NodeData};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use tracing::info;
+
+// what can be dead
+const CANDIDATE_TYPES: [NodeType; 3] = [NodeType::Function, NodeType::Class, NodeType::DataModel];
+
+// followed forward from the entry points
+const REACH_EDGES: [EdgeType; 4] = [
+    EdgeType::Handler,
+    EdgeType::Calls,
+    EdgeType::Renders,
+    EdgeType::Contains,
+];
+
+// which nodes count as entry points
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DeadCodeConfig {
+    pub endpoints: bool,
+    pub pages: bool,
+    pub tests: bool,
+    // functions with these names, like "main"
+    pub functions: Vec<String>,
+    // public functions and classes, for libraries used from outside the repo
+    pub exported: bool,
+}
+
+impl Default for DeadCodeConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: true,
+            pages: true,
+            tests: true,
+            functions: vec!["main".to_string()],
+            exported: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepoDeadCode {
+    pub repository: String,
+    pub entry_points: usize,
+    pub reachable: usize,
+    pub unreachable: Vec<NodeRef>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DeadCodeReport {
+    pub repositories: Vec<RepoDeadCode>,
+}
+
+impl DeadCodeReport {
+    pub fn unreachable(&self) -> impl Iterator<Item = &NodeRef> {
+        self.repositories.iter().flat_map(|r| r.unreachable.iter())
+    }
+
+    // one block per repository, one row per unreachable node
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        for repo in &self.repositories {
+            out.push_str(&format!(
+                "{}: {} entry points, {} reachable, {} unreachable\n",
+                repo.repository,
+                repo.entry_points,
+                repo.reachable,
+                repo.unreachable.len()
+            ));
+            let rows = repo
+                .unreachable
+                .iter()
+                .map(|n| {
+                    (
+                        n.node_type.to_string(),
+                        n.node_data.name.clone(),
+                        format!("{}:{}", n.node_data.file, n.node_data.start + 1),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let type_width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(4);
+            let name_width = rows.iter().map(|r| r.1.len()).max().unwrap_or(0).max(4);
+            out.push_str(&format!(
+                "  {:tw$}  {:nw$}  LOCATION\n",
+                "TYPE",
+                "NAME",
+                tw = type_width,
+                nw = name_width
+            ));
+            for (nt, name, location) in rows {
+                out.push_str(&format!(
+                    "  {:tw$}  {:nw$}  {}\n",
+                    nt,
+                    name,
+                    location,
+                    tw = type_width,
+                    nw = name_width
+                ));
+            }
+        }
+        out
+    }
+}
+
+// a best guess from the source, since visibility isn't parsed
+pub fn is_exported(nd: &NodeData) -> bool {
+    let body = nd.body.trim_start();
+    if body.starts_with("pub ") || body.starts_with("export ") || body.starts_with("public ") {
+        return true;
+    }
+    if nd.file.ends_with(".go") {
+        return nd.name.chars().next().is_some_and(|c| c.is_uppercase());
+    }
+    false
+}
+
+// Functions, Classes and DataModels that can't be reached from any entry point
+// through Handler, Calls, Renders or Contains edges. A class is reachable when
+// any of its methods is. Results are grouped by the Repository node whose root
+// holds the file.
+pub fn find_dead_code<G: Graph>(graph: &G, config: &DeadCodeConfig) -> DeadCodeReport {
+    let mut entries: Vec<Node> = Vec::new();
+    let mut entry_types = Vec::new();
+    if config.endpoints {
+        entry_types.push(NodeType::Endpoint);
+    }
+    if config.pages {
+        entry_types.push(NodeType::Page);
+    }
+    if config.tests {
+        entry_types.push(NodeType::Test);
+        entry_types.push(NodeType::E2eTest);
+    }
+    for nt in entry_types {
+        for nd in graph.find_nodes_by_type(nt.clone()) {
+            entries.push(Node::new(nt.clone(), nd));
+        }
+    }
+    let mut candidates: BTreeMap<NodeId, Node> = BTreeMap::new();
+    for nt in CANDIDATE_TYPES.iter() {
+        for nd in graph.find_nodes_by_type(nt.clone()) {
+            let is_entry = (*nt == NodeType::Function && config.functions.contains(&nd.name))
+                || (config.exported && is_exported(&nd));
+            if is_entry {
+                entries.push(Node::new(nt.clone(), nd.clone()));
+            }
+            candidates.insert(node_id(nt, &nd), Node::new(nt.clone(), nd));
+        }
+    }
+
+    let mut reached: BTreeSet<NodeId> = BTreeSet::new();
+    let mut queue = VecDeque::new();
+    for node in &entries {
+        if reached.insert(node.id()) {
+            queue.push_back(node.clone());
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let neighbors = graph.find_neighbors(
+            node.node_type.clone(),
+            &node.node_data,
+            &REACH_EDGES,
+            Direction::Outgoing,
+        );
+        for next in neighbors {
+            if reached.insert(next.id()) {
+                queue.push_back(next);
+            }
+        }
+    }
+    // a class lives as long as one of its methods does
+    for (class, method) in
+        graph.find_nodes_with_edge_type(NodeType::Class, NodeType::Function, EdgeType::Operand)
+    {
+        if reached.contains(&node_id(&NodeType::Function, &method)) {
+            reached.insert(node_id(&NodeType::Class, &class));
+        }
+    }
+
+    // repository roots, longest first so nested repos win
+    let mut repos = graph
+        .find_nodes_by_type(NodeType::Repository)
+        .into_iter()
+        .map(|r| {
+            let root = r.file.strip_suffix("main").unwrap_or(&r.file).to_string();
+            (root, r.name)
+        })
+        .collect::<Vec<_>>();
+    repos.sort_by_key(|(root, _)| std::cmp::Reverse(root.len()));
+    let repo_of = |file: &str| {
+        repos
+            .iter()
+            .find(|(root, _)| file.starts_with(root.as_str()))
+            .map(|(_, name)| name.clone())
+            .unwrap_or_default()
+    };
+
+    let mut by_repo: BTreeMap<String, RepoDeadCode> = BTreeMap::new();
+    for node in &entries {
+        let repository = repo_of(&node.node_data.file);
+        let entry = by_repo.entry(repository.clone()).or_default();
+        entry.repository = repository;
+        entry.entry_points += 1;
+    }
+    for (k, node) in &candidates {
+        let repository = repo_of(&node.node_data.file);
+        let entry = by_repo.entry(repository.clone()).or_default();
+        entry.repository = repository;
+        if reached.contains(k) {
+            entry.reachable += 1;
+        } else {
+            entry.unreachable.push(NodeRef::from(
+                (&node.node_data).into(),
+                node.node_type.clone(),
+            ));
+        }
+    }
+    let report = DeadCodeReport {
+        repositories: by_repo.into_values().collect(),
+    };
+    info!(
+        "{} unreachable of {} functions, classes and data models",
+        report.unreachable().count(),
+        candidates.len()
+    );
+    report
+}
@@ -0,0 +1,329 @@
+use crate::lang::graphs::{EdgeType, Graph, NodeRef, NodeType};
+use crate::lang::NodeData;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::{info, warn};
+
+// what a File can import
+const IMPORTED_TYPES: [NodeType; 6] = [
+    NodeType::Function,
+    NodeType::Class,
+    NodeType::DataModel,
+    NodeType::Interface,
+    NodeType::Enum,
+    NodeType::Var,
+];
+
+// the "layers" section of .ast.json
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LayerConfig {
+    // layer name => path prefixes, like "ui" => ["src/components"]
+    pub layers: BTreeMap<String, Vec<String>>,
+    pub rules: Vec<LayerRule>,
+}
+
+// files in `layer` must not import anything from the `must_not_import` layers
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LayerRule {
+    pub layer: String,
+    pub must_not_import: Vec<String>,
+}
+
+// one imported node, and the line that imports it
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportLink {
+    pub file: String,
+    pub target: NodeRef,
+    // 0-based, like node starts
+    pub line: Option<usize>,
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerViolation {
+    pub layer: String,
+    pub imports_layer: String,
+    pub import: ImportLink,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModuleStats {
+    pub module: String,
+    // how many modules import this one
+    pub fan_in: usize,
+    // how many modules this one imports
+    pub fan_out: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ModuleGraph {
+    pub modules: Vec<ModuleStats>,
+    // strongly connected components with more than one module
+    pub cycles: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DependencyReport {
+    pub files: ModuleGraph,
+    pub directories: ModuleGraph,
+    pub violations: Vec<LayerViolation>,
+}
+
+impl DependencyReport {
+    pub fn to_table(&self) -> String {
+        let mut out = String::new();
+        for (level, mg) in [("file", &self.files), ("directory", &self.directories)] {
+            out.push_str(&format!(
+                "{} {} modules, {} cycles\n",
+                mg.modules.len(),
+                level,
+                mg.cycles.len()
+            ));
+            for cycle in &mg.cycles {
+                out.push_str(&format!("  cycle: {}\n", cycle.join(" -> ")));
+            }
+            let width = mg.modules.iter().map(|m| m.module.len()).max().unwrap_or(0);
+            out.push_str(&format!("  {:w$}  IN  OUT\n", "MODULE", w = width.max(6)));
+            for m in &mg.modules {
+                out.push_str(&format!(
+                    "  {:w$}  {:>2}  {:>3}\n",
+                    m.module,
+                    m.fan_in,
+                    m.fan_out,
+                    w = width.max(6)
+                ));
+            }
+        }
+        out.push_str(&format!("{} layer violations\n", self.violations.len()));
+        for v in &self.violations {
+            let line = v
+                .import
+                .line
+                .map(|l| (l + 1).to_string())
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "  {} -> {}: {}:{} {}\n",
+                v.layer,
+                v.imports_layer,
+                v.import.file,
+                line,
+                v.import
+                    .code
+                    .as_deref()
+                    .unwrap_or(&v.import.target.node_data.name)
+            ));
+        }
+        out
+    }
+}
+
+// "src/ui" matches ".../src/ui/button.ts" but not ".../src/uikit.ts"
+fn in_prefix(file: &str, prefix: &str) -> bool {
+    let file = file.split('/').collect::<Vec<_>>();
+    let prefix = prefix
+        .trim_matches('/')
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect::<Vec<_>>();
+    !prefix.is_empty() && file.windows(prefix.len()).any(|w| w == prefix.as_slice())
+}
+
+fn directory(file: &str) -> String {
+    file.rsplit_once('/')
+        .map(|(dir, _)| dir.to_string())
+        .unwrap_or_default()
+}
+
+// the first line of the file's imports that mentions `name`
+fn import_line(imports: &[NodeData], name: &str) -> Option<(usize, String)> {
+    for imp in imports {
+        for (i, line) in imp.body.lines().enumerate() {
+            let mentions = line
+                .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .any(|w| w == name);
+            if mentions {
+                return Some((imp.start + i, line.trim().to_string()));
+            }
+        }
+    }
+    None
+}
+
+// Kosaraju: finish order on the graph, then components on the reversed graph
+fn strongly_connected(adj: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    let mut visited = BTreeSet::new();
+    let mut order = Vec::new();
+    for start in adj.keys() {
+        if !visited.insert(start.clone()) {
+            continue;
+        }
+        let mut stack = vec![(
+            start.clone(),
+            adj[start].iter().cloned().collect::<Vec<_>>(),
+        )];
+        while let Some((_, next)) = stack.last_mut() {
+            match next.pop() {
+                Some(n) => {
+                    if visited.insert(n.clone()) {
+                        let children = adj[&n].iter().cloned().collect();
+                        stack.push((n, children));
+                    }
+                }
+                None => order.push(stack.pop().unwrap().0),
+            }
+        }
+    }
+
+    let mut reversed: BTreeMap<&String, Vec<&String>> = BTreeMap::new();
+    for (from, tos) in adj {
+        for to in tos {
+            reversed.entry(to).or_default().push(from);
+        }
+    }
+    let mut assigned = BTreeSet::new();
+    let mut components = Vec::new();
+    for start in order.iter().rev() {
+        if !assigned.insert(start) {
+            continue;
+        }
+        let mut component = vec![start.clone()];
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for prev in reversed.get(node).into_iter().flatten() {
+                if assigned.insert(prev) {
+                    component.push((*prev).clone());
+                    stack.push(prev);
+                }
+            }
+        }
+        component.sort();
+        components.push(component);
+    }
+    components.sort();
+    components
+}
+
+fn module_graph(links: &BTreeSet<(String, String)>) -> ModuleGraph {
+    let mut adj: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for (from, to) in links {
+        adj.entry(from.clone()).or_default().insert(to.clone());
+        adj.entry(to.clone()).or_default();
+    }
+    let mut fan_in: BTreeMap<&String, usize> = BTreeMap::new();
+    for to in adj.values().flatten() {
+        *fan_in.entry(to).or_default() += 1;
+    }
+    let modules = adj
+        .iter()
+        .map(|(module, tos)| ModuleStats {
+            module: module.clone(),
+            fan_in: fan_in.get(module).copied().unwrap_or(0),
+            fan_out: tos.len(),
+        })
+        .collect();
+    let cycles = strongly_connected(&adj)
+        .into_iter()
+        .filter(|c| c.len() > 1)
+        .collect();
+    ModuleGraph { modules, cycles }
+}
+
+// File and directory dependency graphs from the Imports edges: fan-in and
+// fan-out of each module, import cycles, and the imports that break the
+// layering rules. Layer prefixes are matched as whole path segments, so they
+// work relative to any repo root.
+pub fn analyze_dependencies<G: Graph>(graph: &G, config: &LayerConfig) -> DependencyReport {
+    let mut imports: BTreeMap<String, Vec<NodeData>> = BTreeMap::new();
+    for imp in graph.find_nodes_by_type(NodeType::Import) {
+        imports.entry(imp.file.clone()).or_default().push(imp);
+    }
+
+    let mut links = Vec::new();
+    let mut file_links = BTreeSet::new();
+    let mut dir_links = BTreeSet::new();
+    for nt in IMPORTED_TYPES.iter() {
+        for (file, target) in
+            graph.find_nodes_with_edge_type(NodeType::File, nt.clone(), EdgeType::Imports)
+        {
+            if file.file == target.file {
+                continue;
+            }
+            file_links.insert((file.file.clone(), target.file.clone()));
+            let (from_dir, to_dir) = (directory(&file.file), directory(&target.file));
+            if from_dir != to_dir {
+                dir_links.insert((from_dir, to_dir));
+            }
+            let found = imports
+                .get(&file.file)
+                .and_then(|imps| import_line(imps, &target.name));
+            links.push(ImportLink {
+                file: file.file.clone(),
+                target: NodeRef::from((&target).into(), nt.clone()),
+                line: found.as_ref().map(|(l, _)| *l),
+                code: found.map(|(_, c)| c),
+            });
+        }
+    }
+
+    for rule in &config.rules {
+        for name in std::iter::once(&rule.layer).chain(rule.must_not_import.iter()) {
+            if !config.layers.contains_key(name) {
+                warn!("layer rule names an unknown layer {}", name);
+            }
+        }
+    }
+    let layers_of = |file: &str| {
+        config
+            .layers
+            .iter()
+            .filter(|(_, prefixes)| prefixes.iter().any(|p| in_prefix(file, p)))
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>()
+    };
+    let mut violations = Vec::new();
+    for link in &links {
+        let from_layers = layers_of(&link.file);
+        let to_layers = layers_of(&link.target.node_data.file);
+        for rule in config
+            .rules
+            .iter()
+            .filter(|r| from_layers.contains(&r.layer))
+        {
+            for denied in &rule.must_not_import {
+                if denied != &rule.layer && to_layers.contains(denied) {
+                    violations.push(LayerViolation {
+                        layer: rule.layer.clone(),
+                        imports_layer: denied.clone(),
+                        import: link.clone(),
+                    });
+                }
+            }
+        }
+    }
+    violations.sort_by(|a, b| {
+        (
+            &a.import.file,
+            a.import.line,
+            &a.import.target.node_data.name,
+        )
+            .cmp(&(
+                &b.import.file,
+                b.import.line,
+                &b.import.target.node_data.name,
+            ))
+    });
+
+    let report = DependencyReport {
+        files: module_graph(&file_links),
+        directories: module_graph(&dir_links),
+        violations,
+    };
+    info!(
+        "{} import cycles between files, {} between directories, {} layer violations",
+        report.files.cycles.len(),
+        report.directories.cycles.len(),
+        report.violations.len()
+    );
+    report
+}
@@ -0,0 +1,210 @@
+use crate::lang::graphs::{node_id, EdgeType, Graph, NodeId, NodeRef, NodeType};
+use crate::lang::{Edge, NodeData};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::info;
+
+// node types that can belong to a feature
+const MEMBER_TYPES: [NodeType; 11] = [
+    NodeType::Endpoint,
+    NodeType::Page,
+    NodeType::Request,
+    NodeType::Function,
+    NodeType::Class,
+    NodeType::Trait,
+    NodeType::Interface,
+    NodeType::Enum,
+    NodeType::DataModel,
+    NodeType::Test,
+    NodeType::E2eTest,
+];
+
+// edges that link neighbors (in both directions) for label propagation
+const LINK_EDGES: [EdgeType; 4] = [
+    EdgeType::Handler,
+    EdgeType::Calls,
+    EdgeType::Contains,
+    EdgeType::Renders,
+];
+
+// the "features" section of .ast.json
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FeatureConfig {
+    // label propagation rounds. a node further than this many edges from
+    // every endpoint and page is never labelled
+    pub max_rounds: usize,
+    // features with fewer members are dropped
+    pub min_size: usize,
+    // path segments that never name a feature, like "api" or "v1"
+    pub ignore_segments: Vec<String>,
+    // feature name => path prefixes, checked before the path segments
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for FeatureConfig {
+    fn default() -> Self {
+        Self {
+            max_rounds: 8,
+            min_size: 2,
+            ignore_segments: vec!["api".to_string()],
+            groups: BTreeMap::new(),
+        }
+    }
+}
+
+// Feature nodes with Includes edges, found by label propagation. Endpoints
+// and pages (and their handlers) are labelled with a feature named after
+// their route, then in each round every other node takes the label most of
+// its neighbors (and itself) had in the round before, ties going to the
+// first name. Nodes that no label reaches stay out of every feature.
+// Returns the number of features.
+pub fn detect_features<G: Graph>(
+    graph: &mut G,
+    root: &str,
+    config: &FeatureConfig,
+) -> Result<usize> {
+    let mut nodes: BTreeMap<NodeId, NodeData> = BTreeMap::new();
+    for nt in MEMBER_TYPES.iter() {
+        for nd in graph.find_nodes_by_type(nt.clone()) {
+            nodes.insert(node_id(nt, &nd), nd);
+        }
+    }
+    let member = |nr: &NodeRef| {
+        let k = nr.id();
+        nodes.contains_key(&k).then_some(k)
+    };
+
+    let mut neighbors: BTreeMap<NodeId, BTreeSet<NodeId>> = BTreeMap::new();
+    let mut handlers: Vec<(NodeId, NodeId)> = Vec::new();
+    for edge in graph.get_edges() {
+        if !LINK_EDGES.contains(&edge.edge) {
+            continue;
+        }
+        let (Some(s), Some(d)) = (member(&edge.source), member(&edge.target)) else {
+            continue;
+        };
+        if s == d {
+            continue;
+        }
+        if edge.edge == EdgeType::Handler {
+            handlers.push((s.clone(), d.clone()));
+        }
+        neighbors.entry(s.clone()).or_default().insert(d.clone());
+        neighbors.entry(d).or_default().insert(s);
+    }
+
+    // seed => feature name
+    let mut seeds: BTreeMap<NodeId, String> = BTreeMap::new();
+    for (k, nd) in nodes.iter() {
+        if k.0 == NodeType::Endpoint || k.0 == NodeType::Page {
+            if let Some(name) = feature_name(&nd.name, config) {
+                seeds.insert(k.clone(), name);
+            }
+        }
+    }
+    // a handler is the endpoint's own code, so it keeps the endpoint's label
+    // rather than being pulled into whatever it calls
+    for (endpoint, handler) in handlers {
+        if let Some(name) = seeds.get(&endpoint).cloned() {
+            seeds.entry(handler).or_insert(name);
+        }
+    }
+
+    let mut labels = seeds.clone();
+    for _ in 0..config.max_rounds {
+        let mut next = BTreeMap::new();
+        for (k, adjacent) in neighbors.iter() {
+            if let Some(name) = seeds.get(k) {
+                next.insert(k.clone(), name.clone());
+                continue;
+            }
+            let mut votes: BTreeMap<&String, usize> = BTreeMap::new();
+            for n in adjacent.iter().chain(std::iter::once(k)) {
+                if let Some(name) = labels.get(n) {
+                    *votes.entry(name).or_default() += 1;
+                }
+            }
+            // the map is sorted by name, so max_by_key would keep the last tie
+            let best =
+                votes.into_iter().fold(
+                    None,
+                    |best: Option<(&String, usize)>, (name, n)| match best {
+                        Some((_, m)) if m >= n => best,
+                        _ => Some((name, n)),
+                    },
+                );
+            if let Some((name, _)) = best {
+                next.insert(k.clone(), name.clone());
+            }
+        }
+        // seeds without any edges keep their label too
+        for (k, name) in seeds.iter() {
+            next.entry(k.clone()).or_insert_with(|| name.clone());
+        }
+        if next == labels {
+            break;
+        }
+        labels = next;
+    }
+
+    let mut members: BTreeMap<String, Vec<NodeId>> = BTreeMap::new();
+    for (k, name) in labels {
+        members.entry(name).or_default().push(k);
+    }
+
+    let mut count = 0;
+    for (name, keys) in members {
+        if keys.len() < config.min_size {
+            continue;
+        }
+        let mut feature = NodeData::name_file(&name, root);
+        let entry_points = seeds
+            .iter()
+            .filter(|(k, n)| **n == name && (k.0 == NodeType::Endpoint || k.0 == NodeType::Page))
+            .map(|(k, _)| k.1.clone())
+            .collect::<BTreeSet<_>>();
+        feature.meta.insert(
+            "entry_points".to_string(),
+            entry_points.into_iter().collect::<Vec<_>>().join(","),
+        );
+        graph.add_node(NodeType::Feature, feature.clone());
+        for k in keys {
+            if let Some(nd) = nodes.get(&k) {
+                graph.add_edge(Edge::includes(&feature, k.0.clone(), nd));
+            }
+        }
+        count += 1;
+    }
+    info!("detected {} features", count);
+    Ok(count)
+}
+
+// "/api/v1/person/:id" => "person"
+pub fn feature_name(path: &str, config: &FeatureConfig) -> Option<String> {
+    for (name, prefixes) in &config.groups {
+        if prefixes.iter().any(|p| path.starts_with(p.as_str())) {
+            return Some(name.clone());
+        }
+    }
+    path.split('/')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .filter(|s| !s.starts_with([':', '{', '<', '[', '$', '*']))
+        .filter(|s| !is_version(s))
+        .find(|s| {
+            !config
+                .ignore_segments
+                .iter()
+                .any(|i| i.eq_ignore_ascii_case(s))
+        })
+        .map(|s| s.to_lowercase())
+}
+
+fn is_version(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    matches!(chars.next(), Some('v') | Some('V'))
+        && !segment[1..].is_empty()
+        && chars.all(|c| c.is_ascii_digit())
+}
@@ -185,8 +185,9 @@ impl Graph for ArrayGraph {
         for group in eg {
             // group name (like TribesHandlers)
             if let Some(g) = group.meta.get("group") {
-                // function (handler) for the group
-                if let Some(gf) = self.find_nodes_by_name(NodeType::Function, &g).first() {
+                // function (handler) for the group, preferably next to the group itself
+                let gfs = self.find_nodes_by_name(NodeType::Function, g);
+                if let Some(gf) = gfs.iter().find(|f| f.file == group.file).or(gfs.first()) {
                     // each individual endpoint in the group code
                     for q in lang.lang().endpoint_finders() {
                         let endpoints_in_group = lang.get_query_opt::<Self>(
@@ -197,26 +198,30 @@ impl Graph for ArrayGraph {
                         )?;
                         // find the endpoint in the graph
                         for end in endpoints_in_group {
-                            if let Some(idx) =
-                                self.find_index_by_name(NodeType::Endpoint, &end.name)
-                            {
-                                let end_node = self.nodes.get_mut(idx).unwrap();
-                                if end_node.node_type == NodeType::Endpoint {
-                                    let new_endpoint =
-                                        format!("{}{}", group.name, end_node.node_data.name);
-                                    end_node.node_data.name = new_endpoint.clone();
-                                    if let Some(ei) =
-                                        self.find_edge_index_by_src(&end.name, &end.file)
-                                    {
-                                        let edge = self.edges.get_mut(ei).unwrap();
-                                        edge.source.node_data.name = new_endpoint;
-                                    } else {
-                                        println!("missing edge for endpoint: {:?}", end);
-                                    }
-                                }
+                            if let Some(idx) = self.nodes.iter().position(|n| {
+                                n.node_type == NodeType::Endpoint
+                                    && n.node_data.name == end.name
+                                    && n.node_data.file == end.file
+                            }) {
+                                self.prefix_endpoint(idx, &group.name);
                             }
                         }
                     }
+                    // or the group is the handler itself (actix services, rocket routes![])
+                    let handled: Vec<usize> = self
+                        .nodes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, n)| {
+                            n.node_type == NodeType::Endpoint
+                                && n.node_data.file == gf.file
+                                && n.node_data.meta.get("handler") == Some(g)
+                        })
+                        .map(|(i, _)| i)
+                        .collect();
+                    for idx in handled {
+                        self.prefix_endpoint(idx, &group.name);
+                    }
                 }
             }
         }
@@ -373,11 +378,14 @@ impl Graph for ArrayGraph {
         let mut unique_edges: HashSet<(String, String, String, String)> = HashSet::new();
         for (fc, ext_func, class_call) in funcs {
             if let Some(class_call) = &class_call {
-                self.add_edge(Edge::new(
-                    EdgeType::Calls,
-                    NodeRef::from(fc.source.clone(), NodeType::Function),
-                    NodeRef::from(class_call.into(), NodeType::Class),
-                ));
+                self.add_edge(
+                    Edge::new(
+                        EdgeType::Calls,
+                        NodeRef::from(fc.source.clone(), NodeType::Function),
+                        NodeRef::from(class_call.into(), NodeType::Class),
+                    )
+                    .with_call_site(fc.call_site()),
+                );
             }
             if fc.target.is_empty() {
                 continue;
@@ -403,11 +411,18 @@ impl Graph for ArrayGraph {
                     }
                 }
             } else {
-                if let Some(target_function) = self.find_node_by_name_in_file(
-                    NodeType::Function,
-                    &fc.target.name,
-                    &fc.source.file,
-                ) {
+                // the resolved target first, so same-named functions in one file stay apart
+                let target_function = self
+                    .find_node_at(NodeType::Function, &fc.target.file, fc.target.start as u32)
+                    .filter(|t| t.name == fc.target.name)
+                    .or_else(|| {
+                        self.find_node_by_name_in_file(
+                            NodeType::Function,
+                            &fc.target.name,
+                            &fc.source.file,
+                        )
+                    });
+                if let Some(target_function) = target_function {
                     let edge_key = (
                         fc.source.name.clone(),
                         fc.source.file.clone(),
@@ -421,7 +436,8 @@ impl Graph for ArrayGraph {
                             EdgeType::Calls,
                             NodeRef::from(fc.source.clone(), NodeType::Function),
                             NodeRef::from((&target_function).into(), NodeType::Function),
-                        );
+                        )
+                        .with_call_site(fc.call_site());
                         self.add_edge(edge);
                     }
                 } else {
@@ -696,11 +712,22 @@ impl Graph for ArrayGraph {
                 if edge.source.node_data.name == function.name
                     && edge.source.node_data.file == function.file
                 {
-                    if let Some(target_function) = self.find_node_by_name_in_file(
-                        edge.target.node_type.clone(),
-                        &edge.target.node_data.name,
-                        &edge.target.node_data.file,
-                    ) {
+                    let target = &edge.target.node_data;
+                    let target_function = self
+                        .find_node_at(
+                            edge.target.node_type.clone(),
+                            &target.file,
+                            target.start as u32,
+                        )
+                        .filter(|t| t.name == target.name)
+                        .or_else(|| {
+                            self.find_node_by_name_in_file(
+                                edge.target.node_type.clone(),
+                                &target.name,
+                                &target.file,
+                            )
+                        });
+                    if let Some(target_function) = target_function {
                         result.push(target_function);
                     }
                 }
@@ -724,19 +751,41 @@ impl Graph for ArrayGraph {
                     && edge.target.node_type == target_type
             })
             .filter_map(|edge| {
-                let source_nodes = self
-                    .find_nodes_by_name(edge.source.node_type.clone(), &edge.source.node_data.name);
-                let target_nodes = self
-                    .find_nodes_by_name(edge.target.node_type.clone(), &edge.target.node_data.name);
-
-                if let (Some(source), Some(target)) = (source_nodes.first(), target_nodes.first()) {
-                    Some((source.clone(), target.clone()))
-                } else {
-                    None
-                }
+                let source = self.find_edge_node(&edge.source)?;
+                let target = self.find_edge_node(&edge.target)?;
+                Some((source, target))
             })
             .collect::<Vec<(NodeData, NodeData)>>()
     }
+    fn get_edges(&self) -> Vec<Edge> {
+        // ends resolved like resolve_edge_node, through an index built once
+        let mut same_file: BTreeMap<(&NodeType, &str, &str), Vec<&NodeData>> = BTreeMap::new();
+        for n in &self.nodes {
+            let nd = &n.node_data;
+            same_file
+                .entry((&n.node_type, nd.name.as_str(), nd.file.as_str()))
+                .or_default()
+                .push(nd);
+        }
+        let resolve = |node_ref: &NodeRef| {
+            let keys = &node_ref.node_data;
+            let nodes =
+                same_file.get(&(&node_ref.node_type, keys.name.as_str(), keys.file.as_str()))?;
+            let nd = pick_node(nodes.iter().copied(), keys)?;
+            Some(NodeRef::from(nd.into(), node_ref.node_type.clone()))
+        };
+        self.edges
+            .iter()
+            .filter_map(|edge| {
+                Some(Edge {
+                    edge: edge.edge.clone(),
+                    source: resolve(&edge.source)?,
+                    target: resolve(&edge.target)?,
+                    call_site: edge.call_site.clone(),
+                })
+            })
+            .collect()
+    }
     fn count_edges_of_type(&self, edge_type: EdgeType) -> usize {
         self.edges
             .iter()
@@ -754,9 +803,82 @@ impl Graph for ArrayGraph {
             .map(|node| node.node_data.clone())
             .collect()
     }
+
+    fn find_neighbors(
+        &self,
+        node_type: NodeType,
+        node: &NodeData,
+        edge_types: &[EdgeType],
+        direction: Direction,
+    ) -> Vec<Node> {
+        let same_file = |r: &NodeRef| {
+            r.node_type == node_type
+                && r.node_data.name == node.name
+                && r.node_data.file == node.file
+        };
+        // edge ends resolve like resolve_edge_node, so they only point here
+        // if that picks us
+        let namesakes = self
+            .nodes
+            .iter()
+            .filter(|n| {
+                n.node_type == node_type
+                    && n.node_data.name == node.name
+                    && n.node_data.file == node.file
+            })
+            .map(|n| &n.node_data)
+            .collect::<Vec<_>>();
+        let is_node = |r: &NodeRef| {
+            same_file(r)
+                && pick_node(namesakes.iter().copied(), &r.node_data)
+                    .is_some_and(|nd| nd.start == node.start && nd.start_col == node.start_col)
+        };
+        let mut result: Vec<Node> = Vec::new();
+        for edge in &self.edges {
+            if !edge_types.is_empty() && !edge_types.contains(&edge.edge) {
+                continue;
+            }
+            let other = if direction.outgoing() && is_node(&edge.source) {
+                &edge.target
+            } else if direction.incoming() && is_node(&edge.target) {
+                &edge.source
+            } else {
+                continue;
+            };
+            if let Some(nd) = self.resolve_edge_node(other) {
+                let next = Node::new(other.node_type.clone(), nd);
+                if !result.contains(&next) {
+                    result.push(next);
+                }
+            }
+        }
+        result
+    }
 }
 
 impl ArrayGraph {
+    // the node an edge points at, or else one with its name and file
+    fn resolve_edge_node(&self, node_ref: &NodeRef) -> Option<NodeData> {
+        let keys = &node_ref.node_data;
+        let same_file = self
+            .nodes
+            .iter()
+            .filter(|n| {
+                n.node_type == node_ref.node_type
+                    && n.node_data.name == keys.name
+                    && n.node_data.file == keys.file
+            })
+            .map(|n| &n.node_data);
+        pick_node(same_file, keys).cloned()
+    }
+    // like resolve_edge_node, falling back to the first one with its name
+    fn find_edge_node(&self, node_ref: &NodeRef) -> Option<NodeData> {
+        self.resolve_edge_node(node_ref).or_else(|| {
+            self.find_nodes_by_name(node_ref.node_type.clone(), &node_ref.node_data.name)
+                .into_iter()
+                .next()
+        })
+    }
     pub fn file_data(&self, filename: &str) -> Option<NodeData> {
         self.nodes.iter().find_map(|n| {
             if n.node_type == NodeType::File && n.node_data.file == filename {
@@ -773,6 +895,22 @@ impl ArrayGraph {
             .position(|n| n.node_type == nt && n.node_data.name == name)
     }
 
+    fn prefix_endpoint(&mut self, idx: usize, prefix: &str) {
+        let end = self.nodes[idx].node_data.clone();
+        let new_endpoint = format!("{}{}", prefix, end.name);
+        self.nodes[idx].node_data.name = new_endpoint.clone();
+        for edge in self.edges.iter_mut() {
+            let src = &mut edge.source;
+            if src.node_type == NodeType::Endpoint
+                && src.node_data.name == end.name
+                && src.node_data.file == end.file
+                && src.node_data.start == end.start
+            {
+                src.node_data.name = new_endpoint.clone();
+            }
+        }
+    }
+
     pub fn find_edge_index_by_src(&self, name: &str, file: &str) -> Option<usize> {
         for (i, n) in self.edges.iter().enumerate() {
             if n.source.node_data.name == name && n.source.node_data.file == file {
@@ -801,3 +939,16 @@ impl Default for ArrayGraph {
         }
     }
 }
+
+// of the nodes sharing an edge end's type, name and file: the one at its row
+// and column, else the first on its row, else the first
+fn pick_node<'a>(
+    nodes: impl Iterator<Item = &'a NodeData> + Clone,
+    keys: &NodeKeys,
+) -> Option<&'a NodeData> {
+    nodes
+        .clone()
+        .find(|nd| nd.start == keys.start && nd.start_col == keys.start_col)
+        .or_else(|| nodes.clone().find(|nd| nd.start == keys.start))
+        .or_else(|| nodes.clone().next())
+}
@@ -1,6 +1,7 @@
+use super::keyed::{self, KeyedEdge, KeyedGraph};
 use super::{graph::Graph, *};
 use crate::lang::{Function, FunctionCall, Lang};
-use crate::utils::{create_node_key, create_node_key_from_ref, sanitize_string};
+use crate::utils::{create_node_key, create_node_key_from_ref, node_key_prefix, sanitize_string};
 use anyhow::Result;
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 
@@ -10,8 +11,14 @@ use serde::Serialize;
 pub struct BTreeMapGraph {
     pub nodes: BTreeMap<String, Node>,
     pub edges: BTreeSet<(String, String, EdgeType)>,
+    // first call site of each Calls edge, by edge key
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub call_sites: BTreeMap<String, CallSite>,
     #[serde(skip)]
     edge_keys: HashSet<String>,
+    // node keys by qualified name
+    #[serde(skip)]
+    qualified_names: BTreeMap<String, BTreeSet<String>>,
 }
 
 impl Graph for BTreeMapGraph {
@@ -19,7 +26,9 @@ impl Graph for BTreeMapGraph {
         BTreeMapGraph {
             nodes: BTreeMap::new(),
             edges: BTreeSet::new(),
+            call_sites: BTreeMap::new(),
             edge_keys: HashSet::new(),
+            qualified_names: BTreeMap::new(),
         }
     }
 
@@ -44,6 +53,13 @@ impl Graph for BTreeMapGraph {
     fn extend_graph(&mut self, other: Self) {
         self.nodes.extend(other.nodes);
         self.edges.extend(other.edges);
+        self.call_sites.extend(other.call_sites);
+        for (qualified_name, keys) in other.qualified_names {
+            self.qualified_names
+                .entry(qualified_name)
+                .or_default()
+                .extend(keys);
+        }
     }
 
     fn get_graph_size(&self) -> (u32, u32) {
@@ -52,14 +68,12 @@ impl Graph for BTreeMapGraph {
     fn add_edge(&mut self, edge: Edge) {
         let source_key = create_node_key_from_ref(&edge.source);
         let target_key = create_node_key_from_ref(&edge.target);
-        let edge_key = format!("{}-{}-{:?}", source_key, target_key, edge.edge);
-        self.edge_keys.insert(edge_key);
-        self.edges.insert((source_key, target_key, edge.edge));
+        self.insert_keyed_edge((source_key, target_key, edge.edge, edge.call_site));
     }
     fn add_node(&mut self, node_type: NodeType, node_data: NodeData) {
         let node = Node::new(node_type.clone(), node_data.clone());
         let node_key = create_node_key(&node);
-        self.nodes.insert(node_key.clone(), node);
+        self.insert_node(node_key, node);
     }
 
     fn get_graph_keys(&self) -> (HashSet<String>, HashSet<String>) {
@@ -83,6 +97,24 @@ impl Graph for BTreeMapGraph {
             .map(|(_, node)| node.node_data.clone())
             .collect()
     }
+    fn find_nodes_by_qualified_name(
+        &self,
+        node_type: NodeType,
+        qualified_name: &str,
+    ) -> Vec<NodeData> {
+        let Some(keys) = self.qualified_names.get(qualified_name) else {
+            return Vec::new();
+        };
+        // a key can be left behind when its node was replaced
+        keys.iter()
+            .filter_map(|k| self.nodes.get(k))
+            .filter(|n| {
+                n.node_type == node_type
+                    && n.node_data.qualified_name().map(|q| q.as_str()) == Some(qualified_name)
+            })
+            .map(|n| n.node_data.clone())
+            .collect()
+    }
     fn find_node_by_name_in_file(
         &self,
         node_type: NodeType,
@@ -130,7 +162,7 @@ impl Graph for BTreeMapGraph {
         for (key, node) in &self.nodes {
             if node.node_type == NodeType::Repository || final_filter.contains(&node.node_data.file)
             {
-                filtered.nodes.insert(key.clone(), node.clone());
+                filtered.insert_node(key.clone(), node.clone());
             }
         }
 
@@ -142,6 +174,10 @@ impl Graph for BTreeMapGraph {
                     filtered
                         .edges
                         .insert((src.clone(), dst.clone(), edge_type.clone()));
+                    if let Some(call_site) = self.call_site(src, dst, edge_type) {
+                        let edge_key = format!("{}-{}-{:?}", src, dst, edge_type);
+                        filtered.call_sites.insert(edge_key, call_site);
+                    }
                 }
             }
         }
@@ -246,7 +282,7 @@ impl Graph for BTreeMapGraph {
             let func_node = Node::new(NodeType::Function, node);
             let func_key = create_node_key(&func_node);
             if !self.nodes.contains_key(&func_key) {
-                self.nodes.insert(func_key.clone(), func_node);
+                self.insert_node(func_key.clone(), func_node);
             }
 
             let file_prefix = format!("{:?}-", NodeType::File).to_lowercase();
@@ -281,7 +317,7 @@ impl Graph for BTreeMapGraph {
                 let req_node = Node::new(NodeType::Request, r.clone());
                 let req_key = create_node_key(&req_node);
                 if !self.nodes.contains_key(&req_key) {
-                    self.nodes.insert(req_key, req_node);
+                    self.insert_node(req_key, req_node);
                 }
 
                 let edge = Edge::calls(NodeType::Function, &node_clone, NodeType::Request, &r);
@@ -361,183 +397,11 @@ impl Graph for BTreeMapGraph {
             self.add_edge(edge);
         }
     }
-    // Add calls only between function definitions not between function calls
     fn add_calls(&mut self, calls: (Vec<FunctionCall>, Vec<FunctionCall>, Vec<Edge>)) {
-        let (funcs, tests, int_tests) = calls;
-        let mut unique_edges: HashSet<(String, String, String, String)> = HashSet::new();
-
-        for (fc, ext_func, class_call) in funcs {
-            if let Some(class_call) = &class_call {
-                self.add_edge(Edge::new(
-                    EdgeType::Calls,
-                    NodeRef::from(fc.source.clone(), NodeType::Function),
-                    NodeRef::from(class_call.into(), NodeType::Class),
-                ));
-            }
-            if fc.target.is_empty() {
-                continue;
-            }
-
-            if let Some(ext_nd) = ext_func {
-                let edge_key = (
-                    fc.source.name.clone(),
-                    fc.source.file.clone(),
-                    ext_nd.name.clone(),
-                    ext_nd.file.clone(),
-                );
-
-                if !unique_edges.contains(&edge_key) {
-                    unique_edges.insert(edge_key);
-
-                    let ext_node = Node::new(NodeType::Function, ext_nd.clone());
-                    let ext_key = create_node_key(&ext_node);
-                    if !self.nodes.contains_key(&ext_key) {
-                        self.nodes.insert(ext_key, ext_node);
-                    }
-
-                    let edge = Edge::uses(fc.source, &ext_nd);
-                    self.add_edge(edge);
-                }
-            } else {
-                if let Some(target_function) = self.find_node_by_name_in_file(
-                    NodeType::Function,
-                    &fc.target.name,
-                    &fc.source.file,
-                ) {
-                    let edge_key = (
-                        fc.source.name.clone(),
-                        fc.source.file.clone(),
-                        target_function.name.clone(),
-                        target_function.file.clone(),
-                    );
-
-                    if !unique_edges.contains(&edge_key) {
-                        unique_edges.insert(edge_key);
-                        let edge = Edge::new(
-                            EdgeType::Calls,
-                            NodeRef::from(fc.source.clone(), NodeType::Function),
-                            NodeRef::from((&target_function).into(), NodeType::Function),
-                        );
-                        self.add_edge(edge);
-                    }
-                } else {
-                    let edge_key = (
-                        fc.source.name.clone(),
-                        fc.source.file.clone(),
-                        fc.target.name.clone(),
-                        fc.source.file.clone(),
-                    );
-
-                    if !unique_edges.contains(&edge_key) {
-                        unique_edges.insert(edge_key);
-                        self.add_edge(fc.into());
-                    }
-                }
-            }
-        }
-
-        for (tc, ext_func, _) in tests {
-            if let Some(ext_nd) = ext_func {
-                let edge_key = (
-                    tc.source.name.clone(),
-                    tc.source.file.clone(),
-                    ext_nd.name.clone(),
-                    ext_nd.file.clone(),
-                );
-
-                if !unique_edges.contains(&edge_key) {
-                    unique_edges.insert(edge_key);
-
-                    let edge = Edge::uses(tc.source, &ext_nd);
-                    self.add_edge(edge);
-                    let ext_node = Node::new(NodeType::Function, ext_nd.clone());
-                    let ext_key = create_node_key(&ext_node);
-                    if !self.nodes.contains_key(&ext_key) {
-                        self.nodes.insert(ext_key, ext_node);
-                    }
-                }
-            } else {
-                let edge_key = (
-                    tc.source.name.clone(),
-                    tc.source.file.clone(),
-                    tc.target.name.clone(),
-                    tc.source.file.clone(),
-                );
-
-                if !unique_edges.contains(&edge_key) {
-                    unique_edges.insert(edge_key);
-                    self.add_edge(Edge::new_test_call(tc));
-                }
-            }
-        }
-
-        for edge in int_tests {
-            self.add_edge(edge);
-        }
+        keyed::add_calls(self, calls)
     }
     fn process_endpoint_groups(&mut self, eg: Vec<NodeData>, lang: &Lang) -> Result<()> {
-        // Collect all updates we need to make
-        let mut updates = Vec::new();
-
-        for group in eg {
-            if let Some(g) = group.meta.get("group") {
-                if let Some(gf) = self.find_nodes_by_name(NodeType::Function, g).first() {
-                    for q in lang.lang().endpoint_finders() {
-                        let endpoints_in_group = lang.get_query_opt::<Self>(
-                            Some(q),
-                            &gf.body,
-                            &gf.file,
-                            NodeType::Endpoint,
-                        )?;
-
-                        for end in endpoints_in_group {
-                            let prefix =
-                                format!("{:?}-{}", NodeType::Endpoint, sanitize_string(&end.name))
-                                    .to_lowercase();
-                            if let Some((key, node)) = self
-                                .nodes
-                                .range(prefix.clone()..)
-                                .take_while(|(k, _)| k.starts_with(&prefix))
-                                .next()
-                            {
-                                let new_endpoint =
-                                    format!("{}{}", group.name, &node.node_data.name);
-                                let mut updated_node = node.clone();
-                                updated_node.node_data.name = new_endpoint.clone();
-
-                                // Collect edges that need to be updated
-                                let edges_to_update: Vec<_> = self
-                                    .edges
-                                    .iter()
-                                    .filter(|(src, _, _)| src == key)
-                                    .map(|(_, dst, edge)| (dst.clone(), edge.clone()))
-                                    .collect();
-
-                                updates.push((key.clone(), updated_node, edges_to_update));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Apply all updates at once
-        for (old_key, updated_node, edges) in updates {
-            let new_key = create_node_key(&updated_node);
-
-            // Update node
-            self.nodes.remove(&old_key);
-            self.nodes.insert(new_key.clone(), updated_node);
-
-            // Update edges
-            for (dst, edge) in edges {
-                self.edges
-                    .remove(&(old_key.clone(), dst.clone(), edge.clone()));
-                self.edges.insert((new_key.clone(), dst, edge));
-            }
-        }
-
-        Ok(())
+        keyed::process_endpoint_groups(self, eg, lang)
     }
     fn class_includes(&mut self) {
         let class_nodes: Vec<_> = self
@@ -639,26 +503,14 @@ impl Graph for BTreeMapGraph {
             .collect();
 
         for key in nodes_to_remove {
-            self.nodes.remove(&key);
+            self.remove_node(&key);
             self.edges
                 .retain(|(src, dst, _)| src != &key && dst != &key);
         }
     }
 
     fn prefix_paths(&mut self, root: &str) {
-        let nodes_to_update: Vec<_> = self
-            .nodes
-            .iter()
-            .map(|(k, node)| {
-                let mut new_node = node.clone();
-                new_node.add_root(root);
-                (k.clone(), new_node)
-            })
-            .collect();
-
-        for (key, node) in nodes_to_update {
-            self.nodes.insert(key, node);
-        }
+        keyed::prefix_paths(self, root)
     }
 
     fn find_nodes_by_name_contains(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
@@ -785,6 +637,72 @@ impl Graph for BTreeMapGraph {
 
         result
     }
+    fn find_neighbors(
+        &self,
+        node_type: NodeType,
+        node: &NodeData,
+        edge_types: &[EdgeType],
+        direction: Direction,
+    ) -> Vec<Node> {
+        let key = create_node_key(&Node::new(node_type, node.clone()));
+        let Some(prefix) = node_key_prefix(&key) else {
+            return Vec::new();
+        };
+        // edge ends without a node of their own resolve to the first node
+        // with the same type, name and file, so they only point here if that's us
+        let owns_prefix = self.edge_node_key(&prefix) == Some(&key);
+        let is_node = |k: &String| {
+            *k == key || (owns_prefix && k.starts_with(&prefix) && !self.nodes.contains_key(k))
+        };
+        let wanted = |e: &EdgeType| edge_types.is_empty() || edge_types.contains(e);
+        let mut others = Vec::new();
+        if direction.outgoing() {
+            let from = (prefix.clone(), String::new(), EdgeType::Calls);
+            others.extend(
+                self.edges
+                    .range(from..)
+                    .take_while(|(src, _, _)| src.starts_with(&prefix))
+                    .filter(|(src, _, edge)| wanted(edge) && is_node(src))
+                    .map(|(_, dst, _)| dst),
+            );
+        }
+        if direction.incoming() {
+            others.extend(
+                self.edges
+                    .iter()
+                    .filter(|(_, dst, edge)| wanted(edge) && is_node(dst))
+                    .map(|(src, _, _)| src),
+            );
+        }
+        let mut seen = BTreeSet::new();
+        others
+            .into_iter()
+            .filter_map(|k| self.edge_node_key(k))
+            .filter(|k| seen.insert(*k))
+            .map(|k| self.nodes[k].clone())
+            .collect()
+    }
+    fn get_edges(&self) -> Vec<Edge> {
+        self.edges
+            .iter()
+            .filter_map(|(src_key, dst_key, edge_type)| {
+                let src_node = self.nodes.get(self.edge_node_key(src_key)?)?;
+                let dst_node = self.nodes.get(self.edge_node_key(dst_key)?)?;
+                Some(Edge {
+                    edge: edge_type.clone(),
+                    source: NodeRef {
+                        node_type: src_node.node_type.clone(),
+                        node_data: NodeKeys::from(&src_node.node_data),
+                    },
+                    target: NodeRef {
+                        node_type: dst_node.node_type.clone(),
+                        node_data: NodeKeys::from(&dst_node.node_data),
+                    },
+                    call_site: self.call_site(src_key, dst_key, edge_type),
+                })
+            })
+            .collect()
+    }
     fn count_edges_of_type(&self, edge_type: EdgeType) -> usize {
         self.edges
             .iter()
@@ -797,6 +715,41 @@ impl Graph for BTreeMapGraph {
 }
 
 impl BTreeMapGraph {
+    fn insert_node(&mut self, key: String, node: Node) {
+        if let Some(q) = node.node_data.qualified_name() {
+            self.qualified_names
+                .entry(q.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        self.nodes.insert(key, node);
+    }
+
+    fn remove_node(&mut self, key: &str) {
+        let Some(node) = self.nodes.remove(key) else {
+            return;
+        };
+        if let Some(q) = node.node_data.qualified_name() {
+            if let Some(keys) = self.qualified_names.get_mut(q) {
+                keys.remove(key);
+            }
+        }
+    }
+
+    // the node an edge end points at: the node with that key, or else the
+    // first one with the same type, name and file
+    fn edge_node_key<'a>(&'a self, key: &'a String) -> Option<&'a String> {
+        if let Some((k, _)) = self.nodes.get_key_value(key) {
+            return Some(k);
+        }
+        let prefix = node_key_prefix(key)?;
+        self.nodes
+            .range(prefix.clone()..)
+            .next()
+            .map(|(k, _)| k)
+            .filter(|k| k.starts_with(&prefix))
+    }
+
     pub fn to_array_graph_edges(&self) -> Vec<Edge> {
         let mut formatted_edges = Vec::with_capacity(self.edges.len());
 
@@ -814,6 +767,7 @@ impl BTreeMapGraph {
                         node_type: dst_node.node_type.clone(),
                         node_data: NodeKeys::from(&dst_node.node_data),
                     },
+                    call_site: self.call_site(src_key, dst_key, edge_type),
                 };
 
                 formatted_edges.push(edge);
@@ -822,13 +776,90 @@ impl BTreeMapGraph {
 
         formatted_edges
     }
+    pub fn call_site(
+        &self,
+        src_key: &str,
+        dst_key: &str,
+        edge_type: &EdgeType,
+    ) -> Option<CallSite> {
+        let edge_key = format!("{}-{}-{:?}", src_key, dst_key, edge_type);
+        self.call_sites.get(&edge_key).cloned()
+    }
+}
+impl KeyedGraph for BTreeMapGraph {
+    fn contains_key(&self, key: &str) -> bool {
+        self.nodes.contains_key(key)
+    }
+
+    fn get_keyed(&self, key: &str) -> Option<Node> {
+        self.nodes.get(key).cloned()
+    }
+
+    fn insert_keyed(&mut self, key: String, node: Node) {
+        self.insert_node(key, node)
+    }
+
+    fn remove_keyed(&mut self, key: &str) {
+        self.remove_node(key)
+    }
+
+    fn keyed_with_prefix(&self, prefix: &str) -> Vec<(String, Node)> {
+        self.nodes
+            .range(prefix.to_string()..)
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .map(|(k, n)| (k.clone(), n.clone()))
+            .collect()
+    }
+
+    fn keyed_edges_from(&self, src: &str) -> Vec<KeyedEdge> {
+        self.edges
+            .iter()
+            .filter(|(s, _, _)| s == src)
+            .map(|(s, d, e)| (s.clone(), d.clone(), e.clone(), self.call_site(s, d, e)))
+            .collect()
+    }
+
+    fn insert_keyed_edge(&mut self, (src, dst, edge_type, call_site): KeyedEdge) {
+        let edge_key = format!("{}-{}-{:?}", src, dst, edge_type);
+        if let Some(call_site) = call_site {
+            self.call_sites.entry(edge_key.clone()).or_insert(call_site);
+        }
+        self.edge_keys.insert(edge_key);
+        self.edges.insert((src, dst, edge_type));
+    }
+
+    fn remove_keyed_edge(&mut self, src: &str, dst: &str, edge_type: &EdgeType) {
+        let edge_key = format!("{}-{}-{:?}", src, dst, edge_type);
+        self.call_sites.remove(&edge_key);
+        self.edge_keys.remove(&edge_key);
+        self.edges
+            .remove(&(src.to_string(), dst.to_string(), edge_type.clone()));
+    }
+
+    fn drain_keyed(&mut self) -> (Vec<(String, Node)>, Vec<KeyedEdge>) {
+        let edges = std::mem::take(&mut self.edges)
+            .into_iter()
+            .map(|(s, d, e)| {
+                let call_site = self.call_site(&s, &d, &e);
+                (s, d, e, call_site)
+            })
+            .collect();
+        self.call_sites.clear();
+        self.edge_keys.clear();
+        self.qualified_names.clear();
+        let nodes = std::mem::take(&mut self.nodes).into_iter().collect();
+        (nodes, edges)
+    }
 }
+
 impl Default for BTreeMapGraph {
     fn default() -> Self {
         BTreeMapGraph {
             nodes: BTreeMap::new(),
             edges: BTreeSet::new(),
+            call_sites: BTreeMap::new(),
             edge_keys: HashSet::new(),
+            qualified_names: BTreeMap::new(),
         }
     }
 }
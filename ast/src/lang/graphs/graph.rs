@@ -1,12 +1,12 @@
-use crate::lang::{Edge, Lang, NodeType};
+use crate::lang::{Edge, Lang, Node, NodeType};
 use crate::lang::{Function, FunctionCall};
 use anyhow::Result;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::fmt::Debug;
 
-use super::{EdgeType, NodeData, NodeKeys};
+use super::{Direction, EdgeType, NodeData, NodeId, NodeKeys};
 
-pub trait Graph: Default + Debug {
+pub trait Graph: Default + Debug + Sync {
     fn new() -> Self
     where
         Self: Sized,
@@ -19,6 +19,18 @@ pub trait Graph: Default + Debug {
     {
         Self::default()
     }
+    // like new, for graphs whose store can fail to open
+    fn try_new() -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new())
+    }
+    // the sync methods can't return errors, so a graph whose writes can fail
+    // keeps the first one for the builder to return here
+    fn check_writes(&self) -> Result<()> {
+        Ok(())
+    }
     fn analysis(&self);
     fn create_filtered_graph(&self, final_filter: &[String]) -> Self
     where
@@ -84,6 +96,18 @@ pub trait Graph: Default + Debug {
         edge_type: EdgeType,
     ) -> Vec<(NodeData, NodeData)>;
     fn count_edges_of_type(&self, edge_type: EdgeType) -> usize;
+    // every edge in one pass, rather than a query per pair of node types.
+    // ends are the nodes they resolve to, and edges with an end that isn't
+    // a node are left out
+    fn get_edges(&self) -> Vec<Edge>;
+    // nodes one edge away from `node`, following any edge when `edge_types` is empty
+    fn find_neighbors(
+        &self,
+        node_type: NodeType,
+        node: &NodeData,
+        edge_types: &[EdgeType],
+        direction: Direction,
+    ) -> Vec<Node>;
 
     //Default implementations
     fn find_nodes_by_name_contains(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
@@ -128,9 +152,147 @@ pub trait Graph: Default + Debug {
             .find(|node| node.file == file && node.start as u32 <= row && node.end as u32 >= row)
     }
 
+    fn find_nodes_by_qualified_name(
+        &self,
+        node_type: NodeType,
+        qualified_name: &str,
+    ) -> Vec<NodeData> {
+        self.find_nodes_by_type(node_type)
+            .into_iter()
+            .filter(|node| node.qualified_name().map(|q| q.as_str()) == Some(qualified_name))
+            .collect()
+    }
+
     fn find_node_at(&self, node_type: NodeType, file: &str, line: u32) -> Option<NodeData> {
         self.find_nodes_by_type(node_type)
             .into_iter()
             .find(|node| node.file == file && node.start == line as usize)
     }
+
+    // every node within `max_depth` edges of `start` (which is left out), nearest first
+    fn bfs(
+        &self,
+        start: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+    ) -> Vec<(Node, usize)> {
+        let mut seen = BTreeSet::from([start.id()]);
+        let mut queue = VecDeque::from([(start.clone(), 0)]);
+        let mut result = Vec::new();
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+            let neighbors = self.find_neighbors(
+                node.node_type.clone(),
+                &node.node_data,
+                edge_types,
+                direction,
+            );
+            for next in neighbors {
+                if seen.insert(next.id()) {
+                    result.push((next.clone(), depth + 1));
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        result
+    }
+
+    // same as bfs, but each branch is followed to the end before the next one
+    fn dfs(
+        &self,
+        start: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+    ) -> Vec<(Node, usize)> {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![(start.clone(), 0)];
+        let mut result = Vec::new();
+        while let Some((node, depth)) = stack.pop() {
+            // marked when popped rather than pushed, so nodes come out in depth-first order
+            if !seen.insert(node.id()) {
+                continue;
+            }
+            if depth > 0 {
+                result.push((node.clone(), depth));
+            }
+            if depth >= max_depth {
+                continue;
+            }
+            let neighbors = self.find_neighbors(
+                node.node_type.clone(),
+                &node.node_data,
+                edge_types,
+                direction,
+            );
+            // reversed so the first neighbor is visited first
+            for next in neighbors.into_iter().rev() {
+                if !seen.contains(&next.id()) {
+                    stack.push((next, depth + 1));
+                }
+            }
+        }
+        result
+    }
+
+    // the fewest edges from `from` to `to`, both ends included
+    fn shortest_path(
+        &self,
+        from: &Node,
+        to: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+    ) -> Option<Vec<Node>> {
+        let target = to.id();
+        if from.id() == target {
+            return Some(vec![from.clone()]);
+        }
+        let mut parents: BTreeMap<NodeId, Node> = BTreeMap::new();
+        let mut seen = BTreeSet::from([from.id()]);
+        let mut queue = VecDeque::from([(from.clone(), 0)]);
+        while let Some((node, depth)) = queue.pop_front() {
+            if depth >= max_depth {
+                continue;
+            }
+            let neighbors = self.find_neighbors(
+                node.node_type.clone(),
+                &node.node_data,
+                edge_types,
+                direction,
+            );
+            for next in neighbors {
+                let id = next.id();
+                if !seen.insert(id.clone()) {
+                    continue;
+                }
+                parents.insert(id.clone(), node.clone());
+                if id == target {
+                    let mut path = vec![next];
+                    let mut current = id;
+                    while let Some(parent) = parents.get(&current) {
+                        current = parent.id();
+                        path.push(parent.clone());
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back((next, depth + 1));
+            }
+        }
+        None
+    }
+
+    // every function `function` ends up calling, directly or not
+    fn transitive_calls(&self, function: &NodeData) -> Vec<NodeData> {
+        let start = Node::new(NodeType::Function, function.clone());
+        self.bfs(&start, &[EdgeType::Calls], Direction::Outgoing, usize::MAX)
+            .into_iter()
+            .filter(|(n, _)| n.node_type == NodeType::Function)
+            .map(|(n, _)| n.node_data)
+            .collect()
+    }
 }
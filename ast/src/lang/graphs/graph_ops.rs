@@ -1,14 +1,28 @@
+use crate::builder::root_less_tmp;
+use crate::gat::FileChange;
 use crate::lang::graphs::neo4j_graph::Neo4jGraph;
-use crate::lang::graphs::{BTreeMapGraph, Edge, NodeKeys, NodeRef};
+use crate::lang::graphs::{BTreeMapGraph, Edge, NodeKeys, NodeRef, NodeType};
 use crate::lang::neo4j_utils::TransactionManager;
-use crate::repo::{check_revs_files, Repo};
+use crate::repo::{check_revs_changes, Repo};
 use anyhow::Result;
+use std::collections::HashSet;
 use tracing::info;
 
 pub struct GraphOps {
     pub graph: Neo4jGraph,
 }
 
+// what an incremental update changed
+#[derive(Debug, Default, Clone)]
+pub struct UpdateSummary {
+    pub files: Vec<String>,
+    pub files_deleted: Vec<String>,
+    pub files_renamed: Vec<(String, String)>,
+    pub nodes_added: u32,
+    pub nodes_removed: u32,
+    pub edges_relinked: u32,
+}
+
 impl GraphOps {
     pub fn new() -> Self {
         Self {
@@ -34,73 +48,124 @@ impl GraphOps {
         current_hash: &str,
         stored_hash: &str,
     ) -> Result<(u32, u32)> {
+        self.update_incremental_summary(repo_url, repo_path, current_hash, stored_hash)
+            .await?;
+        self.graph.get_graph_size().await
+    }
+
+    pub async fn update_incremental_summary(
+        &mut self,
+        repo_url: &str,
+        repo_path: &str,
+        current_hash: &str,
+        stored_hash: &str,
+    ) -> Result<UpdateSummary> {
+        let mut summary = UpdateSummary::default();
         let revs = vec![stored_hash.to_string(), current_hash.to_string()];
-        if let Some(modified_files) = check_revs_files(repo_path, revs.clone()) {
-            info!(
-                "Processing {} changed files between commits",
-                modified_files.len()
-            );
+        if let Some(changes) = check_revs_changes(repo_path, revs.clone()) {
+            info!("Processing {} changed files between commits", changes.len());
+
+            // nodes in neo4j have their file prefixed with the repo root
+            let root = root_less_tmp(repo_path);
+            let full_path = |file: &str| format!("{}/{}", root, file);
+
+            let mut reparse_files = Vec::new();
+            for change in &changes {
+                match change {
+                    FileChange::Added(path) | FileChange::Modified(path) => {
+                        reparse_files.push(path.clone());
+                    }
+                    FileChange::Deleted(path) => {
+                        let removed = self.graph.remove_nodes_by_file(&full_path(path)).await?;
+                        info!("Deleted {}: removed {} nodes", path, removed);
+                        summary.nodes_removed += removed;
+                        summary.files_deleted.push(path.clone());
+                    }
+                    FileChange::Renamed { from, to } => {
+                        // move the nodes over so incoming edges survive the re-parse below
+                        let renamed = self
+                            .graph
+                            .rename_file(&full_path(from), &full_path(to))
+                            .await?;
+                        info!("Renamed {} -> {}: moved {} nodes", from, to, renamed);
+                        summary.files_renamed.push((from.clone(), to.clone()));
+                        reparse_files.push(to.clone());
+                    }
+                }
+            }
 
-            if !modified_files.is_empty() {
+            if !reparse_files.is_empty() {
                 let mut all_incoming_edges = Vec::new();
-                for file in &modified_files {
+                for file in &reparse_files {
+                    let file = full_path(file);
                     // Collect incoming edges before removing nodes
-                    let incoming = self.graph.get_incoming_edges_for_file(file).await?;
+                    let incoming = self.graph.get_incoming_edges_for_file(&file).await?;
                     all_incoming_edges.extend(incoming);
-                    self.graph.remove_nodes_by_file(file).await?;
+                    summary.nodes_removed += self.graph.remove_nodes_by_file(&file).await?;
                 }
 
+                // the partial graphs also hold the repository, language and
+                // directories around the files, which aren't new
+                let reparsed = reparse_files
+                    .iter()
+                    .map(|f| full_path(f))
+                    .collect::<HashSet<_>>();
                 let file_repos = Repo::new_multi_detect(
                     repo_path,
                     Some(repo_url.to_string()),
-                    modified_files.clone(),
+                    reparse_files.clone(),
                     Vec::new(),
                 )
                 .await?;
 
                 for repo in &file_repos.0 {
-                    // Build in-memory graph for this file
+                    // Build in-memory graph for the changed files
                     let file_graph = repo.build_graph_inner::<BTreeMapGraph>().await?;
-                    // Upload to Neo4j
-                    self.upload_btreemap_to_neo4j(&file_graph).await?;
+                    summary.nodes_added += file_graph
+                        .nodes
+                        .values()
+                        .filter(|n| reparsed.contains(&n.node_data.file))
+                        .count() as u32;
+                    // Upsert into Neo4j, leaving the rest of the repo in place
+                    self.upsert_btreemap_to_neo4j(&file_graph).await?;
+                }
 
-                    // Re-add incoming edges if both nodes exist
-                    for (edge, _target_data) in &all_incoming_edges {
-                        let source_exists = self
-                            .graph
-                            .find_nodes_by_name(
-                                edge.source.node_type.clone(),
-                                &edge.source.node_data.name,
-                            )
-                            .await
-                            .iter()
-                            .any(|n| n.file == edge.source.node_data.file);
-                        let target_exists = self
-                            .graph
-                            .find_nodes_by_name(
-                                edge.target.node_type.clone(),
-                                &edge.target.node_data.name,
-                            )
-                            .await
-                            .iter()
-                            .any(|n| n.file == edge.target.node_data.file);
-                        if source_exists && target_exists {
-                            self.graph.add_edge(edge.clone()).await?;
-                        }
+                // Re-add incoming edges if both nodes exist
+                for (edge, _target_data) in &all_incoming_edges {
+                    let source_exists = self
+                        .graph
+                        .find_nodes_by_name(
+                            edge.source.node_type.clone(),
+                            &edge.source.node_data.name,
+                        )
+                        .await
+                        .iter()
+                        .any(|n| n.file == edge.source.node_data.file);
+                    let target_exists = self
+                        .graph
+                        .find_nodes_by_name(
+                            edge.target.node_type.clone(),
+                            &edge.target.node_data.name,
+                        )
+                        .await
+                        .iter()
+                        .any(|n| n.file == edge.target.node_data.file);
+                    if source_exists && target_exists {
+                        self.graph.add_edge(edge.clone()).await?;
+                        summary.edges_relinked += 1;
                     }
-
-                    let (nodes_after, edges_after) = self.graph.get_graph_size().await?;
-                    info!(
-                        "Updated files: added {} nodes and {} edges",
-                        nodes_after, edges_after
-                    );
                 }
             }
+            info!(
+                "Updated files: added {} nodes, removed {} nodes, relinked {} edges",
+                summary.nodes_added, summary.nodes_removed, summary.edges_relinked
+            );
+            summary.files = reparse_files;
         }
         self.graph
             .update_repository_hash(repo_url, current_hash)
             .await?;
-        self.graph.get_graph_size().await
+        Ok(summary)
     }
 
     pub async fn update_full(
@@ -125,12 +190,23 @@ impl GraphOps {
             .await?;
         self.graph.get_graph_size().await
     }
+    // replaces the subgraph of each repository in `btree_graph`, other repositories are left intact
     pub async fn upload_btreemap_to_neo4j(
         &mut self,
         btree_graph: &BTreeMapGraph,
     ) -> anyhow::Result<(u32, u32)> {
-        self.graph.clear().await?;
+        for prefix in repository_prefixes(btree_graph) {
+            let removed = self.graph.remove_nodes_by_file_prefix(&prefix).await?;
+            info!("Removed {} existing nodes under {}", removed, prefix);
+        }
+        self.upsert_btreemap_to_neo4j(btree_graph).await
+    }
 
+    // merges nodes by their key, without deleting anything
+    pub async fn upsert_btreemap_to_neo4j(
+        &mut self,
+        btree_graph: &BTreeMapGraph,
+    ) -> anyhow::Result<(u32, u32)> {
         let connection = self.graph.ensure_connected().await?;
 
         let mut txn_manager = TransactionManager::new(&connection);
@@ -152,6 +228,7 @@ impl GraphOps {
                         node_type: dst_node.node_type.clone(),
                         node_data: NodeKeys::from(&dst_node.node_data),
                     },
+                    call_site: btree_graph.call_site(src_key, dst_key, edge_type),
                 };
                 txn_manager.add_edge(&edge);
             }
@@ -163,3 +240,15 @@ impl GraphOps {
         Ok((nodes, edges))
     }
 }
+
+// every node of a repo has its file prefixed with the repo root, same as the "main" Repository node
+fn repository_prefixes(btree_graph: &BTreeMapGraph) -> Vec<String> {
+    btree_graph
+        .nodes
+        .values()
+        .filter(|node| node.node_type == NodeType::Repository)
+        .filter_map(|node| node.node_data.file.strip_suffix("main"))
+        .filter(|prefix| !prefix.is_empty() && prefix != &"/")
+        .map(|prefix| prefix.to_string())
+        .collect()
+}
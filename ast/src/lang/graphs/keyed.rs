@@ -0,0 +1,255 @@
+use super::{graph::Graph, *};
+use crate::lang::{FunctionCall, Lang};
+use crate::utils::{create_node_key, rooted_node_key, sanitize_string};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+
+pub type KeyedEdge = (String, String, EdgeType, Option<CallSite>);
+
+// A graph that stores nodes under create_node_key and edges under the keys of
+// their ends, like BTreeMapGraph and SledGraph. The passes that have to re-key
+// nodes are written once below against these primitives.
+pub trait KeyedGraph: Graph {
+    fn contains_key(&self, key: &str) -> bool;
+    fn get_keyed(&self, key: &str) -> Option<Node>;
+    fn insert_keyed(&mut self, key: String, node: Node);
+    fn remove_keyed(&mut self, key: &str);
+    // nodes whose key starts with `prefix`, in key order
+    fn keyed_with_prefix(&self, prefix: &str) -> Vec<(String, Node)>;
+    fn keyed_edges_from(&self, src: &str) -> Vec<KeyedEdge>;
+    // an edge keeps the first call site it was added with
+    fn insert_keyed_edge(&mut self, edge: KeyedEdge);
+    fn remove_keyed_edge(&mut self, src: &str, dst: &str, edge_type: &EdgeType);
+    // empties the graph, handing back every node and edge
+    fn drain_keyed(&mut self) -> (Vec<(String, Node)>, Vec<KeyedEdge>);
+}
+
+// Add calls only between function definitions not between function calls
+pub fn add_calls<G: KeyedGraph>(
+    graph: &mut G,
+    calls: (Vec<FunctionCall>, Vec<FunctionCall>, Vec<Edge>),
+) {
+    let (funcs, tests, int_tests) = calls;
+    let mut unique_edges: HashSet<(String, String, String, String)> = HashSet::new();
+
+    for (fc, ext_func, class_call) in funcs {
+        if let Some(class_call) = &class_call {
+            graph.add_edge(
+                Edge::new(
+                    EdgeType::Calls,
+                    NodeRef::from(fc.source.clone(), NodeType::Function),
+                    NodeRef::from(class_call.into(), NodeType::Class),
+                )
+                .with_call_site(fc.call_site()),
+            );
+        }
+        if fc.target.is_empty() {
+            continue;
+        }
+
+        if let Some(ext_nd) = ext_func {
+            let edge_key = (
+                fc.source.name.clone(),
+                fc.source.file.clone(),
+                ext_nd.name.clone(),
+                ext_nd.file.clone(),
+            );
+
+            if !unique_edges.contains(&edge_key) {
+                unique_edges.insert(edge_key);
+
+                insert_if_missing(graph, Node::new(NodeType::Function, ext_nd.clone()));
+
+                let edge = Edge::uses(fc.source, &ext_nd);
+                graph.add_edge(edge);
+            }
+        } else {
+            // the resolved target first, so same-named functions in one file stay apart
+            let target_function = graph
+                .find_node_at(NodeType::Function, &fc.target.file, fc.target.start as u32)
+                .filter(|t| t.name == fc.target.name)
+                .or_else(|| {
+                    graph.find_node_by_name_in_file(
+                        NodeType::Function,
+                        &fc.target.name,
+                        &fc.source.file,
+                    )
+                });
+            if let Some(target_function) = target_function {
+                let edge_key = (
+                    fc.source.name.clone(),
+                    fc.source.file.clone(),
+                    target_function.name.clone(),
+                    target_function.file.clone(),
+                );
+
+                if !unique_edges.contains(&edge_key) {
+                    unique_edges.insert(edge_key);
+                    let edge = Edge::new(
+                        EdgeType::Calls,
+                        NodeRef::from(fc.source.clone(), NodeType::Function),
+                        NodeRef::from((&target_function).into(), NodeType::Function),
+                    )
+                    .with_call_site(fc.call_site());
+                    graph.add_edge(edge);
+                }
+            } else {
+                let edge_key = (
+                    fc.source.name.clone(),
+                    fc.source.file.clone(),
+                    fc.target.name.clone(),
+                    fc.source.file.clone(),
+                );
+
+                if !unique_edges.contains(&edge_key) {
+                    unique_edges.insert(edge_key);
+                    graph.add_edge(fc.into());
+                }
+            }
+        }
+    }
+
+    for (tc, ext_func, _) in tests {
+        if let Some(ext_nd) = ext_func {
+            let edge_key = (
+                tc.source.name.clone(),
+                tc.source.file.clone(),
+                ext_nd.name.clone(),
+                ext_nd.file.clone(),
+            );
+
+            if !unique_edges.contains(&edge_key) {
+                unique_edges.insert(edge_key);
+
+                let edge = Edge::uses(tc.source, &ext_nd);
+                graph.add_edge(edge);
+                insert_if_missing(graph, Node::new(NodeType::Function, ext_nd));
+            }
+        } else {
+            let edge_key = (
+                tc.source.name.clone(),
+                tc.source.file.clone(),
+                tc.target.name.clone(),
+                tc.source.file.clone(),
+            );
+
+            if !unique_edges.contains(&edge_key) {
+                unique_edges.insert(edge_key);
+                graph.add_edge(Edge::new_test_call(tc));
+            }
+        }
+    }
+
+    for edge in int_tests {
+        graph.add_edge(edge);
+    }
+}
+
+fn insert_if_missing<G: KeyedGraph>(graph: &mut G, node: Node) {
+    let key = create_node_key(&node);
+    if !graph.contains_key(&key) {
+        graph.insert_keyed(key, node);
+    }
+}
+
+pub fn process_endpoint_groups<G: KeyedGraph>(
+    graph: &mut G,
+    eg: Vec<NodeData>,
+    lang: &Lang,
+) -> Result<()> {
+    // Collect all updates we need to make
+    let mut updates: Vec<(String, Node, Vec<KeyedEdge>)> = Vec::new();
+    let endpoint_prefix = format!("{:?}-", NodeType::Endpoint).to_lowercase();
+
+    for group in eg {
+        if let Some(g) = group.meta.get("group") {
+            // function (handler) for the group, preferably next to the group itself
+            let gfs = graph.find_nodes_by_name(NodeType::Function, g);
+            if let Some(gf) = gfs.iter().find(|f| f.file == group.file).or(gfs.first()) {
+                let mut keys: Vec<String> = Vec::new();
+                for q in lang.lang().endpoint_finders() {
+                    let endpoints_in_group =
+                        lang.get_query_opt::<G>(Some(q), &gf.body, &gf.file, NodeType::Endpoint)?;
+
+                    for end in endpoints_in_group {
+                        let prefix =
+                            format!("{:?}-{}", NodeType::Endpoint, sanitize_string(&end.name))
+                                .to_lowercase();
+                        // the same path can be registered for several verbs
+                        if let Some((key, _)) =
+                            graph.keyed_with_prefix(&prefix).into_iter().find(|(k, n)| {
+                                n.node_data.name == end.name
+                                    && n.node_data.file == end.file
+                                    && !keys.contains(k)
+                                    && !updates.iter().any(|(uk, _, _)| uk == k)
+                            })
+                        {
+                            keys.push(key);
+                        }
+                    }
+                }
+                // or the group is the handler itself (actix services, rocket routes![])
+                keys.extend(
+                    graph
+                        .keyed_with_prefix(&endpoint_prefix)
+                        .into_iter()
+                        .filter(|(k, n)| {
+                            n.node_data.file == gf.file
+                                && n.node_data.meta.get("handler") == Some(g)
+                                && !updates.iter().any(|(uk, _, _)| uk == k)
+                        })
+                        .map(|(k, _)| k),
+                );
+
+                for key in keys {
+                    let Some(mut updated_node) = graph.get_keyed(&key) else {
+                        continue;
+                    };
+                    updated_node.node_data.name =
+                        format!("{}{}", group.name, &updated_node.node_data.name);
+                    // Collect edges that need to be updated
+                    let edges_to_update = graph.keyed_edges_from(&key);
+                    updates.push((key, updated_node, edges_to_update));
+                }
+            }
+        }
+    }
+
+    // Apply all updates at once
+    for (old_key, updated_node, edges) in updates {
+        let new_key = create_node_key(&updated_node);
+
+        graph.remove_keyed(&old_key);
+        graph.insert_keyed(new_key.clone(), updated_node);
+
+        for (src, dst, edge_type, call_site) in edges {
+            graph.remove_keyed_edge(&src, &dst, &edge_type);
+            graph.insert_keyed_edge((new_key.clone(), dst, edge_type, call_site));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn prefix_paths<G: KeyedGraph>(graph: &mut G, root: &str) {
+    let (nodes, edges) = graph.drain_keyed();
+
+    // keys include the file, so re-key everything to keep lookups working
+    let mut rekeyed = BTreeMap::new();
+    for (key, mut node) in nodes {
+        node.add_root(root);
+        let new_key = create_node_key(&node);
+        rekeyed.insert(key, new_key.clone());
+        graph.insert_keyed(new_key, node);
+    }
+    // edge ends without a node, like unresolved calls, get the root too
+    let new_key = |k: &String| {
+        rekeyed
+            .get(k)
+            .cloned()
+            .unwrap_or_else(|| rooted_node_key(k, root))
+    };
+    for (src, dst, edge_type, call_site) in edges {
+        graph.insert_keyed_edge((new_key(&src), new_key(&dst), edge_type, call_site));
+    }
+}
@@ -1,6 +1,7 @@
 pub mod array_graph;
 pub mod btreemap_graph;
 pub mod graph;
+mod keyed;
 
 #[cfg(feature = "neo4j")]
 pub mod neo4j_graph;
@@ -11,6 +12,9 @@ pub mod neo4j_utils;
 #[cfg(feature = "neo4j")]
 pub mod graph_ops;
 
+#[cfg(feature = "sled")]
+pub mod sled_graph;
+
 use std::str::FromStr;
 
 pub use array_graph::*;
@@ -20,6 +24,9 @@ pub use graph::*;
 #[cfg(feature = "neo4j")]
 pub use neo4j_graph::*;
 
+#[cfg(feature = "sled")]
+pub use sled_graph::*;
+
 use crate::lang::asg::*;
 use serde::{Deserialize, Serialize};
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
@@ -44,6 +51,9 @@ pub enum NodeType {
     Feature,
     Page,
     Var,
+    Field,
+    Enum,
+    Interface,
 }
 
 // pub enum TestType {
@@ -63,6 +73,9 @@ pub struct Edge {
     pub edge: EdgeType,
     pub source: NodeRef,
     pub target: NodeRef,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_site: Option<CallSite>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
@@ -81,6 +94,24 @@ pub enum EdgeType {
     Renders,  // Page -> Component
     #[serde(rename = "PARENT_OF")]
     ParentOf, // Class -> Class
+    Implements, // Class -> Interface
+}
+
+// which way to follow edges when traversing the graph
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Outgoing,
+    Incoming,
+    Both,
+}
+
+impl Direction {
+    pub fn outgoing(&self) -> bool {
+        matches!(self, Direction::Outgoing | Direction::Both)
+    }
+    pub fn incoming(&self) -> bool {
+        matches!(self, Direction::Incoming | Direction::Both)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, PartialOrd, Ord)]
@@ -89,6 +120,21 @@ pub struct NodeRef {
     pub node_data: NodeKeys,
 }
 
+// what tells nodes apart while walking the graph, the same parts as
+// their keys: type, name, file, start row and column, and verb
+pub type NodeId = (NodeType, String, String, usize, usize, Option<String>);
+
+pub fn node_id(node_type: &NodeType, nd: &NodeData) -> NodeId {
+    (
+        node_type.clone(),
+        nd.name.clone(),
+        nd.file.clone(),
+        nd.start,
+        nd.start_col,
+        nd.meta.get("verb").cloned(),
+    )
+}
+
 impl NodeRef {
     pub fn from(node_data: NodeKeys, node_type: NodeType) -> Self {
         Self {
@@ -96,6 +142,24 @@ impl NodeRef {
             node_data,
         }
     }
+    pub fn id(&self) -> NodeId {
+        let keys = &self.node_data;
+        (
+            self.node_type.clone(),
+            keys.name.clone(),
+            keys.file.clone(),
+            keys.start,
+            keys.start_col,
+            keys.verb.clone(),
+        )
+    }
+    // like form(), absolute paths are left alone
+    pub fn add_root(&mut self, root: &str) {
+        if self.node_data.file.starts_with("/") {
+            return;
+        }
+        self.node_data.file = format!("{}/{}", root, self.node_data.file);
+    }
 }
 
 impl Edge {
@@ -104,14 +168,21 @@ impl Edge {
             edge,
             source,
             target,
+            call_site: None,
         }
     }
+    pub fn with_call_site(mut self, call_site: CallSite) -> Self {
+        self.call_site = Some(call_site);
+        self
+    }
     fn new_test_call(m: Calls) -> Edge {
+        let call_site = m.call_site();
         Edge::new(
             EdgeType::Calls,
             NodeRef::from(m.source, NodeType::Test),
             NodeRef::from(m.target, NodeType::Function),
         )
+        .with_call_site(call_site)
     }
     pub fn linked_e2e_test_call(source: &NodeData, target: &NodeData) -> Edge {
         Edge::new(
@@ -176,6 +247,20 @@ impl Edge {
             NodeRef::from(p.into(), NodeType::Class),
         )
     }
+    pub fn implements(c: &NodeData, i: &NodeData) -> Edge {
+        Edge::new(
+            EdgeType::Implements,
+            NodeRef::from(c.into(), NodeType::Class),
+            NodeRef::from(i.into(), NodeType::Interface),
+        )
+    }
+    pub fn includes(feature: &NodeData, nt: NodeType, member: &NodeData) -> Edge {
+        Edge::new(
+            EdgeType::Includes,
+            NodeRef::from(feature.into(), NodeType::Feature),
+            NodeRef::from(member.into(), nt),
+        )
+    }
     pub fn class_imports(c: &NodeData, m: &NodeData) -> Edge {
         Edge::new(
             EdgeType::Imports,
@@ -191,8 +276,8 @@ impl Edge {
         )
     }
     pub fn add_root(&mut self, root: &str) {
-        self.source.node_data.file = format!("{}/{}", root, self.source.node_data.file);
-        self.target.node_data.file = format!("{}/{}", root, self.target.node_data.file);
+        self.source.add_root(root);
+        self.target.add_root(root);
     }
 }
 
@@ -208,11 +293,13 @@ impl From<Operand> for Edge {
 
 impl From<Calls> for Edge {
     fn from(m: Calls) -> Self {
+        let call_site = m.call_site();
         Edge::new(
             EdgeType::Calls,
             NodeRef::from(m.source, NodeType::Function),
             NodeRef::from(m.target, NodeType::Function),
         )
+        .with_call_site(call_site)
     }
 }
 
@@ -229,6 +316,9 @@ impl Node {
     pub fn to_node_type(&self) -> NodeType {
         self.node_type.clone()
     }
+    pub fn id(&self) -> NodeId {
+        node_id(&self.node_type, &self.node_data)
+    }
 
     pub fn add_root(&mut self, root: &str) {
         form(root, &mut self.node_data)
@@ -256,6 +346,7 @@ impl ToString for EdgeType {
             EdgeType::Uses => "USES".to_string(),
             EdgeType::Includes => "INCLUDES".to_string(),
             EdgeType::Calls => "CALLS".to_string(),
+            EdgeType::Implements => "IMPLEMENTS".to_string(),
         }
     }
 }
@@ -275,6 +366,7 @@ impl FromStr for EdgeType {
             "HANDLER" => Ok(EdgeType::Handler),
             "RENDERS" => Ok(EdgeType::Renders),
             "PARENT_OF" => Ok(EdgeType::ParentOf),
+            "IMPLEMENTS" => Ok(EdgeType::Implements),
             _ => Err(anyhow::anyhow!("Invalid EdgeType: {}", s)),
         }
     }
@@ -1,4 +1,5 @@
-use crate::lang::{Function, FunctionCall};
+use crate::lang::metrics::METRIC_KEYS;
+use crate::lang::{Function, FunctionCall, Lang};
 
 use super::{neo4j_utils::*, *};
 use anyhow::Result;
@@ -148,6 +149,25 @@ impl Neo4jGraph {
         }
     }
 
+    // like ensure_connected, for the read-only methods that only borrow self
+    async fn conn(&self) -> Result<Arc<Neo4jConnection>> {
+        if let Some(conn) = &self.connection {
+            return Ok(conn.clone());
+        }
+        if let Some(conn) = Neo4jConnectionManager::get_connection().await {
+            return Ok(conn);
+        }
+        Neo4jConnectionManager::initialize(
+            &self.config.uri,
+            &self.config.username,
+            &self.config.password,
+        )
+        .await?;
+        Neo4jConnectionManager::get_connection()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Failed to connect to Neo4j"))
+    }
+
     pub fn get_connection(&self) -> Arc<Neo4jConnection> {
         match &self.connection {
             Some(conn) => conn.clone(),
@@ -220,7 +240,37 @@ impl Neo4jGraph {
         }
         let mut result = connection.execute(query_obj).await?;
         if let Some(row) = result.next().await? {
-            Ok(row.get::<u32>("count").unwrap_or(0))
+            Ok(row.get::<u32>("deleted").unwrap_or(0))
+        } else {
+            Ok(0)
+        }
+    }
+
+    pub async fn rename_file(&mut self, from: &str, to: &str) -> Result<u32> {
+        let connection = self.ensure_connected().await?;
+        let (query_str, params) = rename_file_query(from, to);
+        let mut query_obj = query(&query_str);
+        for (k, v) in &params {
+            query_obj = query_obj.param(k, v.as_str());
+        }
+        let mut result = connection.execute(query_obj).await?;
+        if let Some(row) = result.next().await? {
+            Ok(row.get::<u32>("renamed").unwrap_or(0))
+        } else {
+            Ok(0)
+        }
+    }
+
+    pub async fn remove_nodes_by_file_prefix(&mut self, prefix: &str) -> Result<u32> {
+        let connection = self.ensure_connected().await?;
+        let (query_str, params) = remove_nodes_by_file_prefix_query(prefix);
+        let mut query_obj = query(&query_str);
+        for (k, v) in &params {
+            query_obj = query_obj.param(k, v.as_str());
+        }
+        let mut result = connection.execute(query_obj).await?;
+        if let Some(row) = result.next().await? {
+            Ok(row.get::<u32>("deleted").unwrap_or(0))
         } else {
             Ok(0)
         }
@@ -351,18 +401,6 @@ impl std::fmt::Debug for Neo4jGraph {
 }
 
 impl Neo4jGraph {
-    fn new() -> Self {
-        Neo4jGraph {
-            connection: None,
-            config: Neo4jConfig::default(),
-            connected: Arc::new(Mutex::new(false)),
-        }
-    }
-
-    fn with_capacity(_nodes: usize, _edges: usize) -> Self {
-        Neo4jGraph::default()
-    }
-
     pub async fn add_node(&mut self, node_type: NodeType, node_data: NodeData) -> Result<()> {
         self.execute_with_transaction(|txn_manager| {
             txn_manager.add_node(&node_type, &node_data);
@@ -410,7 +448,13 @@ impl Neo4jGraph {
     }
 
     pub async fn find_nodes_by_name(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
-        let connection = self.get_connection();
+        let connection = match self.conn().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                debug!("Connection error: {}", e);
+                return Vec::new();
+            }
+        };
 
         let (query, params) = find_nodes_by_name_query(&node_type, name);
 
@@ -432,7 +476,13 @@ impl Neo4jGraph {
         name: &str,
         file: &str,
     ) -> Option<NodeData> {
-        let connection = self.get_connection();
+        let connection = match self.conn().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                debug!("Connection error: {}", e);
+                return None;
+            }
+        };
 
         let (query, params) = find_node_by_name_file_query(&node_type, name, file);
 
@@ -445,8 +495,8 @@ impl Neo4jGraph {
         }
     }
 
-    pub async fn get_graph_size(&mut self) -> Result<(u32, u32)> {
-        let connection = self.ensure_connected().await?;
+    pub async fn get_graph_size(&self) -> Result<(u32, u32)> {
+        let connection = self.conn().await?;
         let query_str = count_nodes_edges_query();
         let mut result = connection.execute(query(&query_str)).await?;
         if let Some(row) = result.next().await? {
@@ -457,11 +507,33 @@ impl Neo4jGraph {
             Ok((0, 0))
         }
     }
-    fn get_graph_keys(&self) -> (HashSet<&str>, HashSet<&str>) {
-        (HashSet::new(), HashSet::new())
+    pub async fn get_graph_keys(&self) -> Result<(HashSet<String>, HashSet<String>)> {
+        let connection = self.conn().await?;
+        let mut node_keys = HashSet::new();
+        let mut result = connection.execute(query(&all_node_keys_query())).await?;
+        while let Some(row) = result.next().await? {
+            if let Ok(key) = row.get::<String>("key") {
+                node_keys.insert(key.to_lowercase());
+            }
+        }
+        let mut edge_keys = HashSet::new();
+        let mut result = connection.execute(query(&all_edge_keys_query())).await?;
+        while let Some(row) = result.next().await? {
+            if let (Ok(source), Ok(target), Ok(edge_type)) = (
+                row.get::<String>("source"),
+                row.get::<String>("target"),
+                row.get::<String>("edge_type"),
+            ) {
+                if let Ok(edge_type) = EdgeType::from_str(&edge_type) {
+                    edge_keys
+                        .insert(format!("{}-{}-{:?}", source, target, edge_type).to_lowercase());
+                }
+            }
+        }
+        Ok((node_keys, edge_keys))
     }
-    pub async fn analysis(&mut self) -> Result<()> {
-        let connection = self.get_connection();
+    pub async fn analysis(&self) -> Result<()> {
+        let connection = self.conn().await?;
         let (nodes, edges) = self.get_graph_size().await?;
         println!("Graph contains {} nodes and {} edges", nodes, edges);
 
@@ -531,7 +603,13 @@ impl Neo4jGraph {
         Ok(())
     }
     pub async fn count_edges_of_type(&self, edge_type: EdgeType) -> usize {
-        let connection = self.get_connection();
+        let connection = match self.conn().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                debug!("Connection error: {}", e);
+                return 0;
+            }
+        };
 
         let (query_str, params) = count_edges_by_type_query(&edge_type);
         let mut query_obj = query(&query_str);
@@ -653,7 +731,8 @@ impl Neo4jGraph {
                     EdgeType::Calls,
                     NodeRef::from(func_call.source.clone(), NodeType::Function),
                     NodeRef::from(cls_call.into(), NodeType::Class),
-                );
+                )
+                .with_call_site(func_call.call_site());
                 txn_manager.add_edge(&edge);
             }
             if func_call.target.is_empty() {
@@ -684,70 +763,163 @@ impl Neo4jGraph {
         txn_manager.execute().await
     }
 
-    pub async fn find_nodes_by_type(&mut self, node_type: NodeType) -> Result<Vec<NodeData>> {
-        let connection = self.ensure_connected().await?;
+    pub async fn find_nodes_by_type(&self, node_type: NodeType) -> Result<Vec<NodeData>> {
+        let connection = self.conn().await?;
         let (query, params) = find_nodes_by_type_query(&node_type);
         execute_node_query(&connection, query, params).await
     }
 
     pub async fn find_nodes_by_name_contains(
-        &mut self,
+        &self,
         node_type: NodeType,
         name_part: &str,
     ) -> Result<Vec<NodeData>> {
-        let connection = self.ensure_connected().await?;
+        let connection = self.conn().await?;
         let (query, params) = find_nodes_by_name_contains_query(&node_type, name_part);
         execute_node_query(&connection, query, params).await
     }
 
+    pub async fn find_nodes_by_qualified_name(
+        &self,
+        node_type: NodeType,
+        qualified_name: &str,
+    ) -> Result<Vec<NodeData>> {
+        let connection = self.conn().await?;
+        let (query, params) = find_nodes_by_qualified_name_query(&node_type, qualified_name);
+        execute_node_query(&connection, query, params).await
+    }
+
     pub async fn find_nodes_by_file_ends_with(
-        &mut self,
+        &self,
         node_type: NodeType,
         file: &str,
     ) -> Result<Vec<NodeData>> {
-        let connection = self.ensure_connected().await?;
+        let connection = self.conn().await?;
         let (query, params) = find_nodes_by_file_pattern_query(&node_type, file);
         execute_node_query(&connection, query, params).await
     }
 
     pub async fn find_nodes_in_range(
-        &mut self,
+        &self,
         node_type: NodeType,
         row: u32,
         file: &str,
     ) -> Result<Option<NodeData>> {
-        let connection = self.ensure_connected().await?;
+        let connection = self.conn().await?;
         let (query, params) = find_nodes_in_range_query(&node_type, file, row);
         let nodes = execute_node_query(&connection, query, params).await?;
         Ok(nodes.into_iter().next())
     }
 
     pub async fn find_resource_nodes(
-        &mut self,
+        &self,
         node_type: NodeType,
         verb: &str,
         path: &str,
     ) -> Result<Vec<NodeData>> {
-        let connection = self.ensure_connected().await?;
+        let connection = self.conn().await?;
         let (query, params) = find_resource_nodes_query(&node_type, verb, path);
         execute_node_query(&connection, query, params).await
     }
 
-    pub async fn find_handlers_for_endpoint(
-        &mut self,
-        endpoint: &NodeData,
-    ) -> Result<Vec<NodeData>> {
-        let connection = self.ensure_connected().await?;
+    pub async fn find_handlers_for_endpoint(&self, endpoint: &NodeData) -> Result<Vec<NodeData>> {
+        let connection = self.conn().await?;
         let (query, params) = find_handlers_for_endpoint_query(&endpoint);
         execute_node_query(&connection, query, params).await
     }
 
-    pub async fn find_functions_called_by(&mut self, function: &NodeData) -> Result<Vec<NodeData>> {
-        let connection = self.ensure_connected().await?;
+    pub async fn find_functions_called_by(&self, function: &NodeData) -> Result<Vec<NodeData>> {
+        let connection = self.conn().await?;
         let (query, params) = find_functions_called_by_query(&function);
         execute_node_query(&connection, query, params).await
     }
 
+    pub async fn find_neighbors(
+        &self,
+        node_type: NodeType,
+        node: &NodeData,
+        edge_types: &[EdgeType],
+        direction: Direction,
+    ) -> Result<Vec<Node>> {
+        let connection = self.conn().await?;
+        let (query, params) = find_neighbors_query(&node_type, node, edge_types, direction);
+        execute_labeled_node_query(&connection, query, params).await
+    }
+
+    pub async fn transitive_calls(&self, function: &NodeData) -> Result<Vec<NodeData>> {
+        let connection = self.conn().await?;
+        let (query, params) = transitive_calls_query(function);
+        execute_node_query(&connection, query, params).await
+    }
+
+    pub async fn find_hotspots(&self, metric: &str, limit: usize) -> Result<Vec<NodeData>> {
+        if !METRIC_KEYS.contains(&metric) {
+            return Err(anyhow::anyhow!("unknown metric {}", metric));
+        }
+        let connection = self.conn().await?;
+        let (query, params) = hotspots_query(metric, limit);
+        execute_node_query(&connection, query, params).await
+    }
+
+    pub async fn shortest_path(
+        &self,
+        from: &Node,
+        to: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+    ) -> Result<Option<Vec<Node>>> {
+        if from == to {
+            return Ok(Some(vec![from.clone()]));
+        }
+        // cypher has no empty variable-length pattern, and no path fits anyway
+        if max_depth == 0 {
+            return Ok(None);
+        }
+        let connection = self.conn().await?;
+        let (query, params) = shortest_path_query(from, to, edge_types, direction, max_depth);
+        let path = execute_labeled_node_query(&connection, query, params).await?;
+        Ok(if path.is_empty() { None } else { Some(path) })
+    }
+
+    pub async fn bfs(
+        &self,
+        start: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+    ) -> Result<Vec<(Node, usize)>> {
+        self.expand(start, edge_types, direction, max_depth, true)
+            .await
+    }
+
+    pub async fn dfs(
+        &self,
+        start: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+    ) -> Result<Vec<(Node, usize)>> {
+        self.expand(start, edge_types, direction, max_depth, false)
+            .await
+    }
+
+    async fn expand(
+        &self,
+        start: &Node,
+        edge_types: &[EdgeType],
+        direction: Direction,
+        max_depth: usize,
+        bfs: bool,
+    ) -> Result<Vec<(Node, usize)>> {
+        if max_depth == 0 {
+            return Ok(Vec::new());
+        }
+        let connection = self.conn().await?;
+        let (query, params) = expand_query(start, edge_types, direction, max_depth, bfs);
+        execute_depth_node_query(&connection, query, params).await
+    }
+
     pub async fn filter_out_nodes_without_children(
         &mut self,
         parent_type: NodeType,
@@ -791,11 +963,11 @@ impl Neo4jGraph {
     }
 
     pub async fn check_direct_data_model_usage(
-        &mut self,
+        &self,
         function_name: &str,
         data_model: &str,
     ) -> Result<bool> {
-        let connection = self.ensure_connected().await?;
+        let connection = self.conn().await?;
         let (query_str, params) = check_direct_data_model_usage_query(function_name, data_model);
         let mut query_obj = query(&query_str);
         for (key, value) in params {
@@ -809,15 +981,6 @@ impl Neo4jGraph {
         }
     }
 
-    pub async fn prefix_paths(&mut self, root: &str) -> Result<()> {
-        self.execute_with_transaction(|txn_manager| {
-            let (query, params) = prefix_paths_query(root);
-            txn_manager.add_query((query, params));
-            Ok(())
-        })
-        .await
-    }
-
     pub async fn create_filtered_graph(&self, final_filter: &[String]) -> Result<Self> {
         if final_filter.is_empty() {
             return Ok(self.clone());
@@ -896,7 +1059,7 @@ impl Neo4jGraph {
         Ok(filtered_graph)
     }
 
-    pub async fn extend_graph(&mut self, mut other: Self) -> Result<()> {
+    pub async fn extend_graph(&mut self, other: Self) -> Result<()> {
         let (other_nodes, other_edges) = other.get_graph_size().await?;
         if other_nodes == 0 && other_edges == 0 {
             warn!("Warning: Attempting to extend with an empty graph");
@@ -978,12 +1141,12 @@ impl Neo4jGraph {
     }
 
     pub async fn find_source_edge_by_name_and_file(
-        &mut self,
+        &self,
         edge_type: EdgeType,
         target_name: &str,
         target_file: &str,
     ) -> Option<NodeKeys> {
-        let connection = match self.ensure_connected().await {
+        let connection = match self.conn().await {
             Ok(conn) => conn,
             Err(e) => {
                 debug!("Connection error: {}", e);
@@ -1002,12 +1165,14 @@ impl Neo4jGraph {
                     let name = row.get::<String>("name").unwrap_or_default();
                     let file = row.get::<String>("file").unwrap_or_default();
                     let start = row.get::<i32>("start").unwrap_or(0) as usize;
+                    let start_col = row.get::<i32>("start_col").unwrap_or(0) as usize;
                     let verb = row.get::<String>("verb").ok();
 
                     Some(NodeKeys {
                         name,
                         file,
                         start,
+                        start_col,
                         verb,
                     })
                 } else {
@@ -1064,40 +1229,37 @@ impl Neo4jGraph {
         Ok(())
     }
 
-    // pub async fn get_data_models_within(&mut self, lang: &Lang) -> Result<()> {
-    //     let connection = self.ensure_connected().await?;
-    //     let mut txn_manager = TransactionManager::new(&connection);
+    pub async fn get_data_models_within(&mut self, lang: &Lang) -> Result<()> {
+        let data_models = self.find_nodes_by_type(NodeType::DataModel).await?;
+        let functions = self.find_nodes_by_type(NodeType::Function).await?;
 
-    //     let data_models = self.find_nodes_by_type(NodeType::DataModel).await?;
-
-    //     for data_model in data_models {
-    //         let edges = lang.lang().data_model_within_finder(&data_model, &|file| {
-    //             futures::executor::block_on(
-    //                 self.find_nodes_by_file_ends_with(NodeType::Function, file)
-    //                     .unwrap(),
-    //             )
-    //         });
-
-    //         for edge in edges {
-    //             txn_manager.add_edge(&edge);
-    //         }
-    //     }
-
-    //     if let Err(e) = txn_manager.execute().await {
-    //         println!("Transaction failed in get_data_models_within: {:?}", e);
-    //         return Err(e);
-    //     }
+        let mut edges = Vec::new();
+        for data_model in data_models {
+            edges.extend(lang.lang().data_model_within_finder(&data_model, &|file| {
+                functions
+                    .iter()
+                    .filter(|f| f.file.ends_with(file))
+                    .cloned()
+                    .collect()
+            }));
+        }
 
-    //     Ok(())
-    // }
+        self.execute_with_transaction(|txn_manager| {
+            for edge in &edges {
+                txn_manager.add_edge(edge);
+            }
+            Ok(())
+        })
+        .await
+    }
 
     pub async fn find_nodes_with_edge_type(
-        &mut self,
+        &self,
         source_type: NodeType,
         target_type: NodeType,
         edge_type: EdgeType,
     ) -> Result<Vec<(NodeData, NodeData)>> {
-        let connection = self.ensure_connected().await?;
+        let connection = self.conn().await?;
         let (query_str, params) =
             find_nodes_with_edge_type_query(&source_type, &target_type, &edge_type);
 
@@ -1133,7 +1295,13 @@ impl Neo4jGraph {
     }
 
     pub async fn find_endpoint(&self, name: &str, file: &str, verb: &str) -> Option<NodeData> {
-        let connection = self.get_connection();
+        let connection = match self.conn().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                debug!("Connection error: {}", e);
+                return None;
+            }
+        };
         let (query, params) = find_endpoint_query(name, file, verb);
         match execute_node_query(&connection, query, params).await {
             Ok(nodes) => nodes.into_iter().next(),
@@ -1144,58 +1312,294 @@ impl Neo4jGraph {
         }
     }
 
-    // pub async fn process_endpoint_groups(&mut self, eg: Vec<NodeData>, lang: &Lang) -> Result<()> {
-    //     // Pre-process the data we need before entering the transaction
-    //     let mut group_data = Vec::new();
-
-    //     for group in &eg {
-    //         if let Some(group_function_name) = group.meta.get("group") {
-    //             if let Some(group_function) = self
-    //                 .find_nodes_by_name(NodeType::Function, group_function_name)
-    //                 .await
-    //                 .first()
-    //                 .cloned()
-    //             {
-    //                 for finder_query in lang.lang().endpoint_finders() {
-    //                     if let Ok(endpoints) = lang.get_query_opt::<Self>(
-    //                         Some(finder_query),
-    //                         &group_function.body,
-    //                         &group_function.file,
-    //                         NodeType::Endpoint,
-    //                     ) {
-    //                         group_data.push((group.clone(), endpoints));
-    //                     }
-    //                 }
-    //             }
-    //         }
-    //     }
-
-    //     self.execute_with_transaction(|txn_manager|  {
-    //         for (group, endpoints) in &group_data {
-    //             for endpoint in endpoints {
-    //                 let update_node_query = format!(
-    //                     "MATCH (n:Endpoint {{name: $old_name, file: $file}})
-    //                     SET n.name = $new_name
-    //                     RETURN n"
-    //                 );
-    //                 let mut node_params = HashMap::new();
-    //                 node_params.insert("old_name".to_string(), endpoint.name.clone());
-    //                 node_params.insert("file".to_string(), endpoint.file.clone());
-    //                 node_params.insert(
-    //                     "new_name".to_string(),
-    //                     format!("{}{}", group.name, endpoint.name),
-    //                 );
-    //                 txn_manager.add_query((update_node_query, node_params.clone()));
-    //                 let update_rels_query = format!(
-    //                     "MATCH (source:Endpoint {{name: $old_name, file: $file}})-[r]->(target)
-    //                     SET source.name = $new_name
-    //                     RETURN r"
-    //                 );
-    //                 txn_manager.add_query((update_rels_query, node_params));
-    //             }
-    //         }
-    //         Ok(())
-    //     })
-    //     .await
-    // }
+    pub async fn process_endpoint_groups(&mut self, eg: Vec<NodeData>, lang: &Lang) -> Result<()> {
+        self.ensure_connected().await?;
+        // Collect the renames before entering the transaction
+        let mut renames = Vec::new();
+        for group in &eg {
+            if let Some(g) = group.meta.get("group") {
+                // function (handler) for the group, preferably next to the group itself
+                let gfs = self.find_nodes_by_name(NodeType::Function, g).await;
+                if let Some(gf) = gfs.iter().find(|f| f.file == group.file).or(gfs.first()) {
+                    for q in lang.lang().endpoint_finders() {
+                        let endpoints_in_group = lang.get_query_opt::<BTreeMapGraph>(
+                            Some(q),
+                            &gf.body,
+                            &gf.file,
+                            NodeType::Endpoint,
+                        )?;
+                        for end in endpoints_in_group {
+                            renames.push((
+                                end.name.clone(),
+                                end.file.clone(),
+                                format!("{}{}", group.name, end.name),
+                            ));
+                        }
+                    }
+                    // or the group is the handler itself (actix services, rocket routes![])
+                    for end in self.find_nodes_by_type(NodeType::Endpoint).await? {
+                        if end.file == gf.file && end.meta.get("handler") == Some(g) {
+                            renames.push((
+                                end.name.clone(),
+                                end.file.clone(),
+                                format!("{}{}", group.name, end.name),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.execute_with_transaction(|txn_manager| {
+            for (old_name, file, new_name) in &renames {
+                txn_manager.add_query(update_endpoint_name_query(old_name, file, new_name));
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    // writes an in-memory graph in one transaction, merging nodes by their key
+    pub async fn upload(&mut self, graph: &BTreeMapGraph) -> Result<(u32, u32)> {
+        let connection = self.ensure_connected().await?;
+        let mut txn_manager = TransactionManager::new(&connection);
+        for node in graph.nodes.values() {
+            txn_manager.add_node(&node.node_type, &node.node_data);
+        }
+        for edge in graph.to_array_graph_edges() {
+            txn_manager.add_edge(&edge);
+        }
+        txn_manager.execute().await?;
+        self.get_graph_size().await
+    }
+}
+
+// The Graph trait is sync, so the builder and linker write into this in-memory
+// graph and `flush` uploads it to Neo4j, surfacing any query error there.
+// Queries against the database go through `db`, the async Neo4jGraph.
+#[derive(Clone, Debug, Default)]
+pub struct Neo4jStagingGraph {
+    pub db: Neo4jGraph,
+    staged: BTreeMapGraph,
+}
+
+impl Neo4jStagingGraph {
+    pub async fn flush(&mut self) -> Result<(u32, u32)> {
+        let staged = std::mem::take(&mut self.staged);
+        self.db.upload(&staged).await
+    }
+}
+
+impl Graph for Neo4jStagingGraph {
+    fn analysis(&self) {
+        self.staged.analysis()
+    }
+
+    fn create_filtered_graph(&self, final_filter: &[String]) -> Self {
+        Neo4jStagingGraph {
+            db: self.db.clone(),
+            staged: self.staged.create_filtered_graph(final_filter),
+        }
+    }
+
+    fn extend_graph(&mut self, other: Self) {
+        self.staged.extend_graph(other.staged)
+    }
+
+    fn get_graph_size(&self) -> (u32, u32) {
+        self.staged.get_graph_size()
+    }
+
+    fn find_nodes_by_name(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
+        self.staged.find_nodes_by_name(node_type, name)
+    }
+
+    fn add_node_with_parent(
+        &mut self,
+        node_type: NodeType,
+        node_data: NodeData,
+        parent_type: NodeType,
+        parent_file: &str,
+    ) {
+        self.staged
+            .add_node_with_parent(node_type, node_data, parent_type, parent_file)
+    }
+
+    fn add_edge(&mut self, edge: Edge) {
+        self.staged.add_edge(edge)
+    }
+
+    fn add_node(&mut self, node_type: NodeType, node_data: NodeData) {
+        self.staged.add_node(node_type, node_data)
+    }
+
+    fn get_graph_keys(&self) -> (HashSet<String>, HashSet<String>) {
+        self.staged.get_graph_keys()
+    }
+
+    fn find_source_edge_by_name_and_file(
+        &self,
+        edge_type: EdgeType,
+        target_name: &str,
+        target_file: &str,
+    ) -> Option<NodeKeys> {
+        self.staged
+            .find_source_edge_by_name_and_file(edge_type, target_name, target_file)
+    }
+
+    fn process_endpoint_groups(&mut self, eg: Vec<NodeData>, lang: &Lang) -> Result<()> {
+        self.staged.process_endpoint_groups(eg, lang)
+    }
+
+    fn class_inherits(&mut self) {
+        self.staged.class_inherits()
+    }
+
+    fn class_includes(&mut self) {
+        self.staged.class_includes()
+    }
+
+    fn add_instances(&mut self, nodes: Vec<NodeData>) {
+        self.staged.add_instances(nodes)
+    }
+
+    fn add_functions(&mut self, functions: Vec<Function>) {
+        self.staged.add_functions(functions)
+    }
+
+    fn add_page(&mut self, page: (NodeData, Option<Edge>)) {
+        self.staged.add_page(page)
+    }
+
+    fn add_pages(&mut self, pages: Vec<(NodeData, Vec<Edge>)>) {
+        self.staged.add_pages(pages)
+    }
+
+    fn add_endpoints(&mut self, endpoints: Vec<(NodeData, Option<Edge>)>) {
+        self.staged.add_endpoints(endpoints)
+    }
+
+    fn add_test_node(&mut self, test_data: NodeData, test_type: NodeType, test_edge: Option<Edge>) {
+        self.staged.add_test_node(test_data, test_type, test_edge)
+    }
+
+    fn add_calls(&mut self, calls: (Vec<FunctionCall>, Vec<FunctionCall>, Vec<Edge>)) {
+        self.staged.add_calls(calls)
+    }
+
+    fn filter_out_nodes_without_children(
+        &mut self,
+        parent_type: NodeType,
+        child_type: NodeType,
+        child_meta_key: &str,
+    ) {
+        self.staged
+            .filter_out_nodes_without_children(parent_type, child_type, child_meta_key)
+    }
+
+    fn get_data_models_within(&mut self, lang: &Lang) {
+        self.staged.get_data_models_within(lang)
+    }
+
+    fn prefix_paths(&mut self, root: &str) {
+        self.staged.prefix_paths(root)
+    }
+
+    fn find_endpoint(&self, name: &str, file: &str, verb: &str) -> Option<NodeData> {
+        self.staged.find_endpoint(name, file, verb)
+    }
+
+    fn find_resource_nodes(&self, node_type: NodeType, verb: &str, path: &str) -> Vec<NodeData> {
+        self.staged.find_resource_nodes(node_type, verb, path)
+    }
+
+    fn find_handlers_for_endpoint(&self, endpoint: &NodeData) -> Vec<NodeData> {
+        self.staged.find_handlers_for_endpoint(endpoint)
+    }
+
+    fn check_direct_data_model_usage(&self, function_name: &str, data_model: &str) -> bool {
+        self.staged
+            .check_direct_data_model_usage(function_name, data_model)
+    }
+
+    fn find_functions_called_by(&self, function: &NodeData) -> Vec<NodeData> {
+        self.staged.find_functions_called_by(function)
+    }
+
+    fn find_nodes_by_type(&self, node_type: NodeType) -> Vec<NodeData> {
+        self.staged.find_nodes_by_type(node_type)
+    }
+
+    fn find_nodes_with_edge_type(
+        &self,
+        source_type: NodeType,
+        target_type: NodeType,
+        edge_type: EdgeType,
+    ) -> Vec<(NodeData, NodeData)> {
+        self.staged
+            .find_nodes_with_edge_type(source_type, target_type, edge_type)
+    }
+
+    fn count_edges_of_type(&self, edge_type: EdgeType) -> usize {
+        self.staged.count_edges_of_type(edge_type)
+    }
+
+    fn get_edges(&self) -> Vec<Edge> {
+        self.staged.get_edges()
+    }
+
+    fn find_neighbors(
+        &self,
+        node_type: NodeType,
+        node: &NodeData,
+        edge_types: &[EdgeType],
+        direction: Direction,
+    ) -> Vec<Node> {
+        self.staged
+            .find_neighbors(node_type, node, edge_types, direction)
+    }
+
+    fn find_nodes_by_name_contains(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
+        self.staged.find_nodes_by_name_contains(node_type, name)
+    }
+
+    fn find_nodes_by_qualified_name(
+        &self,
+        node_type: NodeType,
+        qualified_name: &str,
+    ) -> Vec<NodeData> {
+        self.staged
+            .find_nodes_by_qualified_name(node_type, qualified_name)
+    }
+
+    fn find_node_by_name_in_file(
+        &self,
+        node_type: NodeType,
+        name: &str,
+        file: &str,
+    ) -> Option<NodeData> {
+        self.staged.find_node_by_name_in_file(node_type, name, file)
+    }
+
+    fn find_node_by_name_and_file_end_with(
+        &self,
+        node_type: NodeType,
+        name: &str,
+        suffix: &str,
+    ) -> Option<NodeData> {
+        self.staged
+            .find_node_by_name_and_file_end_with(node_type, name, suffix)
+    }
+
+    fn find_nodes_by_file_ends_with(&self, node_type: NodeType, file: &str) -> Vec<NodeData> {
+        self.staged.find_nodes_by_file_ends_with(node_type, file)
+    }
+
+    fn find_nodes_in_range(&self, node_type: NodeType, row: u32, file: &str) -> Option<NodeData> {
+        self.staged.find_nodes_in_range(node_type, row, file)
+    }
+
+    fn find_node_at(&self, node_type: NodeType, file: &str, line: u32) -> Option<NodeData> {
+        self.staged.find_node_at(node_type, file, line)
+    }
 }
@@ -6,7 +6,8 @@ use std::{
 };
 use tracing::{debug, info};
 use lazy_static::lazy_static;
-use crate::{lang::FunctionCall, utils::create_node_key};
+use crate::{lang::FunctionCall, utils::{create_node_key, sanitize_string}};
+use crate::lang::metrics::METRIC_KEYS;
 use serde_json;
 
 use super::*;
@@ -35,6 +36,11 @@ impl Neo4jConnectionManager {
         match Neo4jConnection::connect(config).await {
             Ok(connection) => {
                 info!("Successfully connected to Neo4j");
+                for index in qualified_name_index_queries() {
+                    if let Err(e) = connection.run(query(&index)).await {
+                        debug!("Failed to create index: {}", e);
+                    }
+                }
                 *conn_guard = Some(Arc::new(connection));
                 Ok(())
             },
@@ -129,6 +135,10 @@ impl NodeQueryBuilder {
         params.insert("file".to_string(), self.node_data.file.clone());
         params.insert("start".to_string(), self.node_data.start.to_string());
         params.insert("end".to_string(), self.node_data.end.to_string());
+        params.insert("start_col".to_string(), self.node_data.start_col.to_string());
+        params.insert("end_col".to_string(), self.node_data.end_col.to_string());
+        params.insert("start_byte".to_string(), self.node_data.start_byte.to_string());
+        params.insert("end_byte".to_string(), self.node_data.end_byte.to_string());
         params.insert("body".to_string(), self.node_data.body.clone());
 
         if let Some(data_type) = &self.node_data.data_type {
@@ -140,9 +150,18 @@ impl NodeQueryBuilder {
         if let Some(hash) = &self.node_data.hash {
             params.insert("hash".to_string(), hash.clone());
         }
+        if let Some(qualified_name) = self.node_data.qualified_name() {
+            params.insert("qualified_name".to_string(), qualified_name.clone());
+        }
         let string_meta = serde_json::to_string(&self.node_data.meta).unwrap();
         params.insert("meta".to_string(), string_meta);
-        
+        // function metrics get their own properties, so hotspots can be ranked in cypher
+        for key in METRIC_KEYS {
+            if let Some(value) = self.node_data.meta.get(key) {
+                params.insert(key.to_string(), value.clone());
+            }
+        }
+
         let node_key = create_node_key(&Node::new(self.node_type.clone(), self.node_data.clone()));
         params.insert("key".to_string(), node_key.clone());
         
@@ -161,7 +180,7 @@ impl NodeQueryBuilder {
 
 
         let query = format!(
-            "MERGE (n:{} {{name: $name, file: $file, start: $start}})
+            "MERGE (n:{} {{key: $key}})
             ON CREATE SET {}
             ON MATCH SET {}",
             self.node_type.to_string(),
@@ -205,6 +224,15 @@ impl EdgeQueryBuilder {
         if let Some(verb) = &self.edge.target.node_data.verb {
             params.insert("target_verb".to_string(), verb.clone());
         }
+
+        if let Some(cs) = &self.edge.call_site {
+            params.insert("call_start".to_string(), cs.start.to_string());
+            params.insert("call_end".to_string(), cs.end.to_string());
+            params.insert("call_start_col".to_string(), cs.start_col.to_string());
+            params.insert("call_end_col".to_string(), cs.end_col.to_string());
+            params.insert("call_start_byte".to_string(), cs.start_byte.to_string());
+            params.insert("call_end_byte".to_string(), cs.end_byte.to_string());
+        }
         
         params
     }
@@ -216,12 +244,20 @@ impl EdgeQueryBuilder {
         let source_type = self.edge.source.node_type.to_string();
         let target_type = self.edge.target.node_type.to_string();
         
-            let query = format!(
+            let mut query = format!(
                 "MATCH (source:{} {{name: $source_name, file: $source_file}}), \
                        (target:{} {{name: $target_name, file: $target_file}}) \
                  MERGE (source)-[r:{}]->(target)",
                 source_type, target_type, rel_type
             );
+            // keep the first call site seen for this pair
+            if self.edge.call_site.is_some() {
+                query.push_str(
+                    " ON CREATE SET r.call_start = $call_start, r.call_end = $call_end, \
+                     r.call_start_col = $call_start_col, r.call_end_col = $call_end_col, \
+                     r.call_start_byte = $call_start_byte, r.call_end_byte = $call_end_byte",
+                );
+            }
             (query, params)
     }
 }
@@ -336,6 +372,10 @@ pub async fn execute_node_query(
                     let file = node.get::<String>("file").unwrap_or_default();
                     let start = node.get::<i32>("start").unwrap_or_default();
                     let end = node.get::<i32>("end").unwrap_or_default();
+                    let start_col = node.get::<i32>("start_col").unwrap_or_default();
+                    let end_col = node.get::<i32>("end_col").unwrap_or_default();
+                    let start_byte = node.get::<i32>("start_byte").unwrap_or_default();
+                    let end_byte = node.get::<i32>("end_byte").unwrap_or_default();
                     let body = node.get::<String>("body").unwrap_or_default();
                     let data_type = node.get::<String>("data_type").unwrap_or_default();
                     let docs = node.get::<String>("docs").unwrap_or_default();
@@ -348,6 +388,10 @@ pub async fn execute_node_query(
                         file,
                         start: start as usize,
                         end: end as usize,
+                        start_col: start_col as usize,
+                        end_col: end_col as usize,
+                        start_byte: start_byte as usize,
+                        end_byte: end_byte as usize,
                         body,
                         data_type: Some(data_type),
                         docs: Some(docs),
@@ -366,6 +410,57 @@ pub async fn execute_node_query(
     }
 }
 
+// rows of (node_type, m), for queries that can land on any kind of node
+pub async fn execute_labeled_node_query(
+    conn: &Neo4jConnection,
+    query_str: String,
+    params: HashMap<String, String>,
+) -> Result<Vec<Node>> {
+    let mut query_obj = query(&query_str);
+    for (key, value) in params {
+        query_obj = query_obj.param(&key, value);
+    }
+    let mut nodes = Vec::new();
+    let mut result = conn.execute(query_obj).await?;
+    while let Some(row) = result.next().await? {
+        let node_type: String = row.get("node_type").unwrap_or_default();
+        let Ok(node_type) = NodeType::from_str(&node_type) else {
+            continue;
+        };
+        if let Ok(node) = row.get::<neo4rs::Node>("m") {
+            nodes.push(Node::new(node_type, extract_node_data_from_neo4j_node(&node)));
+        }
+    }
+    Ok(nodes)
+}
+
+// like execute_labeled_node_query, with the `depth` each node was reached at
+pub async fn execute_depth_node_query(
+    conn: &Neo4jConnection,
+    query_str: String,
+    params: HashMap<String, String>,
+) -> Result<Vec<(Node, usize)>> {
+    let mut query_obj = query(&query_str);
+    for (key, value) in params {
+        query_obj = query_obj.param(&key, value);
+    }
+    let mut nodes = Vec::new();
+    let mut result = conn.execute(query_obj).await?;
+    while let Some(row) = result.next().await? {
+        let node_type: String = row.get("node_type").unwrap_or_default();
+        let Ok(node_type) = NodeType::from_str(&node_type) else {
+            continue;
+        };
+        let depth = row.get::<i64>("depth").unwrap_or_default() as usize;
+        if let Ok(node) = row.get::<neo4rs::Node>("m") {
+            nodes.push((
+                Node::new(node_type, extract_node_data_from_neo4j_node(&node)),
+                depth,
+            ));
+        }
+    }
+    Ok(nodes)
+}
 
 pub fn node_exists_query(node_key: &str) -> (String, HashMap<String, String>) {
     let mut params = HashMap::new();
@@ -492,6 +587,46 @@ pub fn find_nodes_by_name_contains_query(
     (query, params)
 }
 
+// indexes are per label, so one for each type that gets a qualified name
+pub fn qualified_name_index_queries() -> Vec<String> {
+    [
+        NodeType::Function,
+        NodeType::Class,
+        NodeType::DataModel,
+        NodeType::Interface,
+        NodeType::Enum,
+        NodeType::Instance,
+        NodeType::Field,
+    ]
+    .iter()
+    .map(|nt| {
+        let label = nt.to_string();
+        format!(
+            "CREATE INDEX {}_qualified_name IF NOT EXISTS FOR (n:{}) ON (n.qualified_name)",
+            label.to_lowercase(),
+            label
+        )
+    })
+    .collect()
+}
+
+pub fn find_nodes_by_qualified_name_query(
+    node_type: &NodeType,
+    qualified_name: &str,
+) -> (String, HashMap<String, String>) {
+    let mut params = HashMap::new();
+    params.insert("qualified_name".to_string(), qualified_name.to_string());
+
+    let query = format!(
+        "MATCH (n:{}) 
+         WHERE n.qualified_name = $qualified_name 
+         RETURN n",
+        node_type.to_string()
+    );
+
+    (query, params)
+}
+
 pub fn find_nodes_in_range_query(
     node_type: &NodeType,
     file: &str,
@@ -525,7 +660,8 @@ pub fn find_source_edge_by_name_and_file_query(
 
     let query = format!(
         "MATCH (source)-[r:{}]->(target {{name: $target_name, file: $target_file}})
-         RETURN source.name as name, source.file as file, source.start as start, source.verb as verb
+         RETURN source.name as name, source.file as file, source.start as start, \
+                source.start_col as start_col, source.verb as verb
          LIMIT 1",
         edge_type.to_string()
     );
@@ -615,6 +751,151 @@ pub fn find_functions_called_by_query(function: &NodeData) -> (String, HashMap<S
     (query.to_string(), params)
 }
 
+fn node_match_params(prefix: &str, node: &NodeData) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    params.insert(format!("{}_name", prefix), node.name.clone());
+    params.insert(format!("{}_file", prefix), node.file.clone());
+    params.insert(format!("{}_start", prefix), node.start.to_string());
+    params
+}
+
+// "-[r:CALLS|HANDLER*1..3]->", any edge type when `edge_types` is empty
+fn relationship_pattern(edge_types: &[EdgeType], direction: Direction, hops: &str) -> String {
+    let types = edge_types
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("|");
+    let rel = if types.is_empty() {
+        format!("[r{}]", hops)
+    } else {
+        format!("[r:{}{}]", types, hops)
+    };
+    match direction {
+        Direction::Outgoing => format!("-{}->", rel),
+        Direction::Incoming => format!("<-{}-", rel),
+        Direction::Both => format!("-{}-", rel),
+    }
+}
+
+pub fn find_neighbors_query(
+    node_type: &NodeType,
+    node: &NodeData,
+    edge_types: &[EdgeType],
+    direction: Direction,
+) -> (String, HashMap<String, String>) {
+    let params = node_match_params("node", node);
+    let query = format!(
+        "MATCH (n:{} {{name: $node_name, file: $node_file, start: $node_start}}){}(m)
+         RETURN DISTINCT labels(m)[0] as node_type, m",
+        node_type.to_string(),
+        relationship_pattern(edge_types, direction, "")
+    );
+    (query, params)
+}
+
+pub fn transitive_calls_query(function: &NodeData) -> (String, HashMap<String, String>) {
+    let params = node_match_params("function", function);
+    let query =
+        "MATCH (f:Function {name: $function_name, file: $function_file, start: $function_start})-[:CALLS*1..]->(n:Function)
+         RETURN DISTINCT n";
+    (query.to_string(), params)
+}
+
+// "CALLS>|HANDLER>" for apoc.path.expandConfig, any edge type when `edge_types` is empty
+fn relationship_filter(edge_types: &[EdgeType], direction: Direction) -> String {
+    let (before, after) = match direction {
+        Direction::Outgoing => ("", ">"),
+        Direction::Incoming => ("<", ""),
+        Direction::Both => ("", ""),
+    };
+    if edge_types.is_empty() {
+        return format!("{}{}", before, after);
+    }
+    edge_types
+        .iter()
+        .map(|e| format!("{}{}{}", before, e.to_string(), after))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+// every node within `max_depth` edges of `start`, in breadth- or depth-first order,
+// each visited once at the depth it was first reached
+pub fn expand_query(
+    start: &Node,
+    edge_types: &[EdgeType],
+    direction: Direction,
+    max_depth: usize,
+    bfs: bool,
+) -> (String, HashMap<String, String>) {
+    let mut params = node_match_params("start", &start.node_data);
+    params.insert(
+        "filter".to_string(),
+        relationship_filter(edge_types, direction),
+    );
+    let max_level = if max_depth >= i64::MAX as usize {
+        -1
+    } else {
+        max_depth as i64
+    };
+    let query = format!(
+        "MATCH (a:{} {{name: $start_name, file: $start_file, start: $start_start}})
+         CALL apoc.path.expandConfig(a, {{
+             relationshipFilter: $filter,
+             minLevel: 1,
+             maxLevel: {},
+             uniqueness: 'NODE_GLOBAL',
+             bfs: {}
+         }})
+         YIELD path
+         WITH last(nodes(path)) as m, length(path) as depth
+         RETURN labels(m)[0] as node_type, m, depth",
+        start.node_type.to_string(),
+        max_level,
+        bfs
+    );
+    (query, params)
+}
+
+// functions with the highest value of one of the METRIC_KEYS
+pub fn hotspots_query(metric: &str, limit: usize) -> (String, HashMap<String, String>) {
+    let query = format!(
+        "MATCH (n:Function)
+         WHERE n.{metric} IS NOT NULL
+         RETURN n
+         ORDER BY toInteger(n.{metric}) DESC, n.name
+         LIMIT {limit}"
+    );
+    (query, HashMap::new())
+}
+
+pub fn shortest_path_query(
+    from: &Node,
+    to: &Node,
+    edge_types: &[EdgeType],
+    direction: Direction,
+    max_depth: usize,
+) -> (String, HashMap<String, String>) {
+    let mut params = node_match_params("from", &from.node_data);
+    params.extend(node_match_params("to", &to.node_data));
+    let hops = if max_depth == usize::MAX {
+        "*".to_string()
+    } else {
+        format!("*..{}", max_depth)
+    };
+    let query = format!(
+        "MATCH (a:{} {{name: $from_name, file: $from_file, start: $from_start}}),
+               (b:{} {{name: $to_name, file: $to_file, start: $to_start}}),
+               p = shortestPath((a){}(b))
+         UNWIND nodes(p) as m
+         RETURN labels(m)[0] as node_type, m",
+        from.node_type.to_string(),
+        to.node_type.to_string(),
+        relationship_pattern(edge_types, direction, &hops)
+    );
+    (query, params)
+}
+
 pub fn class_inherits_query() -> String {
     "MATCH (c:Class)
      WHERE c.meta IS NOT NULL
@@ -637,20 +918,6 @@ pub fn class_includes_query() -> String {
 }
 
 
-pub fn prefix_paths_query(root: &str) -> (String, HashMap<String, String>) {
-    let mut params = HashMap::new();
-    let root = if root.ends_with('/') { root.to_string() } else { format!("{}/", root) };
-    params.insert("root".to_string(), root.clone());
-    params.insert("root".to_string(), root.to_string());
-    
-    let query = "MATCH (n)
-                WHERE n.file IS NOT NULL AND NOT n.file STARTS WITH $root
-                SET n.file = $root + n.file";
-    
-    (query.to_string(), params)
-}
-
-
 pub fn add_node_with_parent_query(
     node_type: &NodeType,
     node_data: &NodeData,
@@ -899,6 +1166,12 @@ pub fn extract_node_data_from_neo4j_node(node: &neo4rs::Node) -> NodeData {
     let start = start_str.parse::<usize>().unwrap_or_default();
     let end_str = node.get::<String>("end").unwrap_or_default();
     let end = end_str.parse::<usize>().unwrap_or_default();
+    let parse_usize = |key: &str| {
+        node.get::<String>(key)
+            .unwrap_or_default()
+            .parse::<usize>()
+            .unwrap_or_default()
+    };
     let body = node.get::<String>("body").unwrap_or_default();
     let data_type = node.get::<String>("data_type").ok();
     let docs = node.get::<String>("docs").ok();
@@ -912,6 +1185,10 @@ pub fn extract_node_data_from_neo4j_node(node: &neo4rs::Node) -> NodeData {
         file,
         start,
         end,
+        start_col: parse_usize("start_col"),
+        end_col: parse_usize("end_col"),
+        start_byte: parse_usize("start_byte"),
+        end_byte: parse_usize("end_byte"),
         body,
         data_type,
         docs,
@@ -920,21 +1197,22 @@ pub fn extract_node_data_from_neo4j_node(node: &neo4rs::Node) -> NodeData {
     }
 }
 
+// Repository nodes are named "owner/repo" by the builder, so match on the
+// last two segments of the url
+fn repo_name_from_url(repo_url: &str) -> String {
+    let trimmed = repo_url.trim_end_matches('/').trim_end_matches(".git");
+    let mut parts = trimmed.rsplit(['/', ':']);
+    let name = parts.next().unwrap_or_default();
+    let owner = parts.next().unwrap_or_default();
+    format!("{}/{}", owner, name)
+}
+
 pub fn get_repository_hash_query(repo_url: &str) -> (String, HashMap<String, String>) {
     let mut params = HashMap::new();
-    
-    let repo_name = if repo_url.contains('/') {
-        let parts: Vec<&str> = repo_url.split('/').collect();
-        let name = parts.last().unwrap_or(&repo_url);
-        name.trim_end_matches(".git")
-    } else {
-        repo_url
-    };
-    
-    params.insert("repo_name".to_string(), repo_name.to_string());
+    params.insert("repo_name".to_string(), repo_name_from_url(repo_url));
     
     let query = "MATCH (r:Repository) 
-                 WHERE r.name CONTAINS $repo_name 
+                 WHERE r.name = $repo_name 
                  RETURN r.hash as hash";
     
     (query.to_string(), params)
@@ -942,33 +1220,95 @@ pub fn get_repository_hash_query(repo_url: &str) -> (String, HashMap<String, Str
 
 pub fn remove_nodes_by_file_query(file_path: &str) -> (String, HashMap<String, String>) {
     let mut params = HashMap::new();
-    let file_name = file_path.split('/').last().unwrap_or(file_path);
-    params.insert("file_name".to_string(), file_name.to_string());
+    params.insert("file_path".to_string(), file_path.to_string());
     
     let query = "
         MATCH (n)
-        WHERE n.file = $file_name OR n.file ENDS WITH $file_name
-        WITH DISTINCT n
-        OPTIONAL MATCH (n)-[r]-() 
-        DELETE r
-        WITH n
+        WHERE n.file = $file_path
         DETACH DELETE n
         RETURN count(n) as deleted
     ";
-    
-    
-    
+
+    (query.to_string(), params)
+}
+
+pub fn rename_file_query(from: &str, to: &str) -> (String, HashMap<String, String>) {
+    let mut params = HashMap::new();
+    params.insert("from".to_string(), from.to_string());
+    params.insert("to".to_string(), to.to_string());
+    params.insert("to_name".to_string(), to.split('/').last().unwrap_or(to).to_string());
+    // keys embed the sanitized file path
+    params.insert("from_key".to_string(), sanitize_string(from));
+    params.insert("to_key".to_string(), sanitize_string(to));
+
+    let query = "
+        MATCH (n)
+        WHERE n.file = $from
+        SET n.file = $to,
+            n.key = replace(n.key, $from_key, $to_key),
+            n.name = CASE WHEN n:File THEN $to_name ELSE n.name END
+        RETURN count(n) as renamed
+    ";
+
+    (query.to_string(), params)
+}
+
+pub fn remove_nodes_by_file_prefix_query(prefix: &str) -> (String, HashMap<String, String>) {
+    let mut params = HashMap::new();
+    params.insert("prefix".to_string(), prefix.to_string());
+
+    let query = "
+        MATCH (n)
+        WHERE n.file STARTS WITH $prefix
+        DETACH DELETE n
+        RETURN count(n) as deleted
+    ";
+
     (query.to_string(), params)
 }
 
 pub fn update_repository_hash_query(repo_name: &str, new_hash: &str) -> (String, HashMap<String, String>) {
     let mut params = HashMap::new();
-    params.insert("repo_name".to_string(), repo_name.to_string());
+    params.insert("repo_name".to_string(), repo_name_from_url(repo_name));
     params.insert("new_hash".to_string(), new_hash.to_string());
     
     let query = "MATCH (r:Repository) 
-                 WHERE r.name CONTAINS $repo_name 
+                 WHERE r.name = $repo_name 
                  SET r.hash = $new_hash";
     
     (query.to_string(), params)
-}
\ No newline at end of file
+}
+
+pub fn all_node_keys_query() -> String {
+    "MATCH (n)
+     RETURN n.key as key"
+        .to_string()
+}
+
+pub fn all_edge_keys_query() -> String {
+    "MATCH (source)-[r]->(target)
+     RETURN source.key as source, target.key as target, type(r) as edge_type"
+        .to_string()
+}
+
+pub fn update_endpoint_name_query(
+    old_name: &str,
+    file: &str,
+    new_name: &str,
+) -> (String, HashMap<String, String>) {
+    let mut params = HashMap::new();
+    params.insert("old_name".to_string(), old_name.to_string());
+    params.insert("file".to_string(), file.to_string());
+    params.insert("new_name".to_string(), new_name.to_string());
+    // keys start with the sanitized type and name
+    let endpoint = sanitize_string(&NodeType::Endpoint.to_string());
+    params.insert("old_key".to_string(), format!("{}-{}-", endpoint, sanitize_string(old_name)));
+    params.insert("new_key".to_string(), format!("{}-{}-", endpoint, sanitize_string(new_name)));
+
+    let query = "MATCH (n:Endpoint {name: $old_name, file: $file})
+                 WITH n LIMIT 1
+                 SET n.name = $new_name,
+                     n.key = replace(n.key, $old_key, $new_key)";
+
+    (query.to_string(), params)
+}
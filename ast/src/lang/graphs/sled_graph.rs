@@ -0,0 +1,1085 @@
+use super::keyed::{self, KeyedEdge, KeyedGraph};
+use super::{graph::Graph, *};
+use crate::lang::{Function, FunctionCall, Lang};
+use crate::utils::{create_node_key, create_node_key_from_ref, node_key_prefix, sanitize_string};
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+const NODES_TREE: &str = "nodes";
+const EDGES_TREE: &str = "edges";
+const QUALIFIED_TREE: &str = "qualified_names";
+const SEP: char = '\u{0}';
+
+// Same layout as BTreeMapGraph, but on disk:
+// nodes are keyed by create_node_key, edges by "src\0dst\0edge_type",
+// and each edge holds its call site (if any) as the value.
+// qualified_names indexes node keys as "qualified_name\0node_key"
+#[derive(Debug, Clone)]
+pub struct SledGraph {
+    db: sled::Db,
+    nodes: sled::Tree,
+    edges: sled::Tree,
+    qualified_names: sled::Tree,
+    // the first write that failed, returned by check_writes
+    failed: Arc<Mutex<Option<String>>>,
+}
+
+// NodeData serializes its meta flattened, so store the fields explicitly
+#[derive(Serialize, Deserialize)]
+struct StoredNode {
+    node_type: NodeType,
+    name: String,
+    file: String,
+    body: String,
+    start: usize,
+    end: usize,
+    start_col: usize,
+    end_col: usize,
+    start_byte: usize,
+    end_byte: usize,
+    docs: Option<String>,
+    hash: Option<String>,
+    data_type: Option<String>,
+    meta: BTreeMap<String, String>,
+}
+
+impl From<&Node> for StoredNode {
+    fn from(node: &Node) -> Self {
+        let nd = &node.node_data;
+        StoredNode {
+            node_type: node.node_type.clone(),
+            name: nd.name.clone(),
+            file: nd.file.clone(),
+            body: nd.body.clone(),
+            start: nd.start,
+            end: nd.end,
+            start_col: nd.start_col,
+            end_col: nd.end_col,
+            start_byte: nd.start_byte,
+            end_byte: nd.end_byte,
+            docs: nd.docs.clone(),
+            hash: nd.hash.clone(),
+            data_type: nd.data_type.clone(),
+            meta: nd.meta.clone(),
+        }
+    }
+}
+
+impl From<StoredNode> for Node {
+    fn from(s: StoredNode) -> Self {
+        Node::new(
+            s.node_type,
+            NodeData {
+                name: s.name,
+                file: s.file,
+                body: s.body,
+                start: s.start,
+                end: s.end,
+                start_col: s.start_col,
+                end_col: s.end_col,
+                start_byte: s.start_byte,
+                end_byte: s.end_byte,
+                docs: s.docs,
+                hash: s.hash,
+                data_type: s.data_type,
+                meta: s.meta,
+            },
+        )
+    }
+}
+
+impl SledGraph {
+    // open (or create) a graph persisted at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_db(sled::open(path)?)
+    }
+
+    fn temporary() -> Result<Self> {
+        Self::from_db(sled::Config::new().temporary(true).open()?)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Self> {
+        let nodes = db.open_tree(NODES_TREE)?;
+        let edges = db.open_tree(EDGES_TREE)?;
+        let qualified_names = db.open_tree(QUALIFIED_TREE)?;
+        Ok(Self {
+            db,
+            nodes,
+            edges,
+            qualified_names,
+            failed: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    // write the whole graph to `path`, replacing whatever was stored there
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<SledGraph> {
+        self.check_writes()?;
+        let saved = Self::open(path)?;
+        saved.nodes.clear()?;
+        saved.edges.clear()?;
+        saved.qualified_names.clear()?;
+        for kv in self.nodes.iter() {
+            let (k, v) = kv?;
+            saved.nodes.insert(k, v)?;
+        }
+        for kv in self.edges.iter() {
+            let (k, v) = kv?;
+            saved.edges.insert(k, v)?;
+        }
+        for kv in self.qualified_names.iter() {
+            let (k, v) = kv?;
+            saved.qualified_names.insert(k, v)?;
+        }
+        saved.flush()?;
+        Ok(saved)
+    }
+
+    pub fn flush(&self) -> Result<()> {
+        self.check_writes()?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    pub fn all_nodes(&self) -> Vec<Node> {
+        self.scan("").map(|(_, node)| node).collect()
+    }
+
+    pub fn all_edges(&self) -> Vec<Edge> {
+        self.edge_entries()
+            .filter_map(|(src, dst, edge_type, call_site)| {
+                let src_node = self.get_node(&src)?;
+                let dst_node = self.get_node(&dst)?;
+                Some(Edge {
+                    edge: edge_type,
+                    source: NodeRef {
+                        node_type: src_node.node_type,
+                        node_data: NodeKeys::from(&src_node.node_data),
+                    },
+                    target: NodeRef {
+                        node_type: dst_node.node_type,
+                        node_data: NodeKeys::from(&dst_node.node_data),
+                    },
+                    call_site,
+                })
+            })
+            .collect()
+    }
+
+    pub fn call_site(&self, src: &str, dst: &str, edge_type: &EdgeType) -> Option<CallSite> {
+        let key = Self::edge_key(src, dst, edge_type)?;
+        let value = self.edges.get(key).ok()??;
+        Self::decode_call_site(&value)
+    }
+
+    // keeps the first failed write, the Graph trait's writes can't return it
+    fn fail(&self, what: &str, e: impl Display) {
+        if let Ok(mut failed) = self.failed.lock() {
+            failed.get_or_insert_with(|| format!("sled: failed to {}: {}", what, e));
+        }
+    }
+
+    fn decode_node(bytes: &[u8]) -> Option<Node> {
+        match serde_json::from_slice::<StoredNode>(bytes) {
+            Ok(stored) => Some(stored.into()),
+            Err(e) => {
+                warn!("sled: failed to decode node: {}", e);
+                None
+            }
+        }
+    }
+
+    fn get_node(&self, key: &str) -> Option<Node> {
+        match self.nodes.get(key) {
+            Ok(Some(bytes)) => Self::decode_node(&bytes),
+            Ok(None) => None,
+            Err(e) => {
+                warn!("sled: failed to read node {}: {}", key, e);
+                None
+            }
+        }
+    }
+
+    fn contains_node(&self, key: &str) -> bool {
+        self.nodes.contains_key(key).unwrap_or(false)
+    }
+
+    fn insert_node(&self, key: &str, node: &Node) {
+        let bytes = match serde_json::to_vec(&StoredNode::from(node)) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                self.fail(&format!("encode node {}", key), e);
+                return;
+            }
+        };
+        if let Err(e) = self.nodes.insert(key, bytes) {
+            self.fail(&format!("write node {}", key), e);
+        }
+        self.index_node(key, node);
+    }
+
+    fn remove_node(&self, key: &str) {
+        match self.nodes.remove(key) {
+            Ok(Some(bytes)) => {
+                if let Some(q) =
+                    Self::decode_node(&bytes).and_then(|n| n.node_data.qualified_name().cloned())
+                {
+                    if let Err(e) = self.qualified_names.remove(Self::qualified_key(&q, key)) {
+                        self.fail(&format!("unindex node {}", key), e);
+                    }
+                }
+            }
+            Ok(None) => (),
+            Err(e) => self.fail(&format!("remove node {}", key), e),
+        }
+    }
+
+    fn qualified_key(qualified_name: &str, node_key: &str) -> String {
+        format!("{}{}{}", qualified_name, SEP, node_key)
+    }
+
+    fn index_node(&self, key: &str, node: &Node) {
+        if let Some(q) = node.node_data.qualified_name() {
+            if let Err(e) = self
+                .qualified_names
+                .insert(Self::qualified_key(q, key), Vec::new())
+            {
+                self.fail(&format!("index node {}", key), e);
+            }
+        }
+    }
+
+    // nodes whose key starts with `prefix`, in key order
+    fn scan<'a>(&'a self, prefix: &str) -> impl Iterator<Item = (String, Node)> + 'a {
+        Self::decode_entries(self.nodes.scan_prefix(prefix))
+    }
+
+    // nodes from `start` to the end of the tree, in key order
+    fn range_from<'a>(&'a self, start: &str) -> impl Iterator<Item = (String, Node)> + 'a {
+        Self::decode_entries(self.nodes.range(start.as_bytes().to_vec()..))
+    }
+
+    fn decode_entries<'a>(
+        iter: impl Iterator<Item = sled::Result<(sled::IVec, sled::IVec)>> + 'a,
+    ) -> impl Iterator<Item = (String, Node)> + 'a {
+        iter.filter_map(|kv| kv.ok()).filter_map(|(k, v)| {
+            let key = String::from_utf8(k.to_vec()).ok()?;
+            let node = Self::decode_node(&v)?;
+            Some((key, node))
+        })
+    }
+
+    fn edge_key(src: &str, dst: &str, edge_type: &EdgeType) -> Option<String> {
+        let et = serde_json::to_string(edge_type).ok()?;
+        Some(format!("{}{}{}{}{}", src, SEP, dst, SEP, et))
+    }
+
+    fn decode_edge(key: &[u8]) -> Option<(String, String, EdgeType)> {
+        let key = std::str::from_utf8(key).ok()?;
+        let mut parts = key.splitn(3, SEP);
+        let src = parts.next()?.to_string();
+        let dst = parts.next()?.to_string();
+        let edge_type = serde_json::from_str::<EdgeType>(parts.next()?).ok()?;
+        Some((src, dst, edge_type))
+    }
+
+    fn decode_call_site(value: &[u8]) -> Option<CallSite> {
+        if value.is_empty() {
+            return None;
+        }
+        serde_json::from_slice(value).ok()
+    }
+
+    // like BTreeMapGraph, an edge keeps the first call site it was added with
+    fn insert_edge(
+        &self,
+        src: &str,
+        dst: &str,
+        edge_type: &EdgeType,
+        call_site: Option<&CallSite>,
+    ) {
+        let Some(key) = Self::edge_key(src, dst, edge_type) else {
+            return;
+        };
+        if let Ok(Some(existing)) = self.edges.get(&key) {
+            if !existing.is_empty() || call_site.is_none() {
+                return;
+            }
+        }
+        let value = call_site
+            .and_then(|cs| serde_json::to_vec(cs).ok())
+            .unwrap_or_default();
+        if let Err(e) = self.edges.insert(key, value) {
+            self.fail("write edge", e);
+        }
+    }
+
+    fn remove_edge(&self, src: &str, dst: &str, edge_type: &EdgeType) {
+        if let Some(key) = Self::edge_key(src, dst, edge_type) {
+            if let Err(e) = self.edges.remove(key) {
+                self.fail("remove edge", e);
+            }
+        }
+    }
+
+    fn iter_edges<'a>(&'a self) -> impl Iterator<Item = (String, String, EdgeType)> + 'a {
+        self.edges
+            .iter()
+            .filter_map(|kv| kv.ok())
+            .filter_map(|(k, _)| Self::decode_edge(&k))
+    }
+
+    fn edge_entries<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (String, String, EdgeType, Option<CallSite>)> + 'a {
+        self.edges
+            .iter()
+            .filter_map(|kv| kv.ok())
+            .filter_map(|(k, v)| {
+                let (src, dst, edge_type) = Self::decode_edge(&k)?;
+                Some((src, dst, edge_type, Self::decode_call_site(&v)))
+            })
+    }
+
+    fn edges_from<'a>(
+        &'a self,
+        src: &str,
+    ) -> impl Iterator<Item = (String, String, EdgeType)> + 'a {
+        self.edges_with_prefix(&format!("{}{}", src, SEP))
+    }
+
+    // edges whose source key starts with `prefix`
+    fn edges_with_prefix<'a>(
+        &'a self,
+        prefix: &str,
+    ) -> impl Iterator<Item = (String, String, EdgeType)> + 'a {
+        self.edges
+            .scan_prefix(prefix)
+            .filter_map(|kv| kv.ok())
+            .filter_map(|(k, _)| Self::decode_edge(&k))
+    }
+
+    // the node an edge end points at: the node with that key, or else the
+    // first one with the same type, name and file
+    fn edge_node_key(&self, key: &str) -> Option<String> {
+        if self.contains_node(key) {
+            return Some(key.to_string());
+        }
+        let prefix = node_key_prefix(key)?;
+        self.nodes
+            .scan_prefix(&prefix)
+            .keys()
+            .filter_map(|k| k.ok())
+            .find_map(|k| String::from_utf8(k.to_vec()).ok())
+    }
+}
+
+impl Graph for SledGraph {
+    fn new() -> Self {
+        Self::temporary().expect("failed to open temporary sled graph")
+    }
+
+    fn try_new() -> Result<Self> {
+        Self::temporary()
+    }
+
+    fn check_writes(&self) -> Result<()> {
+        match self.failed.lock() {
+            Ok(failed) => match &*failed {
+                Some(e) => Err(anyhow!("{}", e)),
+                None => Ok(()),
+            },
+            Err(_) => Err(anyhow!("sled: write error lock poisoned")),
+        }
+    }
+
+    fn with_capacity(_nodes: usize, _edges: usize) -> Self
+    where
+        Self: Sized,
+    {
+        Self::default()
+    }
+    fn analysis(&self) {
+        for (src_key, dst_key, edge_type) in self.iter_edges() {
+            println!("Edge: {:?}: {:?} -> {:?}", edge_type, src_key, dst_key);
+        }
+        for (node_key, node) in self.scan("") {
+            println!(
+                "Node: {:?} type: {:?} file: {:?}",
+                node_key, node.node_type, node.node_data.file
+            );
+        }
+    }
+
+    fn extend_graph(&mut self, other: Self) {
+        if let Err(e) = other.check_writes() {
+            self.fail("build the extending graph", e);
+        }
+        for (tree, other_tree) in [
+            (&self.nodes, &other.nodes),
+            (&self.edges, &other.edges),
+            (&self.qualified_names, &other.qualified_names),
+        ] {
+            for kv in other_tree.iter() {
+                if let Err(e) = kv.and_then(|(k, v)| tree.insert(k, v)) {
+                    self.fail("extend graph", e);
+                }
+            }
+        }
+    }
+
+    fn get_graph_size(&self) -> (u32, u32) {
+        (self.nodes.len() as u32, self.edges.len() as u32)
+    }
+    fn add_edge(&mut self, edge: Edge) {
+        let source_key = create_node_key_from_ref(&edge.source);
+        let target_key = create_node_key_from_ref(&edge.target);
+        self.insert_edge(
+            &source_key,
+            &target_key,
+            &edge.edge,
+            edge.call_site.as_ref(),
+        );
+    }
+    fn add_node(&mut self, node_type: NodeType, node_data: NodeData) {
+        let node = Node::new(node_type, node_data);
+        let node_key = create_node_key(&node);
+        self.insert_node(&node_key, &node);
+    }
+
+    fn get_graph_keys(&self) -> (HashSet<String>, HashSet<String>) {
+        let node_keys: HashSet<String> = self.scan("").map(|(k, _)| k.to_lowercase()).collect();
+        let edge_keys: HashSet<String> = self
+            .iter_edges()
+            .map(|(src, dst, edge)| format!("{}-{}-{:?}", src, dst, edge).to_lowercase())
+            .collect();
+        (node_keys, edge_keys)
+    }
+
+    fn find_nodes_by_name(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
+        let prefix = format!(
+            "{}-{}",
+            sanitize_string(&node_type.to_string()),
+            sanitize_string(name)
+        );
+        self.scan(&prefix)
+            .filter(|(_, node)| node.node_data.name == name)
+            .map(|(_, node)| node.node_data)
+            .collect()
+    }
+    fn find_node_by_name_in_file(
+        &self,
+        node_type: NodeType,
+        name: &str,
+        file: &str,
+    ) -> Option<NodeData> {
+        let prefix = format!(
+            "{}-{}-{}",
+            sanitize_string(&node_type.to_string()),
+            sanitize_string(name),
+            sanitize_string(file)
+        );
+        self.scan(&prefix).map(|(_, node)| node.node_data).next()
+    }
+
+    fn add_node_with_parent(
+        &mut self,
+        node_type: NodeType,
+        node_data: NodeData,
+        parent_type: NodeType,
+        parent_file: &str,
+    ) {
+        self.add_node(node_type.clone(), node_data.clone());
+
+        let prefix = format!("{:?}-", parent_type).to_lowercase();
+        let parent = self
+            .scan(&prefix)
+            .find(|(_, n)| n.node_data.file == parent_file);
+        if let Some((_, parent_node)) = parent {
+            let edge = Edge::contains(parent_type, &parent_node.node_data, node_type, &node_data);
+            self.add_edge(edge);
+        }
+    }
+
+    fn create_filtered_graph(&self, final_filter: &[String]) -> Self {
+        let filtered = Self::new();
+
+        for (key, node) in self.scan("") {
+            if node.node_type == NodeType::Repository || final_filter.contains(&node.node_data.file)
+            {
+                filtered.insert_node(&key, &node);
+            }
+        }
+
+        for (src, dst, edge_type, call_site) in self.edge_entries() {
+            if let (Some(src_node), Some(dst_node)) = (self.get_node(&src), self.get_node(&dst)) {
+                if final_filter.contains(&src_node.node_data.file)
+                    || final_filter.contains(&dst_node.node_data.file)
+                {
+                    filtered.insert_edge(&src, &dst, &edge_type, call_site.as_ref());
+                }
+            }
+        }
+
+        filtered
+    }
+
+    fn find_nodes_in_range(&self, node_type: NodeType, row: u32, file: &str) -> Option<NodeData> {
+        let prefix = format!("{:?}-", node_type).to_lowercase();
+        self.scan(&prefix)
+            .find(|(_, node)| {
+                node.node_data.file == file
+                    && (node.node_data.start as u32) <= row
+                    && (node.node_data.end as u32) >= row
+            })
+            .map(|(_, node)| node.node_data)
+    }
+
+    fn find_node_at(&self, node_type: NodeType, file: &str, line: u32) -> Option<NodeData> {
+        let prefix = format!("{:?}-", node_type).to_lowercase();
+        self.scan(&prefix)
+            .find(|(_, node)| node.node_data.file == file && node.node_data.start == line as usize)
+            .map(|(_, node)| node.node_data)
+    }
+
+    fn find_node_by_name_and_file_end_with(
+        &self,
+        node_type: NodeType,
+        name: &str,
+        suffix: &str,
+    ) -> Option<NodeData> {
+        let prefix = format!(
+            "{}-{}-",
+            sanitize_string(&node_type.to_string()),
+            sanitize_string(name)
+        );
+        self.scan(&prefix)
+            .find(|(_, node)| node.node_data.file.ends_with(suffix))
+            .map(|(_, node)| node.node_data)
+    }
+
+    fn find_nodes_by_file_ends_with(&self, node_type: NodeType, file: &str) -> Vec<NodeData> {
+        let prefix = format!("{:?}-", node_type).to_lowercase();
+        self.scan(&prefix)
+            .filter(|(_, node)| node.node_data.file.ends_with(file))
+            .map(|(_, node)| node.node_data)
+            .collect()
+    }
+    fn find_source_edge_by_name_and_file(
+        &self,
+        edge_type: EdgeType,
+        target_name: &str,
+        target_file: &str,
+    ) -> Option<NodeKeys> {
+        for (src_key, dst_key, edge) in self.iter_edges() {
+            if edge == edge_type {
+                if let (Some(src_node), Some(dst_node)) =
+                    (self.get_node(&src_key), self.get_node(&dst_key))
+                {
+                    if dst_node.node_data.name == target_name
+                        && dst_node.node_data.file == target_file
+                    {
+                        return Some(NodeKeys::from(&src_node.node_data));
+                    }
+                }
+            }
+        }
+        None
+    }
+    fn add_instances(&mut self, instances: Vec<NodeData>) {
+        for inst in instances {
+            if let Some(of) = &inst.data_type {
+                if let Some(class_node_data) = self.find_nodes_by_name(NodeType::Class, of).first()
+                {
+                    self.add_node_with_parent(
+                        NodeType::Instance,
+                        inst.clone(),
+                        NodeType::File,
+                        &inst.file,
+                    );
+
+                    let edge = Edge::of(&inst, class_node_data);
+                    self.add_edge(edge);
+                }
+            }
+        }
+    }
+
+    fn add_functions(&mut self, functions: Vec<Function>) {
+        for (node, method_of, reqs, dms, trait_operand, return_types) in functions {
+            let node_clone = node.clone();
+            let func_node = Node::new(NodeType::Function, node);
+            let func_key = create_node_key(&func_node);
+            if !self.contains_node(&func_key) {
+                self.insert_node(&func_key, &func_node);
+            }
+
+            let file_prefix = format!("{:?}-", NodeType::File).to_lowercase();
+
+            let file = self
+                .range_from(&file_prefix)
+                .find(|(_, n)| n.node_data.file == node_clone.file);
+            if let Some((_, file_node)) = file {
+                let edge = Edge::contains(
+                    NodeType::File,
+                    &file_node.node_data,
+                    NodeType::Function,
+                    &node_clone,
+                );
+                self.add_edge(edge);
+            }
+
+            if let Some(p) = method_of {
+                self.add_edge(p.into());
+            }
+
+            if let Some(to) = trait_operand {
+                self.add_edge(to);
+            }
+
+            for rt in return_types {
+                self.add_edge(rt);
+            }
+
+            for r in reqs {
+                let req_node = Node::new(NodeType::Request, r.clone());
+                let req_key = create_node_key(&req_node);
+                if !self.contains_node(&req_key) {
+                    self.insert_node(&req_key, &req_node);
+                }
+
+                let edge = Edge::calls(NodeType::Function, &node_clone, NodeType::Request, &r);
+                self.add_edge(edge);
+            }
+
+            for dm in dms {
+                self.add_edge(dm);
+            }
+        }
+    }
+
+    fn add_page(&mut self, page: (NodeData, Option<Edge>)) {
+        let (page_data, edge_opt) = page;
+        self.add_node(NodeType::Page, page_data);
+
+        if let Some(edge) = edge_opt {
+            self.add_edge(edge);
+        }
+    }
+
+    fn add_pages(&mut self, pages: Vec<(NodeData, Vec<Edge>)>) {
+        for (page_data, edges) in pages {
+            self.add_node(NodeType::Page, page_data);
+
+            for edge in edges {
+                self.add_edge(edge);
+            }
+        }
+    }
+
+    fn find_endpoint(&self, name: &str, file: &str, verb: &str) -> Option<NodeData> {
+        let prefix = format!(
+            "{}-{}-{}",
+            &sanitize_string(&format!("{:?}", NodeType::Endpoint)),
+            sanitize_string(name),
+            sanitize_string(file)
+        );
+        self.scan(&prefix)
+            .find(|(_, node)| node.node_data.meta.get("verb") == Some(&verb.to_string()))
+            .map(|(_, node)| node.node_data)
+    }
+
+    fn add_endpoints(&mut self, endpoints: Vec<(NodeData, Option<Edge>)>) {
+        for (endpoint_data, handler_edge) in endpoints {
+            if endpoint_data.meta.contains_key("handler") {
+                let default_verb = "".to_string();
+                let verb = endpoint_data.meta.get("verb").unwrap_or(&default_verb);
+
+                if self
+                    .find_endpoint(&endpoint_data.name, &endpoint_data.file, verb)
+                    .is_some()
+                {
+                    continue;
+                }
+
+                self.add_node(NodeType::Endpoint, endpoint_data);
+
+                if let Some(edge) = handler_edge {
+                    self.add_edge(edge);
+                }
+            }
+        }
+    }
+
+    fn add_test_node(&mut self, test_data: NodeData, test_type: NodeType, test_edge: Option<Edge>) {
+        self.add_node_with_parent(
+            test_type,
+            test_data.clone(),
+            NodeType::File,
+            &test_data.file,
+        );
+
+        if let Some(edge) = test_edge {
+            self.add_edge(edge);
+        }
+    }
+    fn add_calls(&mut self, calls: (Vec<FunctionCall>, Vec<FunctionCall>, Vec<Edge>)) {
+        keyed::add_calls(self, calls)
+    }
+    fn process_endpoint_groups(&mut self, eg: Vec<NodeData>, lang: &Lang) -> Result<()> {
+        keyed::process_endpoint_groups(self, eg, lang)?;
+        self.check_writes()
+    }
+    fn class_includes(&mut self) {
+        let class_nodes: Vec<_> = self
+            .scan("")
+            .filter(|(_, n)| n.node_type == NodeType::Class)
+            .collect();
+
+        for (_, node) in class_nodes {
+            if let Some(includes) = node.node_data.meta.get("includes") {
+                let modules = includes.split(',').map(|m| m.trim());
+                for module in modules {
+                    if let Some(module_node) =
+                        self.find_nodes_by_name(NodeType::Class, module).first()
+                    {
+                        let edge = Edge::class_imports(&node.node_data, module_node);
+                        self.add_edge(edge);
+                    }
+                }
+            }
+        }
+    }
+    fn class_inherits(&mut self) {
+        let class_nodes: Vec<_> = self
+            .scan("")
+            .filter(|(_, n)| n.node_type == NodeType::Class)
+            .collect();
+
+        for (_, node) in class_nodes {
+            if let Some(parent) = node.node_data.meta.get("parent") {
+                if let Some(parent_node) = self.find_nodes_by_name(NodeType::Class, parent).first()
+                {
+                    let edge = Edge::parent_of(parent_node, &node.node_data);
+                    self.add_edge(edge);
+                }
+            }
+        }
+    }
+    fn get_data_models_within(&mut self, lang: &Lang) {
+        let prefix = format!("{:?}-", NodeType::DataModel).to_lowercase();
+
+        let data_model_nodes: Vec<NodeData> =
+            self.scan(&prefix).map(|(_, node)| node.node_data).collect();
+
+        for data_model in data_model_nodes {
+            let edges = lang.lang().data_model_within_finder(&data_model, &|file| {
+                self.find_nodes_by_file_ends_with(NodeType::Function, file)
+            });
+
+            for edge in edges {
+                self.add_edge(edge);
+            }
+        }
+    }
+
+    fn filter_out_nodes_without_children(
+        &mut self,
+        parent_type: NodeType,
+        child_type: NodeType,
+        child_meta_key: &str,
+    ) {
+        let mut has_children: BTreeMap<String, bool> = BTreeMap::new();
+
+        let parent_prefix = format!("{:?}-", parent_type).to_lowercase();
+        for (_, node) in self.scan(&parent_prefix) {
+            has_children.insert(node.node_data.name.clone(), false);
+        }
+
+        let child_prefix = format!("{:?}-", child_type).to_lowercase();
+        for (_, node) in self.scan(&child_prefix) {
+            if let Some(parent_name) = node.node_data.meta.get(child_meta_key) {
+                if let Some(entry) = has_children.get_mut(parent_name) {
+                    *entry = true;
+                }
+            }
+        }
+        let nodes_to_remove: Vec<_> = self
+            .scan(&parent_prefix)
+            .filter(|(_, node)| !has_children.get(&node.node_data.name).unwrap_or(&true))
+            .map(|(k, _)| k)
+            .collect();
+
+        if nodes_to_remove.is_empty() {
+            return;
+        }
+        let removed: HashSet<String> = nodes_to_remove.into_iter().collect();
+        for key in &removed {
+            self.remove_node(key);
+        }
+        let edges_to_remove: Vec<_> = self
+            .iter_edges()
+            .filter(|(src, dst, _)| removed.contains(src) || removed.contains(dst))
+            .collect();
+        for (src, dst, edge) in edges_to_remove {
+            self.remove_edge(&src, &dst, &edge);
+        }
+    }
+
+    fn prefix_paths(&mut self, root: &str) {
+        keyed::prefix_paths(self, root)
+    }
+
+    fn find_nodes_by_qualified_name(
+        &self,
+        node_type: NodeType,
+        qualified_name: &str,
+    ) -> Vec<NodeData> {
+        // a key can be left behind when its node was replaced
+        self.qualified_names
+            .scan_prefix(format!("{}{}", qualified_name, SEP))
+            .keys()
+            .filter_map(|k| k.ok())
+            .filter_map(|k| String::from_utf8(k.to_vec()).ok())
+            .filter_map(|k| {
+                let node_key = k.rsplit(SEP).next()?;
+                self.get_node(node_key)
+            })
+            .filter(|n| {
+                n.node_type == node_type
+                    && n.node_data.qualified_name().map(|q| q.as_str()) == Some(qualified_name)
+            })
+            .map(|n| n.node_data)
+            .collect()
+    }
+
+    fn find_nodes_by_name_contains(&self, node_type: NodeType, name: &str) -> Vec<NodeData> {
+        let prefix = format!("{:?}-", node_type).to_lowercase();
+        self.scan(&prefix)
+            .take_while(|(_, n)| n.node_data.name.contains(name))
+            .map(|(_, node)| node.node_data)
+            .collect()
+    }
+
+    fn find_resource_nodes(&self, node_type: NodeType, verb: &str, path: &str) -> Vec<NodeData> {
+        let prefix = format!("{:?}-", node_type).to_lowercase();
+        self.scan(&prefix)
+            .filter(|(_, node)| {
+                let node_data = &node.node_data;
+
+                // Check if path matches
+                let path_matches = node_data.name.contains(path);
+
+                // Check if verb matches (if present in metadata)
+                let verb_matches = match node_data.meta.get("verb") {
+                    Some(node_verb) => node_verb.to_uppercase() == verb.to_uppercase(),
+                    None => true, // If no verb in metadata, don't filter on it
+                };
+
+                path_matches && verb_matches
+            })
+            .map(|(_, node)| node.node_data)
+            .collect()
+    }
+
+    fn find_handlers_for_endpoint(&self, endpoint: &NodeData) -> Vec<NodeData> {
+        let endpoint = Node::new(NodeType::Endpoint, endpoint.clone());
+        let endpoint_key = create_node_key(&endpoint);
+
+        self.edges_from(&endpoint_key)
+            .filter(|(_, _, edge_type)| *edge_type == EdgeType::Handler)
+            .filter_map(|(_, dst, _)| self.get_node(&dst))
+            .map(|node| node.node_data)
+            .collect()
+    }
+
+    fn check_direct_data_model_usage(&self, function_name: &str, data_model: &str) -> bool {
+        for (src_key, dst_key, edge_type) in self.iter_edges() {
+            if edge_type == EdgeType::Contains {
+                if let (Some(src_node), Some(dst_node)) =
+                    (self.get_node(&src_key), self.get_node(&dst_key))
+                {
+                    if src_node.node_data.name == function_name
+                        && dst_node.node_data.name.contains(data_model)
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        false
+    }
+
+    fn find_functions_called_by(&self, function: &NodeData) -> Vec<NodeData> {
+        let function_prefix = format!(
+            "{:?}-{}-{}",
+            NodeType::Function,
+            sanitize_string(&function.name),
+            sanitize_string(&function.file)
+        )
+        .to_lowercase();
+
+        self.edges
+            .scan_prefix(&function_prefix)
+            .filter_map(|kv| kv.ok())
+            .filter_map(|(k, _)| Self::decode_edge(&k))
+            .filter(|(_, _, edge_type)| *edge_type == EdgeType::Calls)
+            .filter_map(|(_, dst, _)| self.get_node(&dst))
+            .map(|node| node.node_data)
+            .collect()
+    }
+
+    fn find_nodes_by_type(&self, node_type: NodeType) -> Vec<NodeData> {
+        let prefix = format!("{:?}-", node_type).to_lowercase();
+        self.scan(&prefix).map(|(_, node)| node.node_data).collect()
+    }
+
+    fn find_nodes_with_edge_type(
+        &self,
+        source_type: NodeType,
+        target_type: NodeType,
+        edge_type: EdgeType,
+    ) -> Vec<(NodeData, NodeData)> {
+        let source_prefix = format!("{:?}-", source_type).to_lowercase();
+        let target_prefix = format!("{:?}-", target_type).to_lowercase();
+
+        self.edges
+            .scan_prefix(&source_prefix)
+            .filter_map(|kv| kv.ok())
+            .filter_map(|(k, _)| Self::decode_edge(&k))
+            .filter(|(_, dst_key, edge)| *edge == edge_type && dst_key.starts_with(&target_prefix))
+            .filter_map(|(src_key, dst_key, _)| {
+                let src_node = self.get_node(&src_key)?;
+                let dst_node = self.get_node(&dst_key)?;
+                Some((src_node.node_data, dst_node.node_data))
+            })
+            .collect()
+    }
+    fn find_neighbors(
+        &self,
+        node_type: NodeType,
+        node: &NodeData,
+        edge_types: &[EdgeType],
+        direction: Direction,
+    ) -> Vec<Node> {
+        let key = create_node_key(&Node::new(node_type, node.clone()));
+        let Some(prefix) = node_key_prefix(&key) else {
+            return Vec::new();
+        };
+        // edge ends without a node of their own resolve to the first node
+        // with the same type, name and file, so they only point here if that's us
+        let owns_prefix = self.edge_node_key(&prefix).as_ref() == Some(&key);
+        let is_node = |k: &String| {
+            *k == key || (owns_prefix && k.starts_with(&prefix) && !self.contains_node(k))
+        };
+        let mut others = Vec::new();
+        if direction.outgoing() {
+            others.extend(
+                self.edges_with_prefix(&prefix)
+                    .filter(|(src, _, _)| is_node(src))
+                    .map(|(_, dst, e)| (dst, e)),
+            );
+        }
+        if direction.incoming() {
+            others.extend(
+                self.iter_edges()
+                    .filter(|(_, dst, _)| is_node(dst))
+                    .map(|(src, _, e)| (src, e)),
+            );
+        }
+        let mut seen = HashSet::new();
+        others
+            .into_iter()
+            .filter(|(_, e)| edge_types.is_empty() || edge_types.contains(e))
+            .filter_map(|(k, _)| self.edge_node_key(&k))
+            .filter(|k| seen.insert(k.clone()))
+            .filter_map(|k| self.get_node(&k))
+            .collect()
+    }
+    fn get_edges(&self) -> Vec<Edge> {
+        self.edge_entries()
+            .filter_map(|(src, dst, edge_type, call_site)| {
+                let src_node = self.get_node(&self.edge_node_key(&src)?)?;
+                let dst_node = self.get_node(&self.edge_node_key(&dst)?)?;
+                Some(Edge {
+                    edge: edge_type,
+                    source: NodeRef {
+                        node_type: src_node.node_type,
+                        node_data: NodeKeys::from(&src_node.node_data),
+                    },
+                    target: NodeRef {
+                        node_type: dst_node.node_type,
+                        node_data: NodeKeys::from(&dst_node.node_data),
+                    },
+                    call_site,
+                })
+            })
+            .collect()
+    }
+
+    fn count_edges_of_type(&self, edge_type: EdgeType) -> usize {
+        self.iter_edges()
+            .filter(|(_, _, edge)| *edge == edge_type)
+            .count()
+    }
+}
+
+impl KeyedGraph for SledGraph {
+    fn contains_key(&self, key: &str) -> bool {
+        self.contains_node(key)
+    }
+
+    fn get_keyed(&self, key: &str) -> Option<Node> {
+        self.get_node(key)
+    }
+
+    fn insert_keyed(&mut self, key: String, node: Node) {
+        self.insert_node(&key, &node)
+    }
+
+    fn remove_keyed(&mut self, key: &str) {
+        self.remove_node(key)
+    }
+
+    fn keyed_with_prefix(&self, prefix: &str) -> Vec<(String, Node)> {
+        self.scan(prefix).collect()
+    }
+
+    fn keyed_edges_from(&self, src: &str) -> Vec<KeyedEdge> {
+        self.edges_from(src)
+            .map(|(s, d, e)| {
+                let call_site = self.call_site(&s, &d, &e);
+                (s, d, e, call_site)
+            })
+            .collect()
+    }
+
+    fn insert_keyed_edge(&mut self, (src, dst, edge_type, call_site): KeyedEdge) {
+        self.insert_edge(&src, &dst, &edge_type, call_site.as_ref())
+    }
+
+    fn remove_keyed_edge(&mut self, src: &str, dst: &str, edge_type: &EdgeType) {
+        self.remove_edge(src, dst, edge_type)
+    }
+
+    fn drain_keyed(&mut self) -> (Vec<(String, Node)>, Vec<KeyedEdge>) {
+        let nodes = self.scan("").collect();
+        let edges = self.edge_entries().collect();
+        for tree in [&self.nodes, &self.edges, &self.qualified_names] {
+            if let Err(e) = tree.clear() {
+                self.fail("clear graph", e);
+            }
+        }
+        (nodes, edges)
+    }
+}
+
+impl Default for SledGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+use crate::builder::root_less_tmp;
+use crate::gat::{get_changed_lines, is_head, ChangedLines};
+use crate::lang::graphs::{EdgeType, Graph, NodeId, NodeRef, NodeType};
+use crate::repo::Repo;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use tracing::info;
+
+// node types a changed line range can land in
+const CHANGED_TYPES: [NodeType; 7] = [
+    NodeType::Function,
+    NodeType::Class,
+    NodeType::DataModel,
+    NodeType::Endpoint,
+    NodeType::Page,
+    NodeType::Test,
+    NodeType::E2eTest,
+];
+
+// walked backwards, from a change to whatever depends on it
+const IMPACT_EDGES: [EdgeType; 4] = [
+    EdgeType::Calls,
+    EdgeType::Handler,
+    EdgeType::Renders,
+    EdgeType::Contains,
+];
+
+// every node is contained by its file, so these would reach everything
+const STRUCTURAL_TYPES: [NodeType; 4] = [
+    NodeType::Repository,
+    NodeType::Language,
+    NodeType::Directory,
+    NodeType::File,
+];
+
+// something affected by the diff, and how the change reaches it
+#[derive(Debug, Clone, Serialize)]
+pub struct Impact {
+    pub node: NodeRef,
+    // from the changed node to `node`, both included
+    pub path: Vec<NodeRef>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImpactReport {
+    pub changed: Vec<NodeRef>,
+    pub endpoints: Vec<Impact>,
+    pub pages: Vec<Impact>,
+    pub tests: Vec<Impact>,
+    pub e2e_tests: Vec<Impact>,
+}
+
+impl ImpactReport {
+    // the files of every impacted test, for picking what to run
+    pub fn test_files(&self) -> Vec<String> {
+        let mut files = self
+            .tests
+            .iter()
+            .chain(self.e2e_tests.iter())
+            .map(|t| t.node.node_data.file.clone())
+            .collect::<Vec<_>>();
+        files.sort();
+        files.dedup();
+        files
+    }
+}
+
+// The nodes touched by `changes`, then everything that reaches them through
+// Calls, Handler, Renders or Contains edges. Each impacted node keeps the
+// shortest path back to a changed node. Diff files are relative to
+// `repo_path`, graph files are prefixed with it.
+pub fn analyze_impact<G: Graph>(
+    graph: &G,
+    repo_path: &str,
+    changes: &[ChangedLines],
+) -> ImpactReport {
+    let root = root_less_tmp(repo_path.trim_end_matches('/'));
+    let files = changes
+        .iter()
+        .map(|c| format!("{}/{}", root, c.file))
+        .collect::<Vec<_>>();
+    let mut changed = Vec::new();
+    for nt in CHANGED_TYPES.iter() {
+        for nd in graph.find_nodes_by_type(nt.clone()) {
+            let touched = changes
+                .iter()
+                .zip(files.iter())
+                .any(|(c, file)| &nd.file == file && c.overlaps(nd.start, nd.end));
+            if touched {
+                changed.push(NodeRef::from((&nd).into(), nt.clone()));
+            }
+        }
+    }
+
+    // the other end of each node's incoming edges, gathered in one pass
+    let mut incoming: BTreeMap<NodeId, Vec<NodeRef>> = BTreeMap::new();
+    for edge in graph.get_edges() {
+        if IMPACT_EDGES.contains(&edge.edge) && !STRUCTURAL_TYPES.contains(&edge.source.node_type) {
+            incoming
+                .entry(edge.target.id())
+                .or_default()
+                .push(edge.source);
+        }
+    }
+    for sources in incoming.values_mut() {
+        sources.sort_by_key(NodeRef::id);
+    }
+
+    // multi-source bfs, remembering where each node was reached from
+    let mut parents: BTreeMap<NodeId, Option<NodeRef>> = BTreeMap::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+    for node in &changed {
+        if parents.insert(node.id(), None).is_none() {
+            order.push(node.clone());
+            queue.push_back(node.clone());
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        for next in incoming.get(&node.id()).into_iter().flatten() {
+            if parents.contains_key(&next.id()) {
+                continue;
+            }
+            parents.insert(next.id(), Some(node.clone()));
+            order.push(next.clone());
+            queue.push_back(next.clone());
+        }
+    }
+
+    let path_to = |node: &NodeRef| {
+        let mut path = vec![node.clone()];
+        let mut current = node.id();
+        while let Some(Some(parent)) = parents.get(&current) {
+            path.push(parent.clone());
+            current = parent.id();
+        }
+        path.reverse();
+        path
+    };
+
+    let mut report = ImpactReport {
+        changed: changed.clone(),
+        ..Default::default()
+    };
+    for node in &order {
+        let list = match node.node_type {
+            NodeType::Endpoint => &mut report.endpoints,
+            NodeType::Page => &mut report.pages,
+            NodeType::Test => &mut report.tests,
+            NodeType::E2eTest => &mut report.e2e_tests,
+            _ => continue,
+        };
+        list.push(Impact {
+            node: node.clone(),
+            path: path_to(node),
+        });
+    }
+    info!(
+        "{} changed nodes impact {} endpoints, {} pages, {} tests and {} e2e tests",
+        report.changed.len(),
+        report.endpoints.len(),
+        report.pages.len(),
+        report.tests.len(),
+        report.e2e_tests.len()
+    );
+    report
+}
+
+// builds the graph of the repo at `repo_path` as it is checked out, and
+// finds what the diff between two revs (the second defaults to HEAD) affects.
+// the graph only matches the second rev if that is what's checked out
+pub async fn impact_from_revs(repo_path: &str, mut revs: Vec<String>) -> Result<ImpactReport> {
+    if revs.is_empty() {
+        return Err(anyhow!("impact analysis needs at least one rev"));
+    }
+    if revs.len() == 1 {
+        revs.push("HEAD".into());
+    }
+    if !is_head(repo_path, &revs[1])? {
+        return Err(anyhow!(
+            "{} is not checked out, so the graph would not match the diff",
+            revs[1]
+        ));
+    }
+    let changes = get_changed_lines(repo_path, &revs[0], &revs[1])?;
+    let repos = Repo::new_multi_detect(repo_path, None, Vec::new(), Vec::new()).await?;
+    let graph = repos.build_graphs().await?;
+    Ok(analyze_impact(&graph, repo_path, &changes))
+}
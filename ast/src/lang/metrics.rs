@@ -0,0 +1,259 @@
+use crate::lang::graphs::{Graph, NodeType};
+use crate::lang::NodeData;
+use serde::Serialize;
+use tree_sitter::Node as TreeNode;
+
+// meta keys, in the order of the FunctionMetrics fields
+pub const METRIC_KEYS: [&str; 6] = [
+    "complexity",
+    "nesting_depth",
+    "param_count",
+    "loc",
+    "call_count",
+    "return_count",
+];
+
+// each one adds a path through the function
+const BRANCH_KINDS: [&str; 29] = [
+    "if_statement",
+    "if_expression",
+    "if",
+    "elif_clause",
+    "elsif",
+    "unless",
+    "guard_statement",
+    "when",
+    "match_arm",
+    "switch_case",
+    "case_clause",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "switch_label",
+    "switch_section",
+    "for_statement",
+    "for_in_statement",
+    "for_expression",
+    "enhanced_for_statement",
+    "foreach_statement",
+    "for",
+    "while_statement",
+    "while_expression",
+    "while",
+    "until",
+    "do_statement",
+    "catch_clause",
+    "except_clause",
+];
+
+// branches that add no nesting
+const CONDITIONAL_KINDS: [&str; 4] = [
+    "conditional_expression",
+    "ternary_expression",
+    "conditional",
+    "rescue",
+];
+
+const BOOLEAN_OPERATORS: [&str; 4] = ["&&", "||", "and", "or"];
+
+// blocks that count towards the nesting depth
+const NESTING_KINDS: [&str; 27] = [
+    "if_statement",
+    "if_expression",
+    "if",
+    "unless",
+    "guard_statement",
+    "match_expression",
+    "match_statement",
+    "switch_statement",
+    "switch_expression",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+    "case",
+    "for_statement",
+    "for_in_statement",
+    "for_expression",
+    "enhanced_for_statement",
+    "foreach_statement",
+    "for",
+    "while_statement",
+    "while_expression",
+    "while",
+    "until",
+    "loop_expression",
+    "do_statement",
+    "try_statement",
+    "begin",
+];
+
+const CALL_KINDS: [&str; 7] = [
+    "call_expression",
+    "call",
+    "method_invocation",
+    "invocation_expression",
+    "function_call_expression",
+    "member_call_expression",
+    "scoped_call_expression",
+];
+
+const RETURN_KINDS: [&str; 3] = ["return_statement", "return_expression", "return"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FunctionMetrics {
+    // 1 + branches, loops, catches and boolean operators
+    pub complexity: usize,
+    pub nesting_depth: usize,
+    pub param_count: usize,
+    // non-blank lines
+    pub loc: usize,
+    pub call_count: usize,
+    pub return_count: usize,
+}
+
+impl FunctionMetrics {
+    pub fn values(&self) -> [usize; 6] {
+        [
+            self.complexity,
+            self.nesting_depth,
+            self.param_count,
+            self.loc,
+            self.call_count,
+            self.return_count,
+        ]
+    }
+    pub fn from_meta(nd: &NodeData) -> Option<Self> {
+        let get = |key: &str| nd.meta.get(key)?.parse::<usize>().ok();
+        Some(Self {
+            complexity: get(METRIC_KEYS[0])?,
+            nesting_depth: get(METRIC_KEYS[1])?,
+            param_count: get(METRIC_KEYS[2])?,
+            loc: get(METRIC_KEYS[3])?,
+            call_count: get(METRIC_KEYS[4])?,
+            return_count: get(METRIC_KEYS[5])?,
+        })
+    }
+}
+
+fn is_boolean_operator(node: TreeNode, code: &str) -> bool {
+    if !node.kind().contains("binary") && node.kind() != "boolean_operator" {
+        return false;
+    }
+    node.child_by_field_name("operator")
+        .and_then(|op| op.utf8_text(code.as_bytes()).ok())
+        .is_some_and(|op| BOOLEAN_OPERATORS.contains(&op))
+}
+
+// an `else if` continues its chain instead of nesting deeper. an if that is
+// another if's body, like java's braceless `if (a) if (b)`, still nests
+fn is_else_if(node: TreeNode) -> bool {
+    if !node.kind().starts_with("if") {
+        return false;
+    }
+    node.parent().is_some_and(|p| {
+        p.kind().contains("else") || p.child_by_field_name("alternative") == Some(node)
+    })
+}
+
+// `parameters` field, or the first parameter list above the body
+fn find_parameters(node: TreeNode, depth: usize) -> Option<TreeNode> {
+    if let Some(params) = node.child_by_field_name("parameters") {
+        return Some(params);
+    }
+    if depth == 0 {
+        return None;
+    }
+    let mut cursor = node.walk();
+    let children = node.named_children(&mut cursor).collect::<Vec<_>>();
+    for child in children {
+        let kind = child.kind();
+        if kind.contains("parameter") && (kind.ends_with('s') || kind.ends_with("list")) {
+            return Some(child);
+        }
+        if kind.contains("block") || kind.contains("body") {
+            continue;
+        }
+        if let Some(params) = find_parameters(child, depth - 1) {
+            return Some(params);
+        }
+    }
+    None
+}
+
+// go's `a, b int` is one declaration with two names
+fn count_parameters(params: TreeNode) -> usize {
+    let mut cursor = params.walk();
+    let children = params.named_children(&mut cursor).collect::<Vec<_>>();
+    children
+        .into_iter()
+        .filter(|c| !c.kind().contains("comment"))
+        .map(|c| {
+            let mut cursor = c.walk();
+            c.children_by_field_name("name", &mut cursor).count().max(1)
+        })
+        .sum()
+}
+
+// Metrics over the tree-sitter node of a function definition. Closures
+// count towards the function that holds them.
+pub fn function_metrics(node: TreeNode, code: &str) -> FunctionMetrics {
+    function_metrics_with(node, code, &[])
+}
+
+// like function_metrics, with a language's own node kinds that each add
+// a path through the function
+pub fn function_metrics_with(node: TreeNode, code: &str, branches: &[&str]) -> FunctionMetrics {
+    let mut metrics = FunctionMetrics {
+        complexity: 1,
+        param_count: find_parameters(node, 3).map(count_parameters).unwrap_or(0),
+        loc: node
+            .utf8_text(code.as_bytes())
+            .unwrap_or_default()
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .count(),
+        ..Default::default()
+    };
+    let mut stack = vec![(node, 0)];
+    while let Some((n, depth)) = stack.pop() {
+        let kind = n.kind();
+        if n.is_named() {
+            if BRANCH_KINDS.contains(&kind)
+                || branches.contains(&kind)
+                || CONDITIONAL_KINDS.contains(&kind)
+                || is_boolean_operator(n, code)
+            {
+                metrics.complexity += 1;
+            }
+            if CALL_KINDS.contains(&kind) {
+                metrics.call_count += 1;
+            }
+            if RETURN_KINDS.contains(&kind) {
+                metrics.return_count += 1;
+            }
+        }
+        let depth = if n.is_named() && NESTING_KINDS.contains(&kind) && !is_else_if(n) {
+            depth + 1
+        } else {
+            depth
+        };
+        metrics.nesting_depth = metrics.nesting_depth.max(depth);
+        let mut cursor = n.walk();
+        stack.extend(n.children(&mut cursor).map(|c| (c, depth)));
+    }
+    metrics
+}
+
+// the functions with the highest value of a metric, like "complexity"
+pub fn hotspots<G: Graph>(graph: &G, metric: &str, limit: usize) -> Vec<NodeData> {
+    let mut funcs = graph
+        .find_nodes_by_type(NodeType::Function)
+        .into_iter()
+        .filter_map(|f| {
+            let value = f.meta.get(metric)?.parse::<usize>().ok()?;
+            Some((value, f))
+        })
+        .collect::<Vec<_>>();
+    funcs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    funcs.into_iter().take(limit).map(|(_, f)| f).collect()
+}
@@ -1,6 +1,11 @@
 pub mod asg;
+pub mod dead_code;
+pub mod dependencies;
+pub mod features;
 pub mod graphs;
+pub mod impact;
 pub mod linker;
+pub mod metrics;
 pub mod parse;
 pub mod queries;
 
@@ -9,16 +14,23 @@ use asg::*;
 use consts::*;
 pub use graphs::*;
 use lsp::{CmdSender, Language};
+use parse::ranges_nest;
 use queries::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
+use std::sync::RwLock;
 use streaming_iterator::{IntoStreamingIterator, StreamingIterator};
 use tracing::trace;
-use tree_sitter::{Node as TreeNode, Query, QueryCursor, QueryMatch};
+use tree_sitter::{Node as TreeNode, Query, QueryCursor, QueryMatch, Tree};
 
 pub struct Lang {
     pub kind: Language,
     lang: Box<dyn Stack + Send + Sync + 'static>,
+    // parsed trees by filename, with the hash of the code they were parsed from
+    trees: RwLock<HashMap<String, (u64, Tree)>>,
 }
 
 impl fmt::Display for Lang {
@@ -44,72 +56,105 @@ impl Lang {
         Self {
             kind: Language::Python,
             lang: Box::new(python::Python::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_go() -> Self {
         Self {
             kind: Language::Go,
             lang: Box::new(go::Go::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_rust() -> Self {
         Self {
             kind: Language::Rust,
             lang: Box::new(rust::Rust::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_react() -> Self {
         Self {
             kind: Language::React,
             lang: Box::new(react::ReactTs::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_typescript() -> Self {
         Self {
             kind: Language::Typescript,
             lang: Box::new(typescript::TypeScript::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_ruby() -> Self {
         Self {
             kind: Language::Ruby,
             lang: Box::new(ruby::Ruby::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_kotlin() -> Self {
         Self {
             kind: Language::Kotlin,
             lang: Box::new(kotlin::Kotlin::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_swift() -> Self {
         Self {
             kind: Language::Swift,
             lang: Box::new(swift::Swift::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_java() -> Self {
         Self {
             kind: Language::Java,
             lang: Box::new(java::Java::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_svelte() -> Self {
         Self {
             kind: Language::Svelte,
             lang: Box::new(svelte::Svelte::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_angular() -> Self {
         Self {
             kind: Language::Angular,
             lang: Box::new(angular::Angular::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn new_cpp() -> Self {
         Self {
             kind: Language::Cpp,
             lang: Box::new(cpp::Cpp::new()),
+            trees: RwLock::new(HashMap::new()),
+        }
+    }
+    pub fn new_csharp() -> Self {
+        Self {
+            kind: Language::CSharp,
+            lang: Box::new(csharp::CSharp::new()),
+            trees: RwLock::new(HashMap::new()),
+        }
+    }
+    pub fn new_c() -> Self {
+        Self {
+            kind: Language::C,
+            lang: Box::new(c::C::new()),
+            trees: RwLock::new(HashMap::new()),
+        }
+    }
+    pub fn new_php() -> Self {
+        Self {
+            kind: Language::Php,
+            lang: Box::new(php::Php::new()),
+            trees: RwLock::new(HashMap::new()),
         }
     }
     pub fn lang(&self) -> &dyn Stack {
@@ -118,6 +163,29 @@ impl Lang {
     pub fn q(&self, q: &str, nt: &NodeType) -> Query {
         self.lang.q(q, nt)
     }
+    // parse the file once up front so every later pass can reuse the tree
+    pub fn cache_tree(&self, code: &str, file: &str) -> Result<()> {
+        let tree = self.lang.parse(code, &NodeType::File)?;
+        let mut trees = self.trees.write().unwrap();
+        trees.insert(file.to_string(), (hash_code(code), tree));
+        Ok(())
+    }
+    pub fn clear_trees(&self) {
+        self.trees.write().unwrap().clear();
+    }
+    // cached tree for this file if the code matches, otherwise a fresh parse
+    pub fn parse(&self, code: &str, file: &str, nt: &NodeType) -> Result<Tree> {
+        // pkg files use a different parser
+        if !matches!(nt, NodeType::Library) {
+            let trees = self.trees.read().unwrap();
+            if let Some((hash, tree)) = trees.get(file) {
+                if *hash == hash_code(code) {
+                    return Ok(tree.clone());
+                }
+            }
+        }
+        self.lang.parse(code, nt)
+    }
     pub fn get_libs<G: Graph>(&self, code: &str, file: &str) -> Result<Vec<NodeData>> {
         if let Some(qo) = self.lang.lib_query() {
             let qo = self.q(&qo, &NodeType::Library);
@@ -138,6 +206,22 @@ impl Lang {
             Ok(Vec::new())
         }
     }
+    pub fn get_interfaces<G: Graph>(&self, code: &str, file: &str) -> Result<Vec<NodeData>> {
+        if let Some(qo) = self.lang.interface_query() {
+            let qo = self.q(&qo, &NodeType::Interface);
+            Ok(self.collect::<G>(&qo, code, file, NodeType::Interface)?)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+    pub fn get_enums<G: Graph>(&self, code: &str, file: &str) -> Result<Vec<NodeData>> {
+        if let Some(qo) = self.lang.enum_query() {
+            let qo = self.q(&qo, &NodeType::Enum);
+            Ok(self.collect::<G>(&qo, code, file, NodeType::Enum)?)
+        } else {
+            Ok(Vec::new())
+        }
+    }
     pub fn get_imports<G: Graph>(&self, code: &str, file: &str) -> Result<Vec<NodeData>> {
         if let Some(qo) = self.lang.imports_query() {
             let qo = self.q(&qo, &NodeType::Import);
@@ -146,6 +230,14 @@ impl Lang {
             Ok(Vec::new())
         }
     }
+    pub fn get_fields<G: Graph>(&self, code: &str, file: &str) -> Result<Vec<NodeData>> {
+        if let Some(qo) = self.lang.field_query() {
+            let qo = self.q(&qo, &NodeType::Field);
+            Ok(self.collect::<G>(&qo, code, file, NodeType::Field)?)
+        } else {
+            Ok(Vec::new())
+        }
+    }
     pub fn get_varables<G: Graph>(&self, code: &str, file: &str) -> Result<Vec<NodeData>> {
         if let Some(qo) = self.lang.variables_query() {
             let qo = self.q(&qo, &NodeType::Var);
@@ -199,12 +291,21 @@ impl Lang {
     ) -> Result<(Vec<Function>, Vec<Function>)> {
         let qo = self.q(&self.lang.function_definition_query(), &NodeType::Function);
         let funcs1 = self.collect_functions(&qo, code, file, graph, lsp_tx)?;
-        let (funcs, mut tests) = self.lang.filter_tests(funcs1);
+        let (mut funcs, mut tests) = self.lang.filter_tests(funcs1);
         if let Some(tq) = self.lang.test_query() {
             let qo2 = self.q(&tq, &NodeType::Test);
             let more_tests = self.collect_tests(&qo2, code, file)?;
+            // tests marked by attributes are function definitions too
+            funcs.retain(|f| {
+                !more_tests
+                    .iter()
+                    .any(|t| t.0.name == f.0.name && t.0.start == f.0.start)
+            });
             tests.extend(more_tests);
         }
+        for test in tests.iter_mut() {
+            self.lang.update_test(&mut test.0);
+        }
         Ok((funcs, tests))
     }
     pub fn get_query_opt<G: Graph>(
@@ -230,11 +331,13 @@ impl Lang {
         lsp_tx: &Option<CmdSender>,
     ) -> Result<(Vec<FunctionCall>, Vec<FunctionCall>, Vec<Edge>)> {
         trace!("get_function_calls");
-        let tree = self.lang.parse(&code, &NodeType::Function)?;
+        let tree = self.parse(&code, file, &NodeType::Function)?;
         // get each function
         let qo1 = self.q(&self.lang.function_definition_query(), &NodeType::Function);
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(&qo1, tree.root_node(), code.as_bytes());
+        let test_positions = self.test_positions(code, file)?;
+        let mut callers: Vec<(String, (usize, usize))> = Vec::new();
         // calls from functions, calls from tests, integration tests
         let mut res = (Vec::new(), Vec::new(), Vec::new());
         // get each function call within that function
@@ -247,6 +350,15 @@ impl Lang {
                     caller_name = body;
                 } else if o == FUNCTION_DEFINITION {
                     // NOTE this should always be the last one
+                    // skip the extra matches collect_functions dropped
+                    let range = (node.start_byte(), node.end_byte());
+                    if callers
+                        .iter()
+                        .any(|(n, r)| n == &caller_name && ranges_nest(*r, range))
+                    {
+                        return Ok(());
+                    }
+                    callers.push((caller_name.clone(), range));
                     let q2 = self.q(&self.lang.function_call_query(), &NodeType::Function);
                     let calls = self.collect_calls_in_function(
                         &q2,
@@ -257,8 +369,12 @@ impl Lang {
                         graph,
                         lsp_tx,
                     )?;
-                    self.add_calls_inside(&mut res, &caller_name, file, calls);
-                    if self.lang.is_test(&caller_name, file) {
+                    let is_test = self.lang.is_test(&caller_name, file)
+                        || test_positions
+                            .iter()
+                            .any(|(n, row)| n == &caller_name && *row == node.start_position().row);
+                    self.add_calls_inside(&mut res, is_test, calls);
+                    if is_test {
                         let int_calls = self.collect_integration_test_calls(
                             code,
                             file,
@@ -275,14 +391,23 @@ impl Lang {
         }
         Ok(res)
     }
+    // (name, start row) of the functions picked up by the test query
+    fn test_positions(&self, code: &str, file: &str) -> Result<Vec<(String, usize)>> {
+        if let Some(tq) = self.lang.test_query() {
+            let q = self.q(&tq, &NodeType::Test);
+            let tests = self.collect_tests(&q, code, file)?;
+            Ok(tests.into_iter().map(|t| (t.0.name, t.0.start)).collect())
+        } else {
+            Ok(Vec::new())
+        }
+    }
     fn add_calls_inside(
         &self,
         res: &mut (Vec<FunctionCall>, Vec<FunctionCall>, Vec<Edge>),
-        caller_name: &str,
-        caller_file: &str,
+        is_test: bool,
         calls: Vec<FunctionCall>,
     ) {
-        if self.lang.is_test(&caller_name, caller_file) {
+        if is_test {
             res.1.extend_from_slice(&calls);
         } else {
             res.0.extend_from_slice(&calls);
@@ -307,6 +432,9 @@ impl Lang {
             Language::Svelte => Lang::new_svelte(),
             Language::Angular => Lang::new_angular(),
             Language::Cpp => Lang::new_cpp(),
+            Language::CSharp => Lang::new_csharp(),
+            Language::C => Lang::new_c(),
+            Language::Php => Lang::new_php(),
         }
     }
 }
@@ -323,6 +451,12 @@ impl FromStr for Lang {
     }
 }
 
+fn hash_code(code: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    code.hash(&mut hasher);
+    hasher.finish()
+}
+
 pub fn vecy(args: &[&str]) -> Vec<String> {
     args.iter().map(|s| s.to_string()).collect()
 }
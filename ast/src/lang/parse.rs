@@ -13,7 +13,7 @@ impl Lang {
         file: &str,
         nt: NodeType,
     ) -> Result<Vec<NodeData>> {
-        let tree = self.lang.parse(&code, &nt)?;
+        let tree = self.parse(&code, file, &nt)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, tree.root_node(), code.as_bytes());
         let mut res = Vec::new();
@@ -23,6 +23,8 @@ impl Lang {
                 NodeType::Import => self.format_imports(&m, code, file, q)?,
                 NodeType::Instance => vec![self.format_instance(&m, code, file, q)?],
                 NodeType::Trait => vec![self.format_trait(&m, code, file, q)?],
+                NodeType::Interface => vec![self.format_interface(&m, code, file, q)?],
+                NodeType::Enum => vec![self.format_enum(&m, code, file, q)?],
                 // req and endpoint are the same format in the query templates
                 NodeType::Endpoint | NodeType::Request => self
                     .format_endpoint::<G>(&m, code, file, q, None, &None)?
@@ -31,6 +33,7 @@ impl Lang {
                     .collect(),
                 NodeType::DataModel => vec![self.format_data_model(&m, code, file, q)?],
                 NodeType::Var => self.format_variables(&m, code, file, q)?,
+                NodeType::Field => self.format_field(&m, code, file, q)?.into_iter().collect(),
                 _ => return Err(anyhow::anyhow!("collect: {nt:?} not implemented")),
             };
             res.extend(another);
@@ -49,18 +52,24 @@ impl Lang {
         let mut associations = Vec::new();
         let mut association_type = None;
         let mut assocition_target = None;
+        let mut scopes = Vec::new();
 
         Self::loop_captures(q, &m, code, |body, node, o| {
             if o == CLASS_NAME {
                 cls.name = body;
             } else if o == CLASS_DEFINITION {
                 cls.body = body;
-                cls.start = node.start_position().row;
-                cls.end = node.end_position().row;
+                cls.set_range(&node);
+                cls.docs = self.lang.find_docs(node, code);
+                scopes = self.lang.enclosing_scopes(declaration_of(node), code);
             } else if o == CLASS_PARENT {
                 cls.add_parent(&body);
             } else if o == INCLUDED_MODULES {
                 cls.add_includes(&body);
+            } else if o == CLASS_IMPLEMENTS {
+                for name in implemented_types(node, code) {
+                    cls.add_implements(&name);
+                }
             } else if o == ASSOCIATION_TYPE {
                 association_type = Some(body.clone());
             } else if o == ASSOCIATION_TARGET {
@@ -80,6 +89,7 @@ impl Lang {
             }
             Ok(())
         })?;
+        cls.add_qualified_name(&self.qualify(code, file, &scopes, &cls.name));
         Ok((cls, associations))
     }
     pub fn collect_classes<G: Graph>(
@@ -89,7 +99,7 @@ impl Lang {
         file: &str,
         graph: &G,
     ) -> Result<Vec<(NodeData, Vec<Edge>)>> {
-        let tree = self.lang.parse(&code, &NodeType::Class)?;
+        let tree = self.parse(&code, file, &NodeType::Class)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, tree.root_node(), code.as_bytes());
         let mut res = Vec::new();
@@ -112,8 +122,7 @@ impl Lang {
                 cls.name = trim_quotes(&body).to_string();
             } else if o == LIBRARY {
                 cls.body = body;
-                cls.start = node.start_position().row;
-                cls.end = node.end_position().row;
+                cls.set_range(&node);
             } else if o == LIBRARY_VERSION {
                 cls.add_version(&trim_quotes(&body).to_string());
             }
@@ -134,8 +143,7 @@ impl Lang {
             if o == IMPORTS {
                 impy.name = "imports".to_string();
                 impy.body = body;
-                impy.start = node.start_position().row;
-                impy.end = node.end_position().row;
+                impy.set_range(&node);
                 res.push(impy);
             }
 
@@ -157,8 +165,7 @@ impl Lang {
                 v.name = body.to_string();
             } else if o == VARIABLE_DECLARATION {
                 v.body = body;
-                v.start = node.start_position().row;
-                v.end = node.end_position().row;
+                v.set_range(&node);
             } else if o == VARIABLE_TYPE {
                 v.data_type = Some(body);
             }
@@ -170,6 +177,47 @@ impl Lang {
         }
         Ok(res)
     }
+    pub fn format_field(
+        &self,
+        m: &QueryMatch,
+        code: &str,
+        file: &str,
+        q: &Query,
+    ) -> Result<Option<NodeData>> {
+        let mut field = NodeData::in_file(file);
+        let mut annotations = Vec::new();
+        Self::loop_captures_multi(q, &m, code, |body, node, o| {
+            if o == FIELD_NAME {
+                // ruby :name, php $name
+                field.name = body.trim_start_matches([':', '$']).to_string();
+            } else if o == FIELD {
+                field.body = body;
+                field.set_range(&node);
+                field.docs = self.lang.find_docs(node, code);
+                annotations.extend(field_annotations(node, code));
+            } else if o == FIELD_TYPE {
+                field.data_type = Some(body);
+            } else if o == FIELD_DEFAULT {
+                field.add_default_value(&body);
+            } else if o == FIELD_ANNOTATION {
+                annotations.push(body);
+            } else if o == FIELD_MODIFIERS {
+                let (visibility, annos) = split_modifiers(node, code);
+                if let Some(visibility) = visibility {
+                    field.add_visibility(&visibility);
+                }
+                annotations.extend(annos);
+            }
+            Ok(())
+        })?;
+        if field.name.is_empty() || field.body.is_empty() {
+            return Ok(None);
+        }
+        if !annotations.is_empty() {
+            field.add_annotations(&annotations);
+        }
+        Ok(Some(field))
+    }
     pub fn collect_pages<G: Graph>(
         &self,
         q: &Query,
@@ -178,7 +226,7 @@ impl Lang {
         lsp_tx: &Option<CmdSender>,
         graph: &G,
     ) -> Result<Vec<(NodeData, Vec<Edge>)>> {
-        let tree = self.lang.parse(&code, &NodeType::Page)?;
+        let tree = self.parse(&code, file, &NodeType::Page)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, tree.root_node(), code.as_bytes());
         let mut res = Vec::new();
@@ -211,8 +259,7 @@ impl Lang {
                     .collect();
             } else if o == PAGE {
                 pag.body = body;
-                pag.start = node.start_position().row;
-                pag.end = node.end_position().row;
+                pag.set_range(&node);
             } else if o == PAGE_COMPONENT {
                 let p = node.start_position();
                 let pos = Position::new(file, p.row as u32, p.column as u32)?;
@@ -296,13 +343,66 @@ impl Lang {
                 tr.name = body;
             } else if o == TRAIT {
                 tr.body = body;
-                tr.start = node.start_position().row;
-                tr.end = node.end_position().row;
+                tr.set_range(&node);
+                tr.docs = self.lang.find_docs(node, code);
             }
             Ok(())
         })?;
         Ok(tr)
     }
+    pub fn format_interface(
+        &self,
+        m: &QueryMatch,
+        code: &str,
+        file: &str,
+        q: &Query,
+    ) -> Result<NodeData> {
+        let mut iface = NodeData::in_file(file);
+        let mut scopes = Vec::new();
+        Self::loop_captures(q, &m, code, |body, node, o| {
+            if o == INTERFACE_NAME {
+                iface.name = body;
+            } else if o == INTERFACE {
+                iface.body = body;
+                iface.set_range(&node);
+                iface.docs = self.lang.find_docs(node, code);
+                scopes = self.lang.enclosing_scopes(node, code);
+            }
+            Ok(())
+        })?;
+        iface.add_qualified_name(&self.qualify(code, file, &scopes, &iface.name));
+        Ok(iface)
+    }
+    pub fn format_enum(
+        &self,
+        m: &QueryMatch,
+        code: &str,
+        file: &str,
+        q: &Query,
+    ) -> Result<NodeData> {
+        let mut en = NodeData::in_file(file);
+        let mut scopes = Vec::new();
+        Self::loop_captures(q, &m, code, |body, node, o| {
+            if o == ENUM_NAME {
+                en.name = body;
+            } else if o == ENUM {
+                en.body = body;
+                en.set_range(&node);
+                en.docs = self.lang.find_docs(node, code);
+                scopes = self.lang.enclosing_scopes(node, code);
+            }
+            Ok(())
+        })?;
+        let first_node = |name: &str| {
+            q.capture_index_for_name(name)
+                .and_then(|ci| m.nodes_for_capture_index(ci).next())
+        };
+        if let (Some(enum_node), Some(first)) = (first_node(ENUM), first_node(ENUM_VARIANT)) {
+            en.add_variants(&enum_variants(enum_node, first, code));
+        }
+        en.add_qualified_name(&self.qualify(code, file, &scopes, &en.name));
+        Ok(en)
+    }
     pub fn format_instance(
         &self,
         m: &QueryMatch,
@@ -314,8 +414,7 @@ impl Lang {
         Self::loop_captures(q, &m, code, |body, node, o| {
             if o == INSTANCE_NAME {
                 inst.name = body;
-                inst.start = node.start_position().row;
-                inst.end = node.end_position().row;
+                inst.set_range(&node);
             } else if o == CLASS_NAME {
                 inst.data_type = Some(body);
             } else if o == INSTANCE {
@@ -336,9 +435,9 @@ impl Lang {
             return Ok(Vec::new());
         }
         let mut res = Vec::new();
+        let tree = self.parse(&code, file, &NodeType::Endpoint)?;
         for ef in self.lang().endpoint_finders() {
             let q = self.lang.q(&ef, &NodeType::Endpoint);
-            let tree = self.lang.parse(&code, &NodeType::Endpoint)?;
             let mut cursor = QueryCursor::new();
             let mut matches = cursor.matches(&q, tree.root_node(), code.as_bytes());
             while let Some(m) = matches.next() {
@@ -364,6 +463,8 @@ impl Lang {
         let mut call = None;
         let mut params = HandlerParams::default();
         let mut handler_position = None;
+        let mut group_prefix = None;
+        let mut route_prefix = None;
         Self::loop_captures(q, &m, code, |body, node, o| {
             if o == ENDPOINT {
                 let namey = trim_quotes(&body);
@@ -380,8 +481,10 @@ impl Lang {
                 // println!("alias {:?}", inst.name);
             } else if o == ROUTE {
                 endp.body = body;
-                endp.start = node.start_position().row;
-                endp.end = node.end_position().row;
+                endp.set_range(&node);
+                endp.docs = self.lang.find_docs(node, code);
+                group_prefix = self.lang.endpoint_group_prefix(node, code);
+                route_prefix = self.lang.endpoint_prefix(node, code);
             } else if o == HANDLER {
                 // tracing::info!("found HANDLER {:?} {:?}", body, endp.name);
                 let handler_name = trim_quotes(&body);
@@ -416,6 +519,14 @@ impl Lang {
             }
             Ok(())
         })?;
+        if endp.name.is_empty() && endp.meta.contains_key("group") {
+            if let Some(prefix) = group_prefix {
+                endp.name = prefix;
+            }
+        }
+        if let Some(prefix) = route_prefix {
+            endp.name = format!("{}{}", prefix.trim_end_matches('/'), endp.name);
+        }
         if endp.meta.get("verb").is_none() {
             self.lang.add_endpoint_verb(&mut endp, &call);
         }
@@ -492,16 +603,19 @@ impl Lang {
         q: &Query,
     ) -> Result<NodeData> {
         let mut inst = NodeData::in_file(file);
+        let mut scopes = Vec::new();
         Self::loop_captures(q, &m, code, |body, node, o| {
             if o == STRUCT_NAME {
                 inst.name = trim_quotes(&body).to_string();
             } else if o == STRUCT {
                 inst.body = body;
-                inst.start = node.start_position().row;
-                inst.end = node.end_position().row;
+                inst.set_range(&node);
+                inst.docs = self.lang.find_docs(node, code);
+                scopes = self.lang.enclosing_scopes(declaration_of(node), code);
             }
             Ok(())
         })?;
+        inst.add_qualified_name(&self.qualify(code, file, &scopes, &inst.name));
         Ok(inst)
     }
     pub fn collect_functions<G: Graph>(
@@ -512,24 +626,41 @@ impl Lang {
         graph: &G,
         lsp_tx: &Option<CmdSender>,
     ) -> Result<Vec<Function>> {
-        let tree = self.lang.parse(&code, &NodeType::Function)?;
+        let tree = self.parse(&code, file, &NodeType::Function)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, tree.root_node(), code.as_bytes());
         let mut res = Vec::new();
         while let Some(m) = matches.next() {
             if let Some(ff) = self.format_function(&m, code, file, &q, graph, lsp_tx)? {
+                // several patterns can match one function, keep the first
+                if res.iter().any(|f: &Function| {
+                    f.0.name == ff.0.name
+                        && ranges_nest(
+                            (f.0.start_byte, f.0.end_byte),
+                            (ff.0.start_byte, ff.0.end_byte),
+                        )
+                }) {
+                    continue;
+                }
                 res.push(ff);
             }
         }
         Ok(res)
     }
     pub fn collect_tests(&self, q: &Query, code: &str, file: &str) -> Result<Vec<Function>> {
-        let tree = self.lang.parse(&code, &NodeType::Test)?;
+        let tree = self.parse(&code, file, &NodeType::Test)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, tree.root_node(), code.as_bytes());
-        let mut res = Vec::new();
+        let mut res: Vec<Function> = Vec::new();
         while let Some(m) = matches.next() {
             let ff = self.format_test(&m, code, file, &q)?;
+            // a test can match more than one pattern
+            if res
+                .iter()
+                .any(|t| t.0.name == ff.name && t.0.start == ff.start)
+            {
+                continue;
+            }
             // FIXME trait operand here as well?
             res.push((ff, None, vec![], vec![], None, vec![]));
         }
@@ -552,6 +683,7 @@ impl Lang {
         let mut trait_operand = None;
         let mut name_pos = None;
         let mut return_types = Vec::new();
+        let mut scopes = Vec::new();
         Self::loop_captures(q, &m, code, |body, node, o| {
             if o == PARENT_TYPE {
                 parent_type = Some(body);
@@ -562,8 +694,10 @@ impl Lang {
                 name_pos = Some(pos);
             } else if o == FUNCTION_DEFINITION {
                 func.body = body;
-                func.start = node.start_position().row;
-                func.end = node.end_position().row;
+                func.set_range(&node);
+                func.docs = self.lang.find_docs(node, code);
+                scopes = self.lang.enclosing_scopes(node, code);
+                func.add_metrics(&self.lang.function_metrics(node, code));
                 // parent
                 parent = self.lang.find_function_parent(
                     node,
@@ -619,20 +753,18 @@ impl Lang {
                         {
                             continue;
                         }
-                        match graph
-                            .find_nodes_by_name(NodeType::DataModel, &dm_node.name)
-                            .first()
-                            .cloned()
-                        {
-                            Some(dmr) => {
-                                models.push(Edge::contains(
-                                    NodeType::Function,
-                                    &func,
-                                    NodeType::DataModel,
-                                    &dmr,
-                                ));
-                            }
-                            None => (),
+                        // typescript models are usually interfaces
+                        let found = [NodeType::DataModel, NodeType::Interface]
+                            .into_iter()
+                            .find_map(|nt| {
+                                graph
+                                    .find_nodes_by_name(nt.clone(), &dm_node.name)
+                                    .first()
+                                    .cloned()
+                                    .map(|dmr| (nt, dmr))
+                            });
+                        if let Some((nt, dmr)) = found {
+                            models.push(Edge::contains(NodeType::Function, &func, nt, &dmr));
                         }
                     }
                 }
@@ -646,9 +778,14 @@ impl Lang {
                             if let LspRes::GotoDefinition(Some(gt)) = res {
                                 let dfile = gt.file.display().to_string();
                                 if !self.lang.is_lib_file(&dfile) {
-                                    if let Some(t) =
-                                        graph.find_node_at(NodeType::DataModel, &dfile, gt.line)
-                                    {
+                                    let found = [NodeType::DataModel, NodeType::Interface]
+                                        .into_iter()
+                                        .find_map(|nt| {
+                                            graph
+                                                .find_node_at(nt.clone(), &dfile, gt.line)
+                                                .map(|t| (nt, t))
+                                        });
+                                    if let Some((nt, t)) = found {
                                         log_cmd(format!(
                                             "*******RETURN_TYPE found target for {:?} {} {}!!!",
                                             name, &t.file, &t.name
@@ -656,7 +793,7 @@ impl Lang {
                                         return_types.push(Edge::contains(
                                             NodeType::Function,
                                             &func,
-                                            NodeType::DataModel,
+                                            nt,
                                             &t,
                                         ));
                                     }
@@ -672,6 +809,17 @@ impl Lang {
             log_cmd(format!("found function but empty body {:?}", func.name));
             return Ok(None);
         }
+        // methods declared outside their class, like go receivers
+        if scopes.is_empty() {
+            let owner = parent
+                .as_ref()
+                .map(|pp| pp.source.name.clone())
+                .or(parent_type);
+            if let Some(owner) = owner {
+                scopes.push(owner.trim_start_matches(['*', '&']).to_string());
+            }
+        }
+        func.add_qualified_name(&self.qualify(code, file, &scopes, &func.name));
         if let Some(pos) = name_pos {
             trait_operand = self.lang.find_trait_operand(
                 pos,
@@ -719,8 +867,8 @@ impl Lang {
                 test.name = trim_quotes(&body).to_string();
             } else if o == FUNCTION_DEFINITION {
                 test.body = body;
-                test.start = node.start_position().row;
-                test.end = node.end_position().row;
+                test.set_range(&node);
+                test.docs = self.lang.find_docs(node, code);
             }
             Ok(())
         })?;
@@ -770,6 +918,17 @@ impl Lang {
         }
         Ok(())
     }
+    // module path + enclosing scopes + name, like "db::Database::new"
+    fn qualify(&self, code: &str, file: &str, scopes: &[String], name: &str) -> String {
+        let mut parts = self
+            .lang
+            .module_path(file, code)
+            .into_iter()
+            .collect::<Vec<_>>();
+        parts.extend(scopes.iter().cloned());
+        parts.push(name.to_string());
+        parts.join(self.lang.qualified_separator())
+    }
     pub fn collect_calls_in_function<'a, G: Graph>(
         &self,
         q: &Query,
@@ -783,10 +942,11 @@ impl Lang {
         trace!("collect_calls_in_function");
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, caller_node, code.as_bytes());
+        let caller = NodeKeys::at_node(caller_name, file, &caller_node);
         let mut res = Vec::new();
         while let Some(m) = matches.next() {
             if let Some(fc) =
-                self.format_function_call(&m, code, file, q, caller_name, graph, lsp_tx)?
+                self.format_function_call(&m, code, file, q, &caller, graph, lsp_tx)?
             {
                 res.push(fc);
             }
@@ -799,13 +959,22 @@ impl Lang {
         code: &str,
         file: &str,
         q: &Query,
-        caller_name: &str,
+        caller: &NodeKeys,
         graph: &G,
         lsp_tx: &Option<CmdSender>,
     ) -> Result<Option<FunctionCall>> {
+        let caller_name = &caller.name;
         let mut fc = Calls::default();
         let mut external_func = None;
         let mut class_call = None;
+        // the operand is needed to pick between functions with the same name
+        let operand = q
+            .capture_index_for_name(OPERAND)
+            .and_then(|ci| m.nodes_for_capture_index(ci).next())
+            .and_then(|n| n.utf8_text(code.as_bytes()).ok())
+            .map(|o| self.lang.qualified_operand(o))
+            .filter(|o| !o.is_empty() && !matches!(o.as_str(), "self" | "Self" | "this"));
+        let separator = self.lang.qualified_separator();
         Self::loop_captures(q, &m, code, |body, node, o| {
             if o == FUNCTION_NAME {
                 let called = body;
@@ -820,16 +989,27 @@ impl Lang {
                     }
                     if let LspRes::GotoDefinition(Some(gt)) = res {
                         let target_file = gt.file.display().to_string();
-                        if let Some(t) = graph.find_node_by_name_in_file(
-                            NodeType::Function,
+                        let in_file = find_qualified_target(
                             &called,
-                            &target_file,
-                        ) {
+                            operand.as_deref(),
+                            caller,
+                            separator,
+                            Some(&target_file),
+                            graph,
+                        )
+                        .or_else(|| {
+                            graph.find_node_by_name_in_file(
+                                NodeType::Function,
+                                &called,
+                                &target_file,
+                            )
+                        });
+                        if let Some(t) = in_file {
                             log_cmd(format!(
                                 "==> ! found target for {:?} {}!!!",
                                 called, &t.file
                             ));
-                            fc.target = NodeKeys::new(&called, &t.file, t.start);
+                            fc.target = NodeKeys::from(&t);
                             // set extenal func so this is marked as USES edge rather than CALLS
                             if t.body.is_empty() && t.docs.is_some() {
                                 log_cmd(format!("==> ! found target is external {:?}!!!", called));
@@ -876,8 +1056,7 @@ impl Lang {
                                                 "==> ! found target for impl {:?} {:?}!!!",
                                                 called, &t_file
                                             ));
-                                            fc.target =
-                                                NodeKeys::new(&called, &t_file.file, t_file.start);
+                                            fc.target = NodeKeys::from(&t_file);
                                         }
                                     }
                                 }
@@ -891,7 +1070,28 @@ impl Lang {
                 } else {
                     // println!("no target for {:?}", body);
                     // FALLBACK to find?
-                    if let Some(tf) = func_target_file_finder(&called, &None, graph) {
+                    let qualified = find_qualified_target(
+                        &called,
+                        operand.as_deref(),
+                        caller,
+                        separator,
+                        None,
+                        graph,
+                    );
+                    if let Some(t) = qualified {
+                        log_cmd(format!(
+                            "==> ? (no lsp) qualified target for {:?} {:?}",
+                            called,
+                            t.qualified_name()
+                        ));
+                        fc.target = NodeKeys::from(&t);
+                    } else if operand.is_some() && self.lang.operand_is_path() {
+                        // Type::func where Type is not in the graph, so not one of ours
+                        log_cmd(format!(
+                            "==> ? (no lsp) no qualified target for {:?}",
+                            called
+                        ));
+                    } else if let Some(tf) = func_target_file_finder(&called, &None, graph) {
                         log_cmd(format!(
                             "==> ? (no lsp) ONE target for {:?} {}",
                             called, &tf
@@ -900,9 +1100,8 @@ impl Lang {
                     }
                 }
             } else if o == FUNCTION_CALL {
-                fc.source = NodeKeys::new(&caller_name, file, node.start_position().row);
-                fc.call_start = node.start_position().row;
-                fc.call_end = node.end_position().row;
+                fc.source = caller.clone();
+                fc.set_call_range(&node);
             } else if o == OPERAND {
                 fc.operand = Some(body.clone());
                 if self.lang.direct_class_calls() {
@@ -966,7 +1165,7 @@ impl Lang {
             &self.lang.integration_test_query().unwrap(),
             &NodeType::Test,
         );
-        let tree = self.lang.parse(&code, &NodeType::Test)?;
+        let tree = self.parse(&code, file, &NodeType::Test)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(&q, tree.root_node(), code.as_bytes());
         let mut res = Vec::new();
@@ -1003,8 +1202,7 @@ impl Lang {
             }
             if o == INTEGRATION_TEST {
                 nd.body = body.clone();
-                nd.start = node.start_position().row;
-                nd.end = node.end_position().row;
+                nd.set_range(&node);
             }
             if o == E2E_TEST_NAME {
                 e2e_test_name = Some(trim_quotes(&body).to_string());
@@ -1036,8 +1234,7 @@ impl Lang {
             if o == HANDLER {
                 // println!("====> TEST HANDLER {}", body);
                 // GetWorkspaceRepoByWorkspaceUuidAndRepoUuid
-                fc.call_start = node.start_position().row;
-                fc.call_end = node.end_position().row;
+                fc.set_call_range(&node);
                 let p = node.start_position();
                 let pos = Position::new(file, p.row as u32, p.column as u32)?;
                 handler_name = Some(body);
@@ -1101,7 +1298,8 @@ impl Lang {
             EdgeType::Calls,
             NodeRef::from(source, NodeType::Test),
             NodeRef::from(endpoint, NodeType::Endpoint),
-        );
+        )
+        .with_call_site(fc.call_site());
         Ok(Some(edge))
     }
     pub fn collect_import_edges<G: Graph>(
@@ -1115,7 +1313,7 @@ impl Lang {
         if let Some(lsp) = lsp_tx {
             return self.collect_import_edges_with_lsp(code, file, graph, lsp);
         }
-        let tree = self.lang.parse(&code, &NodeType::Import)?;
+        let tree = self.parse(&code, file, &NodeType::Import)?;
         let mut cursor = QueryCursor::new();
         let mut matches = cursor.matches(q, tree.root_node(), code.as_bytes());
         let mut edges = Vec::new();
@@ -1141,6 +1339,8 @@ impl Lang {
                         NodeType::Function,
                         NodeType::Class,
                         NodeType::DataModel,
+                        NodeType::Interface,
+                        NodeType::Enum,
                         NodeType::Var,
                     ] {
                         let name = self.lang.resolve_import_name(import_name);
@@ -1181,7 +1381,7 @@ impl Lang {
         let mut processed = std::collections::HashSet::new();
 
         let query = self.q(&self.lang.identifier_query(), &NodeType::Var);
-        let tree = self.lang.parse(code, &NodeType::Function)?;
+        let tree = self.parse(code, file, &NodeType::Function)?;
         let mut cursor = tree_sitter::QueryCursor::new();
         let mut matches = cursor.matches(&query, tree.root_node(), code.as_bytes());
 
@@ -1212,6 +1412,8 @@ impl Lang {
                     NodeType::Function,
                     NodeType::Class,
                     NodeType::DataModel,
+                    NodeType::Interface,
+                    NodeType::Enum,
                     NodeType::Var,
                 ] {
                     if file == target_file {
@@ -1403,6 +1605,62 @@ fn func_target_file_finder<G: Graph>(
     tf
 }
 
+// when several functions share a name, pick the one whose qualified name
+// matches the call's operand (Database::new) or is closest to the caller's scope
+fn find_qualified_target<G: Graph>(
+    func_name: &str,
+    operand: Option<&str>,
+    caller: &NodeKeys,
+    separator: &str,
+    file: Option<&str>,
+    graph: &G,
+) -> Option<NodeData> {
+    let candidates = graph
+        .find_nodes_by_name(NodeType::Function, func_name)
+        .into_iter()
+        .filter(|f| !f.body.is_empty() && file.is_none_or(|file| f.file == file))
+        .filter(|f| f.qualified_name().is_some())
+        .collect::<Vec<_>>();
+    let only_one = |matches: &dyn Fn(&str) -> bool| {
+        let mut found = candidates
+            .iter()
+            .filter(|f| f.qualified_name().is_some_and(|q| matches(q)));
+        match (found.next(), found.next()) {
+            (Some(f), None) => Some(f.clone()),
+            _ => None,
+        }
+    };
+    if let Some(op) = operand {
+        let path = format!("{}{}{}", op, separator, func_name);
+        // either side may be the more qualified one: crate::db::Database vs db::Database
+        let ends_with = |long: &str, short: &str| {
+            long == short || long.ends_with(&format!("{}{}", separator, short))
+        };
+        if let Some(f) = only_one(&|q| ends_with(q, &path) || ends_with(&path, q)) {
+            return Some(f);
+        }
+    }
+    if candidates.len() < 2 {
+        return None;
+    }
+    // walk out from the caller: Database::new_person => Database::new, then new
+    let caller_node = graph.find_node_at(NodeType::Function, &caller.file, caller.start as u32)?;
+    let mut scope = caller_node
+        .qualified_name()?
+        .split(separator)
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    while scope.pop().is_some() {
+        let mut path = scope.clone();
+        path.push(func_name.to_string());
+        let path = path.join(separator);
+        if let Some(f) = only_one(&|q| q == path) {
+            return Some(f);
+        }
+    }
+    None
+}
+
 // FIXME: prefer funcitons in the same file?? Instead of skipping if there are 2
 fn find_only_one_function_file<G: Graph>(func_name: &str, graph: &G) -> Option<String> {
     let mut target_files = Vec::new();
@@ -1492,6 +1750,263 @@ pub fn trim_quotes(value: &str) -> &str {
     value
 }
 
+// true if one byte range contains the other
+pub fn ranges_nest(a: (usize, usize), b: (usize, usize)) -> bool {
+    (a.0 <= b.0 && b.1 <= a.1) || (b.0 <= a.0 && a.1 <= b.1)
+}
+
+// comments right above a definition, skipping attributes like #[get("/")] in between
+pub fn leading_comments(
+    node: TreeNode,
+    code: &str,
+    is_doc: &dyn Fn(&str) -> bool,
+) -> Option<String> {
+    // `export function`, `r.GET(...)` statements and friends hold the comments
+    let mut node = node;
+    while let Some(parent) = node.parent() {
+        if parent.parent().is_none() || parent.start_position().row != node.start_position().row {
+            break;
+        }
+        node = parent;
+    }
+    let mut row = node.start_position().row;
+    let mut comments = Vec::new();
+    let mut prev = node.prev_sibling();
+    while let Some(p) = prev {
+        // a blank line ends the block
+        if last_row(&p) + 1 < row {
+            break;
+        }
+        if p.kind().contains("comment") {
+            let text = p.utf8_text(code.as_bytes()).ok()?;
+            if !is_doc(text) {
+                break;
+            }
+            comments.push(text);
+        } else if !p.kind().contains("attribute") && !p.kind().contains("decorator") {
+            break;
+        }
+        row = p.start_position().row;
+        prev = p.prev_sibling();
+    }
+    comments.reverse();
+    let docs = comments
+        .iter()
+        .flat_map(|c| c.lines())
+        .map(clean_comment_line)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let docs = docs.trim();
+    if docs.is_empty() {
+        None
+    } else {
+        Some(docs.to_string())
+    }
+}
+
+// line comments can include their newline
+fn last_row(node: &TreeNode) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+fn clean_comment_line(line: &str) -> &str {
+    let line = line.trim();
+    let line = line.strip_suffix("*/").unwrap_or(line).trim_end();
+    let line = ["///", "//", "/**", "/*", "*", "#"]
+        .iter()
+        .find_map(|m| line.strip_prefix(m))
+        .unwrap_or(line);
+    line.strip_prefix(' ').unwrap_or(line)
+}
+
+const ANNOTATION_KINDS: [&str; 5] = [
+    "attribute_item",
+    "attribute_list",
+    "annotation",
+    "marker_annotation",
+    "decorator",
+];
+const VISIBILITIES: [&str; 6] = [
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "fileprivate",
+    "open",
+];
+
+// #[serde(..)] above a rust field, or [Required] and @Column(..) inside the declaration
+fn field_annotations(node: TreeNode, code: &str) -> Vec<String> {
+    let text = |n: TreeNode| n.utf8_text(code.as_bytes()).unwrap_or_default().to_string();
+    let mut above = Vec::new();
+    let mut prev = node.prev_named_sibling();
+    while let Some(p) = prev {
+        if ANNOTATION_KINDS.contains(&p.kind()) {
+            above.push(text(p));
+        } else if !p.kind().contains("comment") {
+            break;
+        }
+        prev = p.prev_named_sibling();
+    }
+    above.reverse();
+    let mut cursor = node.walk();
+    let inside = node
+        .named_children(&mut cursor)
+        .filter(|c| ANNOTATION_KINDS.contains(&c.kind()))
+        .map(text);
+    above.into_iter().chain(inside).collect()
+}
+
+// visibility and annotations out of a modifiers node like `@Id private final`
+fn split_modifiers(node: TreeNode, code: &str) -> (Option<String>, Vec<String>) {
+    let text = |n: TreeNode| n.utf8_text(code.as_bytes()).unwrap_or_default().to_string();
+    let is_visibility =
+        |n: TreeNode| n.kind().contains("visibility") || n.kind().contains("accessibility");
+    // pub(crate)
+    if is_visibility(node) {
+        return (Some(text(node)), Vec::new());
+    }
+    let mut cursor = node.walk();
+    let mut items = node.children(&mut cursor).collect::<Vec<_>>();
+    if items.is_empty() {
+        items.push(node);
+    }
+    let mut visibility = None;
+    let mut annotations = Vec::new();
+    for item in items {
+        if ANNOTATION_KINDS.contains(&item.kind()) || item.kind() == "attribute" {
+            annotations.push(text(item));
+        } else if is_visibility(item) || VISIBILITIES.contains(&text(item).as_str()) {
+            visibility.get_or_insert(text(item));
+        }
+    }
+    (visibility, annotations)
+}
+
+// a quantified capture only yields the first variant,
+// so find the rest by their shape: same kind, same parent kind, same field
+fn enum_variants(enum_node: TreeNode, first: TreeNode, code: &str) -> Vec<String> {
+    let parent_kind = first.parent().map(|p| p.kind());
+    let is_name = |n: TreeNode| {
+        n.parent()
+            .and_then(|p| p.child_by_field_name("name"))
+            .is_some_and(|name| name.id() == n.id())
+    };
+    let first_is_name = is_name(first);
+    let mut variants = Vec::new();
+    let mut stack = vec![enum_node];
+    while let Some(node) = stack.pop() {
+        if node.kind() == first.kind()
+            && node.parent().map(|p| p.kind()) == parent_kind
+            && is_name(node) == first_is_name
+        {
+            if let Ok(text) = node.utf8_text(code.as_bytes()) {
+                variants.push((node.start_byte(), text.to_string()));
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+    variants.sort();
+    variants.into_iter().map(|(_, v)| v).collect()
+}
+
+// type names in an implements clause, without their generic arguments
+fn implemented_types(node: TreeNode, code: &str) -> Vec<String> {
+    if node.kind() == "type_identifier" {
+        return node
+            .utf8_text(code.as_bytes())
+            .map(|t| vec![t.to_string()])
+            .unwrap_or_default();
+    }
+    let mut types = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() != "type_arguments" {
+            types.extend(implemented_types(child, code));
+        }
+    }
+    types
+}
+
+// java classes and ts interfaces capture their body as the definition
+fn declaration_of(node: TreeNode) -> TreeNode {
+    match node.parent() {
+        Some(parent) if node.kind().ends_with("body") => parent,
+        _ => node,
+    }
+}
+
+// "a/b/c.py" => "a.b.c"
+pub fn module_from_path(file: &str, separator: &str) -> Option<String> {
+    let mut parts = file
+        .split('/')
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>();
+    if let Some(last) = parts.pop() {
+        let stem = last.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(last);
+        if !stem.is_empty() {
+            parts.push(stem);
+        }
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(separator))
+    }
+}
+
+// "package a.b;" => "a.b"
+pub fn declared_package(code: &str, keyword: &str) -> Option<String> {
+    code.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix(keyword)?.strip_prefix(' ')?;
+        let package = rest.trim().trim_end_matches(';').trim();
+        (!package.is_empty() && !package.contains('{')).then(|| package.to_string())
+    })
+}
+
+// names of the classes, impls, modules... around a definition, outermost first
+pub fn enclosing_scopes(node: TreeNode, code: &str) -> Vec<String> {
+    const SCOPES: [&str; 9] = [
+        "class",
+        "interface",
+        "module",
+        "namespace",
+        "impl_item",
+        "mod_item",
+        "trait",
+        "enum",
+        "object",
+    ];
+    let mut scopes = Vec::new();
+    let mut parent = node.parent();
+    while let Some(p) = parent {
+        // the root of a python file is a "module"
+        if p.parent().is_none() {
+            break;
+        }
+        if SCOPES.iter().any(|s| p.kind().contains(s)) {
+            let name = p
+                .child_by_field_name("name")
+                .or_else(|| p.child_by_field_name("type"));
+            if let Some(name) = name.and_then(|n| n.utf8_text(code.as_bytes()).ok()) {
+                // impl<T> Store<T>
+                let name = name.split('<').next().unwrap_or(name).trim();
+                scopes.push(name.to_string());
+            }
+        }
+        parent = p.parent();
+    }
+    scopes.reverse();
+    scopes
+}
+
 fn log_cmd(cmd: String) {
     debug!("{}", cmd);
 }
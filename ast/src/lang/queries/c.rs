@@ -0,0 +1,209 @@
+use super::super::*;
+use super::consts::*;
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Parser, Query, Tree};
+
+pub struct C(Language);
+
+impl C {
+    pub fn new() -> Self {
+        C(tree_sitter_c::LANGUAGE.into())
+    }
+}
+
+impl Stack for C {
+    fn q(&self, q: &str, _nt: &NodeType) -> Query {
+        Query::new(&self.0, q).unwrap()
+    }
+
+    fn parse(&self, code: &str, _nt: &NodeType) -> Result<Tree> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.0)?;
+        parser.parse(code, None).context("failed to parse")
+    }
+
+    fn imports_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (preproc_include
+                path: (_) @{IMPORTS_FROM} @{IMPORTS_NAME}
+            ) @{IMPORTS}
+            "#
+        ))
+    }
+
+    // macros (also inside include guards) and initialized globals
+    fn variables_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (preproc_def
+                name: (identifier) @{VARIABLE_NAME}
+                value: (preproc_arg) @{VARIABLE_VALUE}
+            ) @{VARIABLE_DECLARATION}
+            (preproc_function_def
+                name: (identifier) @{VARIABLE_NAME}
+                value: (preproc_arg) @{VARIABLE_VALUE}
+            ) @{VARIABLE_DECLARATION}
+            (translation_unit
+                (declaration
+                    type: (_) @{VARIABLE_TYPE}
+                    declarator: (init_declarator
+                        declarator: (identifier) @{VARIABLE_NAME}
+                        value: (_) @{VARIABLE_VALUE}
+                    )
+                ) @{VARIABLE_DECLARATION}
+            )
+            "#
+        ))
+    }
+
+    // C has no classes, but a struct of function pointers is its vtable
+    fn class_definition_query(&self) -> String {
+        format!(
+            r#"
+            (struct_specifier
+                name: (type_identifier) @{CLASS_NAME}
+                body: (field_declaration_list
+                    (field_declaration
+                        declarator: (function_declarator
+                            declarator: (parenthesized_declarator
+                                (pointer_declarator)
+                            )
+                        )
+                    )
+                )
+            ) @{CLASS_DEFINITION}
+            "#
+        )
+    }
+
+    fn function_definition_query(&self) -> String {
+        format!(
+            r#"
+            (function_definition
+                type: (_) @{RETURN_TYPES}
+                declarator: [
+                    (function_declarator
+                        declarator: (identifier) @{FUNCTION_NAME}
+                        parameters: (parameter_list) @{ARGUMENTS}
+                    )
+                    (pointer_declarator
+                        declarator: (function_declarator
+                            declarator: (identifier) @{FUNCTION_NAME}
+                            parameters: (parameter_list) @{ARGUMENTS}
+                        )
+                    )
+                ]
+            ) @{FUNCTION_DEFINITION}
+            "#
+        )
+    }
+
+    // direct calls, calls through a struct field (ops->fn()), and (*fp)() calls
+    fn function_call_query(&self) -> String {
+        format!(
+            r#"
+            (call_expression
+                function: [
+                    (identifier) @{FUNCTION_NAME}
+                    (field_expression
+                        argument: (_) @{OPERAND}
+                        field: (field_identifier) @{FUNCTION_NAME}
+                    )
+                    (parenthesized_expression
+                        (pointer_expression
+                            argument: (identifier) @{FUNCTION_NAME}
+                        )
+                    )
+                ]
+                arguments: (argument_list) @{ARGUMENTS}
+            ) @{FUNCTION_CALL}
+            "#
+        )
+    }
+
+    fn data_model_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (struct_specifier
+                name: (type_identifier) @{STRUCT_NAME}
+                body: (field_declaration_list)
+            ) @{STRUCT}
+            (union_specifier
+                name: (type_identifier) @{STRUCT_NAME}
+                body: (field_declaration_list)
+            ) @{STRUCT}
+            (type_definition
+                type: [
+                    (struct_specifier
+                        .
+                        (field_declaration_list)
+                    )
+                    (union_specifier
+                        .
+                        (field_declaration_list)
+                    )
+                ]
+                declarator: (type_identifier) @{STRUCT_NAME}
+            ) @{STRUCT}
+            "#
+        ))
+    }
+
+    // struct and union members, including arrays, pointers and function pointers
+    // (which may return pointers themselves)
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (field_declaration
+                type: (_) @{FIELD_TYPE}
+                declarator: [
+                    (field_identifier) @{FIELD_NAME}
+                    (array_declarator
+                        declarator: (field_identifier) @{FIELD_NAME}
+                    )
+                    (pointer_declarator
+                        declarator: (field_identifier) @{FIELD_NAME}
+                    )
+                    (function_declarator
+                        declarator: (parenthesized_declarator
+                            (pointer_declarator
+                                declarator: (field_identifier) @{FIELD_NAME}
+                            )
+                        )
+                    )
+                    (pointer_declarator
+                        declarator: (function_declarator
+                            declarator: (parenthesized_declarator
+                                (pointer_declarator
+                                    declarator: (field_identifier) @{FIELD_NAME}
+                                )
+                            )
+                        )
+                    )
+                ]
+            ) @{FIELD}
+            "#
+        ))
+    }
+
+    fn data_model_within_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (type_identifier) @{STRUCT_NAME}
+            "#
+        ))
+    }
+
+    fn is_test(&self, func_name: &str, func_file: &str) -> bool {
+        func_name.starts_with("test_") && self.is_test_file(func_file)
+    }
+
+    fn is_test_file(&self, filename: &str) -> bool {
+        let name = filename.rsplit('/').next().unwrap_or(filename);
+        filename.contains("/tests/")
+            || filename.contains("/test/")
+            || name.starts_with("test_")
+            || name.ends_with("_test.c")
+    }
+}
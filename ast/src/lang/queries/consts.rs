@@ -12,6 +12,7 @@ pub const MODULE_NAME: &str = "module-name";
 pub const CLASS_NAME: &str = "class-name";
 pub const CLASS_PARENT: &str = "class-parent";
 pub const INCLUDED_MODULES: &str = "included-modules";
+pub const CLASS_IMPLEMENTS: &str = "class-implements";
 pub const INSTANCE: &str = "instance";
 pub const INSTANCE_NAME: &str = "instance-name";
 pub const CLASS_DEFINITION: &str = "class-definition";
@@ -54,3 +55,14 @@ pub const VARIABLE_DECLARATION: &str = "variable-declaration";
 pub const VARIABLE_NAME: &str = "variable-name";
 pub const VARIABLE_VALUE: &str = "variable-value";
 pub const VARIABLE_TYPE: &str = "variable-type";
+pub const FIELD: &str = "field";
+pub const FIELD_NAME: &str = "field-name";
+pub const FIELD_TYPE: &str = "field-type";
+pub const FIELD_DEFAULT: &str = "field-default";
+pub const FIELD_MODIFIERS: &str = "field-modifiers";
+pub const FIELD_ANNOTATION: &str = "field-annotation";
+pub const ENUM: &str = "enum";
+pub const ENUM_NAME: &str = "enum-name";
+pub const ENUM_VARIANT: &str = "enum-variant";
+pub const INTERFACE: &str = "interface";
+pub const INTERFACE_NAME: &str = "interface-name";
@@ -194,6 +194,20 @@ impl Stack for Cpp {
         )
     }
 
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_specifier
+                    name: (type_identifier) @{ENUM_NAME}
+                    body: (enumerator_list
+                        (enumerator
+                            name: (identifier) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             r#"
@@ -205,6 +219,17 @@ impl Stack for Cpp {
             "#
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (field_declaration
+                    type: (_) @{FIELD_TYPE}
+                    declarator: (field_identifier) @{FIELD_NAME}
+                    default_value: (_)? @{FIELD_DEFAULT}
+                ) @{FIELD}
+            "#
+        ))
+    }
 
     fn endpoint_finders(&self) -> Vec<String> {
         vec![format!(
@@ -287,4 +312,11 @@ impl Stack for Cpp {
             nd.add_verb("ANY");
         }
     }
+    // namespaces and classes are the only scopes
+    fn qualified_separator(&self) -> &'static str {
+        "::"
+    }
+    fn module_path(&self, _file: &str, _code: &str) -> Option<String> {
+        None
+    }
 }
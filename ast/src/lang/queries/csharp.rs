@@ -0,0 +1,413 @@
+use super::super::*;
+use super::consts::*;
+use crate::lang::parse::{declared_package, trim_quotes};
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
+
+pub struct CSharp(Language);
+
+impl CSharp {
+    pub fn new() -> Self {
+        CSharp(tree_sitter_c_sharp::LANGUAGE.into())
+    }
+}
+
+impl Stack for CSharp {
+    fn q(&self, q: &str, nt: &NodeType) -> Query {
+        if matches!(nt, NodeType::Library) {
+            Query::new(&tree_sitter_html::LANGUAGE.into(), q).unwrap()
+        } else {
+            Query::new(&self.0, q).unwrap()
+        }
+    }
+
+    fn parse(&self, code: &str, nt: &NodeType) -> Result<Tree> {
+        let mut parser = Parser::new();
+        // .csproj files are xml, the html parser is close enough
+        if matches!(nt, NodeType::Library) {
+            parser.set_language(&tree_sitter_html::LANGUAGE.into())?;
+        } else {
+            parser.set_language(&self.0)?;
+        }
+        parser.parse(code, None).context("failed to parse")
+    }
+
+    fn lib_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (self_closing_tag
+                (tag_name) @tag (#eq? @tag "PackageReference")
+                (attribute
+                    (attribute_name) @include (#eq? @include "Include")
+                    (quoted_attribute_value
+                        (attribute_value) @{LIBRARY_NAME}
+                    )
+                )
+                (attribute
+                    (attribute_name) @version (#eq? @version "Version")
+                    (quoted_attribute_value
+                        (attribute_value) @{LIBRARY_VERSION}
+                    )
+                )?
+            ) @{LIBRARY}
+            "#
+        ))
+    }
+
+    fn imports_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (compilation_unit
+                (using_directive
+                    [
+                        (qualified_name)
+                        (identifier)
+                    ] @{IMPORTS_NAME} @{IMPORTS_FROM}
+                ) @{IMPORTS}
+            )
+            "#
+        ))
+    }
+
+    fn trait_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (interface_declaration
+                name: (identifier) @{TRAIT_NAME}
+            ) @{TRAIT}
+            "#
+        ))
+    }
+
+    fn class_definition_query(&self) -> String {
+        format!(
+            r#"
+            (class_declaration
+                name: (identifier) @{CLASS_NAME}
+                (base_list
+                    .
+                    [
+                        (identifier)
+                        (generic_name)
+                        (qualified_name)
+                    ] @{CLASS_PARENT}
+                )?
+            ) @{CLASS_DEFINITION}
+            "#
+        )
+    }
+
+    fn instance_definition_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (field_declaration
+                (variable_declaration
+                    type: (identifier) @{CLASS_NAME}
+                    (variable_declarator
+                        name: (identifier) @{INSTANCE_NAME}
+                    )
+                )
+            ) @{INSTANCE}
+            "#
+        ))
+    }
+
+    fn function_definition_query(&self) -> String {
+        // interface methods have no body, they belong to the Trait
+        format!(
+            r#"
+            (method_declaration
+                returns: (_) @{RETURN_TYPES}
+                name: (identifier) @{FUNCTION_NAME}
+                parameters: (parameter_list) @{ARGUMENTS}
+                body: (_)
+            ) @{FUNCTION_DEFINITION}
+            "#
+        )
+    }
+
+    fn function_call_query(&self) -> String {
+        format!(
+            r#"
+            (invocation_expression
+                function: [
+                    (identifier) @{FUNCTION_NAME}
+                    (generic_name
+                        (identifier) @{FUNCTION_NAME}
+                    )
+                    (member_access_expression
+                        expression: (_) @{OPERAND}
+                        name: [
+                            (identifier) @{FUNCTION_NAME}
+                            (generic_name
+                                (identifier) @{FUNCTION_NAME}
+                            )
+                        ]
+                    )
+                ]
+                arguments: (argument_list) @{ARGUMENTS}
+            ) @{FUNCTION_CALL}
+            "#
+        )
+    }
+
+    fn find_function_parent(
+        &self,
+        node: TreeNode,
+        code: &str,
+        file: &str,
+        func_name: &str,
+        _callback: &dyn Fn(&str) -> Option<NodeData>,
+        _parent_type: Option<&str>,
+    ) -> Result<Option<Operand>> {
+        let mut parent = node.parent();
+        while parent.is_some() && parent.unwrap().kind() != "class_declaration" {
+            parent = parent.unwrap().parent();
+        }
+        let parent_of = match parent {
+            // attributes come before the name, so look it up by field
+            Some(p) => match p.child_by_field_name("name") {
+                Some(name) => Some(Operand {
+                    source: NodeKeys::at_node(name.utf8_text(code.as_bytes())?, file, &p),
+                    target: NodeKeys::at_node(func_name, file, &node),
+                }),
+                None => None,
+            },
+            None => None,
+        };
+        Ok(parent_of)
+    }
+
+    fn endpoint_finders(&self) -> Vec<String> {
+        vec![
+            // [Route] on the controller is captured as the group
+            format!(
+                r#"
+                (class_declaration
+                    (attribute_list
+                        (attribute
+                            name: (identifier) @route-attribute (#eq? @route-attribute "Route")
+                            (attribute_argument_list
+                                (attribute_argument
+                                    (string_literal) @{ENDPOINT_GROUP}
+                                )
+                            )
+                        )
+                    )?
+                    body: (declaration_list
+                        (method_declaration
+                            (attribute_list
+                                (attribute
+                                    name: (identifier) @{ENDPOINT_VERB} (#match? @{ENDPOINT_VERB} "^Http(Get|Post|Put|Delete|Patch)$")
+                                    (attribute_argument_list
+                                        (attribute_argument
+                                            (string_literal) @{ENDPOINT}
+                                        )
+                                    )?
+                                )
+                            )
+                            name: (identifier) @{HANDLER}
+                        ) @{ROUTE}
+                    )
+                )
+                "#
+            ),
+            // minimal apis: app.MapGet("/path", Handler)
+            format!(
+                r#"
+                (invocation_expression
+                    function: (member_access_expression
+                        name: (identifier) @{ENDPOINT_VERB} (#match? @{ENDPOINT_VERB} "^Map(Get|Post|Put|Delete|Patch)$")
+                    )
+                    arguments: (argument_list
+                        .
+                        (argument
+                            (string_literal) @{ENDPOINT}
+                        )
+                        .
+                        (argument
+                            [
+                                (identifier) @{HANDLER}
+                                (member_access_expression
+                                    name: (identifier) @{HANDLER}
+                                )
+                            ]
+                        )
+                    )
+                ) @{ROUTE}
+                "#
+            ),
+        ]
+    }
+
+    fn update_endpoint_verb(&self, nd: &mut NodeData, _call: &Option<String>) {
+        if let Some(verb) = nd.meta.get("verb").cloned() {
+            let verb = verb
+                .strip_prefix("HTTP")
+                .or_else(|| verb.strip_prefix("MAP"))
+                .unwrap_or(&verb)
+                .to_string();
+            nd.add_verb(&verb);
+        } else {
+            nd.add_verb("GET");
+        }
+        // the controller route is the prefix of the action route
+        if let Some(group) = nd.meta.get("group").cloned() {
+            let prefix = trim_quotes(&group).trim_matches('/').to_string();
+            nd.name = if nd.name.starts_with('/') {
+                nd.name.clone()
+            } else if nd.name.is_empty() {
+                format!("/{}", prefix)
+            } else {
+                format!("/{}/{}", prefix, nd.name)
+            };
+            nd.add_group(&prefix);
+        }
+    }
+
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_declaration
+                    name: (identifier) @{ENUM_NAME}
+                    body: (enum_member_declaration_list
+                        (enum_member_declaration
+                            name: (identifier) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
+    fn data_model_query(&self) -> Option<String> {
+        // EF Core entities: [Table] classes, or classes with a [Key] property
+        Some(format!(
+            r#"
+            (class_declaration
+                (attribute_list
+                    (attribute
+                        name: (identifier) @attribute (#match? @attribute "^(Table|PrimaryKey)$")
+                    )
+                )
+                name: (identifier) @{STRUCT_NAME}
+            ) @{STRUCT}
+            (class_declaration
+                name: (identifier) @{STRUCT_NAME}
+                body: (declaration_list
+                    (property_declaration
+                        (attribute_list
+                            (attribute
+                                name: (identifier) @key (#eq? @key "Key")
+                            )
+                        )
+                    )
+                )
+            ) @{STRUCT}
+            "#
+        ))
+    }
+    // the entities of a DbContext
+    fn data_model_registry_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (generic_name
+                (identifier) @db-set (#eq? @db-set "DbSet")
+                (type_argument_list
+                    [
+                        (identifier) @{STRUCT_NAME}
+                        (qualified_name
+                            name: (identifier) @{STRUCT_NAME}
+                        )
+                    ]
+                )
+            )
+            "#
+        ))
+    }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (property_declaration
+                    (modifier)* @{FIELD_MODIFIERS}
+                    type: (_) @{FIELD_TYPE}
+                    name: (identifier) @{FIELD_NAME}
+                    value: (_)? @{FIELD_DEFAULT}
+                ) @{FIELD}
+                (field_declaration
+                    (modifier)* @{FIELD_MODIFIERS}
+                    (variable_declaration
+                        type: (_) @{FIELD_TYPE}
+                        (variable_declarator
+                            name: (identifier) @{FIELD_NAME}
+                        )
+                    )
+                ) @{FIELD}
+            "#
+        ))
+    }
+
+    fn data_model_within_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            [
+                (identifier) @{STRUCT_NAME}
+                (generic_name
+                    (type_argument_list
+                        (identifier) @{STRUCT_NAME}
+                    )
+                )
+            ]
+            "#
+        ))
+    }
+
+    fn test_query(&self) -> Option<String> {
+        // xUnit [Fact]/[Theory], NUnit [Test]/[TestCase], MSTest [TestMethod]
+        Some(format!(
+            r#"
+            (method_declaration
+                (attribute_list
+                    (attribute
+                        name: [
+                            (identifier) @test-attr
+                            (qualified_name
+                                name: (identifier) @test-attr
+                            )
+                        ]
+                    )
+                )
+                name: (identifier) @{FUNCTION_NAME}
+                body: (_)
+                (#match? @test-attr "^(Fact|Theory|Test|TestCase|TestMethod)$")
+            ) @{FUNCTION_DEFINITION}
+            "#
+        ))
+    }
+
+    // xUnit / NUnit / MSTest projects are named *.Tests by convention
+    fn is_test_file(&self, filename: &str) -> bool {
+        filename.contains(".Tests/")
+            || filename.contains(".Test/")
+            || filename.ends_with("Tests.cs")
+            || filename.ends_with("Test.cs")
+    }
+
+    fn resolve_import_name(&self, import_name: &str) -> String {
+        import_name
+            .split('.')
+            .next_back()
+            .unwrap_or(import_name)
+            .to_string()
+    }
+
+    fn resolve_import_path(&self, import_path: &str, _current_file: &str) -> String {
+        import_path.replace('.', "/")
+    }
+    // file scoped namespaces, block ones are enclosing scopes
+    fn module_path(&self, _file: &str, code: &str) -> Option<String> {
+        code.lines()
+            .map(|line| line.trim())
+            .find(|line| line.starts_with("namespace ") && line.ends_with(';'))
+            .and_then(|line| declared_package(line, "namespace"))
+    }
+}
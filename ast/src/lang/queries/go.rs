@@ -1,8 +1,9 @@
 use super::super::*;
 use super::consts::*;
+use crate::lang::parse::{declared_package, trim_quotes};
 use anyhow::{Context, Result};
 use lsp::{Cmd as LspCmd, CmdSender, Position, Res as LspRes};
-use tree_sitter::{Language, Parser, Query, Tree};
+use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
 
 pub struct Go(Language);
 
@@ -162,27 +163,136 @@ impl Stack for Go {
     //         vec![q1.to_string()]
     //     }
     fn endpoint_finders(&self) -> Vec<String> {
-        vec![format!(
+        let handler = format!(
+            r#"[
+                (selector_expression
+                    field: (field_identifier) @{HANDLER}
+                )
+                (identifier) @{HANDLER}
+            ]"#
+        );
+        vec![
+            // chi and fiber: r.Get("/path", handler), gin and echo: r.GET("/path", handler)
+            format!(
+                r#"(call_expression
+                    function: (selector_expression
+                        operand: (identifier)
+                        field: (field_identifier) @{ENDPOINT_VERB} (#match? @{ENDPOINT_VERB} "^(Get|Post|Put|Delete|Patch|GET|POST|PUT|DELETE|PATCH)$")
+                    )
+                    arguments: (argument_list
+                        .
+                        (interpreted_string_literal) @{ENDPOINT}
+                        {handler}
+                        .
+                    )
+                ) @{ROUTE}"#
+            ),
+            // gorilla/mux: r.HandleFunc("/path", handler).Methods("GET")
+            format!(
+                r#"(call_expression
+                    function: (selector_expression
+                        operand: (call_expression
+                            function: (selector_expression
+                                field: (field_identifier) @handle-func (#eq? @handle-func "HandleFunc")
+                            )
+                            arguments: (argument_list
+                                .
+                                (interpreted_string_literal) @{ENDPOINT}
+                                .
+                                {handler}
+                                .
+                            )
+                        )
+                        field: (field_identifier) @methods (#eq? @methods "Methods")
+                    )
+                    arguments: (argument_list
+                        .
+                        [
+                            (interpreted_string_literal)
+                            (selector_expression)
+                        ] @{ENDPOINT_VERB}
+                    )
+                ) @{ROUTE}"#
+            ),
+            // net/http: mux.HandleFunc("GET /path", handler), the method is optional since go 1.22
+            format!(
+                r#"(expression_statement
+                    (call_expression
+                        function: (selector_expression
+                            field: (field_identifier) @handle-func (#eq? @handle-func "HandleFunc")
+                        )
+                        arguments: (argument_list
+                            .
+                            (interpreted_string_literal) @{ENDPOINT}
+                            .
+                            {handler}
+                            .
+                        )
+                    ) @{ROUTE}
+                )"#
+            ),
+        ]
+    }
+    // v1 := r.Group("/v1") earlier in the function, then v1.GET("/items", h)
+    fn endpoint_prefix(&self, route: TreeNode, code: &str) -> Option<String> {
+        // the receiver of r.HandleFunc("/x", h).Methods("GET") is r
+        let mut receiver = route;
+        while receiver.kind() == "call_expression" {
+            receiver = receiver
+                .child_by_field_name("function")?
+                .child_by_field_name("operand")?;
+        }
+        group_var_prefix(receiver, code)
+    }
+    fn update_endpoint_verb(&self, nd: &mut NodeData, _call: &Option<String>) {
+        // "GET /tasks/{id}"
+        if let Some((method, path)) = nd.name.clone().split_once(' ') {
+            nd.add_verb(method);
+            nd.name = path.trim().to_string();
+        }
+        match nd.meta.get("verb").cloned() {
+            // .Methods("GET") or .Methods(http.MethodGet)
+            Some(verb) => {
+                let verb = trim_quotes(&verb);
+                let verb = verb.rsplit('.').next().unwrap_or(verb);
+                nd.add_verb(verb.strip_prefix("METHOD").unwrap_or(verb));
+            }
+            None => nd.add_verb("GET"),
+        }
+    }
+    // a router function mounted under a prefix
+    fn endpoint_group_find(&self) -> Option<String> {
+        let group_fn = format!(
             r#"(call_expression
                 function: (selector_expression
-                    operand: (identifier)
-                    field: (field_identifier) @{ENDPOINT_VERB} (#match? @{ENDPOINT_VERB} "^Get$|^Post$|^Put$|^Delete$")
+                    field: (field_identifier) @group-fn (#eq? @group-fn "Group")
                 )
                 arguments: (argument_list
+                    .
                     (interpreted_string_literal) @{ENDPOINT}
-                    [
-                        (selector_expression
-                            field: (field_identifier) @{HANDLER}
+                )
+            )"#
+        );
+        let path_prefix = format!(
+            r#"(call_expression
+                function: (selector_expression
+                    operand: (call_expression
+                        function: (selector_expression
+                            field: (field_identifier) @path-prefix (#eq? @path-prefix "PathPrefix")
                         )
-                        (identifier) @{HANDLER}
-                    ]
+                        arguments: (argument_list
+                            .
+                            (interpreted_string_literal) @{ENDPOINT}
+                        )
+                    )
+                    field: (field_identifier) @subrouter (#eq? @subrouter "Subrouter")
                 )
-            ) @{ROUTE}"#
-        )]
-    }
-    fn endpoint_group_find(&self) -> Option<String> {
+            )"#
+        );
         Some(format!(
-            r#"(call_expression
+            r#"
+            ; chi: r.Mount("/prefix", subRouter())
+            (call_expression
                 function: (selector_expression
                     operand: (identifier)
                     field: (field_identifier) @{ENDPOINT_VERB} (#match? @{ENDPOINT_VERB} "Mount")
@@ -193,7 +303,67 @@ impl Stack for Go {
                         function: (identifier) @{ENDPOINT_GROUP}
                     )
                 )
-            ) @{ROUTE}"#
+            ) @{ROUTE}
+            ; gin, echo, fiber and gorilla: register(r.Group("/prefix"))
+            (call_expression
+                function: (identifier) @{ENDPOINT_GROUP}
+                arguments: (argument_list
+                    .
+                    [
+                        {group_fn}
+                        {path_prefix}
+                    ]
+                    .
+                )
+            ) @{ROUTE}
+            ; g := r.Group("/prefix")
+            ; register(g)
+            (block
+                (short_var_declaration
+                    left: (expression_list
+                        .
+                        (identifier) @group-var
+                    )
+                    right: (expression_list
+                        [
+                            {group_fn}
+                            {path_prefix}
+                        ]
+                    )
+                )
+                (expression_statement
+                    (call_expression
+                        function: (identifier) @{ENDPOINT_GROUP}
+                        arguments: (argument_list
+                            .
+                            (identifier) @group-arg (#eq? @group-arg @group-var)
+                            .
+                        )
+                    ) @{ROUTE}
+                )
+            )
+            ; net/http: mux.Handle("/v2/", http.StripPrefix("/v2", subMux()))
+            (call_expression
+                function: (selector_expression
+                    field: (field_identifier) @handle (#eq? @handle "Handle")
+                )
+                arguments: (argument_list
+                    (call_expression
+                        function: (selector_expression
+                            field: (field_identifier) @strip-prefix (#eq? @strip-prefix "StripPrefix")
+                        )
+                        arguments: (argument_list
+                            .
+                            (interpreted_string_literal) @{ENDPOINT}
+                            .
+                            (call_expression
+                                function: (identifier) @{ENDPOINT_GROUP}
+                            )
+                        )
+                    )
+                )
+            ) @{ROUTE}
+            "#
         ))
     }
     fn find_function_parent(
@@ -212,8 +382,8 @@ impl Stack for Go {
         let nodedata = find_class(parent_type);
         Ok(match nodedata {
             Some(class) => Some(Operand {
-                source: NodeKeys::new(&class.name, &class.file, class.start),
-                target: NodeKeys::new(func_name, file, node.start_position().row),
+                source: NodeKeys::from(&class),
+                target: NodeKeys::at_node(func_name, file, &node),
             }),
             None => None,
         })
@@ -253,6 +423,24 @@ impl Stack for Go {
     // ) @{STRUCT}"
     //         ))
     //     }
+    // go has no enums, a typed const block starting at iota is the idiom
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (const_declaration
+                    (const_spec
+                        name: (identifier) @{ENUM_VARIANT}
+                        type: (type_identifier) @{ENUM_NAME}
+                        value: (expression_list (iota))
+                    )
+                    (const_spec
+                        name: (identifier) @{ENUM_VARIANT}
+                        !value
+                    )*
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             "(type_declaration
@@ -263,6 +451,15 @@ impl Stack for Go {
             ) @{STRUCT}"
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            "(field_declaration
+                name: (field_identifier) @{FIELD_NAME}
+                type: (_) @{FIELD_TYPE}
+                tag: (_)? @{FIELD_ANNOTATION}
+            ) @{FIELD}"
+        ))
+    }
     fn data_model_within_query(&self) -> Option<String> {
         // the surrounding () is required to match the match work
         let type_finder = format!(
@@ -294,6 +491,85 @@ impl Stack for Go {
     fn clean_graph(&self, callback: &mut dyn FnMut(NodeType, NodeType, &str)) {
         callback(NodeType::Class, NodeType::Function, "operand");
     }
+    // qualified by the declared package rather than the path
+    fn module_path(&self, _file: &str, code: &str) -> Option<String> {
+        declared_package(code, "package")
+    }
+}
+
+// the prefix of the group `var` was assigned before it is used, in its own
+// block or one around it within the function. groups of groups add up
+fn group_var_prefix(var: TreeNode, code: &str) -> Option<String> {
+    if var.kind() != "identifier" {
+        return None;
+    }
+    let name = var.utf8_text(code.as_bytes()).ok()?;
+    let mut node = var;
+    while let Some(parent) = node.parent() {
+        if matches!(
+            parent.kind(),
+            "function_declaration" | "method_declaration" | "func_literal"
+        ) {
+            return None;
+        }
+        if parent.kind() == "block" {
+            let mut cursor = parent.walk();
+            let statements = parent.named_children(&mut cursor).collect::<Vec<_>>();
+            let group = statements
+                .into_iter()
+                .rev()
+                .filter(|s| s.end_byte() <= var.start_byte())
+                .find_map(|s| assigned_group(s, name, code));
+            if let Some((receiver, prefix)) = group {
+                let outer = group_var_prefix(receiver, code).unwrap_or_default();
+                return Some(format!("{}{}", outer, prefix));
+            }
+        }
+        node = parent;
+    }
+    None
+}
+
+// name := <group call>
+fn assigned_group<'a>(
+    stmt: TreeNode<'a>,
+    name: &str,
+    code: &str,
+) -> Option<(TreeNode<'a>, String)> {
+    if stmt.kind() != "short_var_declaration" {
+        return None;
+    }
+    let left = stmt.child_by_field_name("left")?.named_child(0)?;
+    if left.utf8_text(code.as_bytes()).ok()? != name {
+        return None;
+    }
+    group_call(stmt.child_by_field_name("right")?.named_child(0)?, code)
+}
+
+// r.Group("/v1"), or gorilla's r.PathPrefix("/v1").Subrouter(), as the
+// receiver and the prefix
+fn group_call<'a>(call: TreeNode<'a>, code: &str) -> Option<(TreeNode<'a>, String)> {
+    if call.kind() != "call_expression" {
+        return None;
+    }
+    let function = call.child_by_field_name("function")?;
+    let operand = function.child_by_field_name("operand")?;
+    match function
+        .child_by_field_name("field")?
+        .utf8_text(code.as_bytes())
+        .ok()?
+    {
+        "Group" | "PathPrefix" => {
+            let path = call.child_by_field_name("arguments")?.named_child(0)?;
+            if path.kind() != "interpreted_string_literal" {
+                return None;
+            }
+            let path = trim_quotes(path.utf8_text(code.as_bytes()).ok()?);
+            Some((operand, path.to_string()))
+        }
+        "Subrouter" => group_call(operand, code),
+        _ => None,
+    }
 }
 
 /*
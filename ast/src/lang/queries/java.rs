@@ -1,5 +1,6 @@
 use super::super::*;
 use super::consts::*;
+use crate::lang::parse::declared_package;
 use anyhow::{Context, Result};
 use tree_sitter::{Language, Parser, Query, Tree};
 
@@ -65,11 +66,7 @@ impl Stack for Java {
                     (superclass 
                         (type_identifier)@{CLASS_PARENT}
                     )?
-                    (super_interfaces
-                        (type_list
-                            (type_identifier)@{INCLUDED_MODULES}
-                        )
-                    )?
+                    (super_interfaces)? @{CLASS_IMPLEMENTS}
                 )
                 "#
         )
@@ -169,6 +166,29 @@ impl Stack for Java {
         // if all else fails, default to GET
         nd.add_verb("GET");
     }
+    fn interface_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (interface_declaration
+                    name: (identifier) @{INTERFACE_NAME}
+                ) @{INTERFACE}
+            "#
+        ))
+    }
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_declaration
+                    name: (identifier) @{ENUM_NAME}
+                    body: (enum_body
+                        (enum_constant
+                            name: (identifier) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             r#"
@@ -188,6 +208,29 @@ impl Stack for Java {
             "#
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (field_declaration
+                    (modifiers)? @{FIELD_MODIFIERS}
+                    type: (_) @{FIELD_TYPE}
+                    declarator: (variable_declarator
+                        name: (identifier) @{FIELD_NAME}
+                        value: (_)? @{FIELD_DEFAULT}
+                    )
+                ) @{FIELD}
+                (record_declaration
+                    parameters: (formal_parameters
+                        (formal_parameter
+                            (modifiers)? @{FIELD_MODIFIERS}
+                            type: (_) @{FIELD_TYPE}
+                            name: (identifier) @{FIELD_NAME}
+                        ) @{FIELD}
+                    )
+                )
+            "#
+        ))
+    }
     fn data_model_within_query(&self) -> Option<String> {
         Some(format!(
             r#"
@@ -228,4 +271,8 @@ impl Stack for Java {
             import_path
         }
     }
+    // qualified by the declared package rather than the path
+    fn module_path(&self, _file: &str, code: &str) -> Option<String> {
+        declared_package(code, "package")
+    }
 }
@@ -1,5 +1,7 @@
 use super::super::*;
 use super::consts::*;
+use crate::lang::metrics::{function_metrics_with, FunctionMetrics};
+use crate::lang::parse::declared_package;
 use anyhow::{Context, Result};
 use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
 
@@ -141,8 +143,8 @@ impl Stack for Kotlin {
                 let query = self.q(&self.identifier_query(), &NodeType::Class);
                 match query_to_ident(query, p, code)? {
                     Some(parent_name) => Some(Operand {
-                        source: NodeKeys::new(&parent_name, file, p.start_position().row),
-                        target: NodeKeys::new(func_name, file, node.start_position().row),
+                        source: NodeKeys::at_node(&parent_name, file, &p),
+                        target: NodeKeys::at_node(func_name, file, &node),
                     }),
                     None => None,
                 }
@@ -217,6 +219,33 @@ impl Stack for Kotlin {
         inst.name = path;
     }
 
+    fn interface_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (class_declaration
+                    "interface"
+                    (type_identifier) @{INTERFACE_NAME}
+                ) @{INTERFACE}
+            "#
+        ))
+    }
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (class_declaration
+                    (modifiers
+                        (class_modifier) @modifier (#eq? @modifier "enum")
+                    )
+                    (type_identifier) @{ENUM_NAME}
+                    (enum_class_body
+                        (enum_entry
+                            (simple_identifier) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             "(class_declaration
@@ -224,6 +253,27 @@ impl Stack for Kotlin {
             ) @{STRUCT}"
         ))
     }
+    // constructor vals and vars, and properties in the class body
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (class_parameter
+                    (modifiers)? @{FIELD_MODIFIERS}
+                    (simple_identifier) @{FIELD_NAME}
+                    [(user_type) (nullable_type)] @{FIELD_TYPE}
+                ) @{FIELD}
+                (class_body
+                    (property_declaration
+                        (modifiers)? @{FIELD_MODIFIERS}
+                        (variable_declaration
+                            (simple_identifier) @{FIELD_NAME}
+                            [(user_type) (nullable_type)]? @{FIELD_TYPE}
+                        )
+                    ) @{FIELD}
+                )
+            "#
+        ))
+    }
 
     fn data_model_path_filter(&self) -> Option<String> {
         Some("app/models".to_string())
@@ -266,6 +316,22 @@ impl Stack for Kotlin {
             import_path
         }
     }
+    // qualified by the declared package rather than the path
+    fn module_path(&self, _file: &str, code: &str) -> Option<String> {
+        declared_package(code, "package")
+    }
+    // when entries, and && and || which have node kinds of their own
+    fn function_metrics(&self, node: TreeNode, code: &str) -> FunctionMetrics {
+        function_metrics_with(
+            node,
+            code,
+            &[
+                "when_entry",
+                "conjunction_expression",
+                "disjunction_expression",
+            ],
+        )
+    }
 }
 fn extract_path_from_url(url: &str) -> String {
     if url == "url" {
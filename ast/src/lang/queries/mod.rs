@@ -1,12 +1,15 @@
 pub mod angular;
 pub mod bash;
+pub mod c;
 pub mod consts;
 pub mod cpp;
+pub mod csharp;
 pub mod erb;
 pub mod go;
 pub mod haml;
 pub mod java;
 pub mod kotlin;
+pub mod php;
 pub mod python;
 mod rails_routes;
 pub mod react;
@@ -19,6 +22,8 @@ pub mod typescript;
 
 use crate::lang::asg::Operand;
 use crate::lang::graphs::Edge;
+use crate::lang::metrics::{function_metrics, FunctionMetrics};
+use crate::lang::parse::{enclosing_scopes, leading_comments, module_from_path};
 use crate::lang::{Function, NodeData, NodeType};
 use anyhow::Result;
 use lsp::Language as LspLanguage;
@@ -76,6 +81,12 @@ pub trait Stack {
     fn trait_query(&self) -> Option<String> {
         None
     }
+    fn interface_query(&self) -> Option<String> {
+        None
+    }
+    fn enum_query(&self) -> Option<String> {
+        None
+    }
     fn class_definition_query(&self) -> String;
     fn instance_definition_query(&self) -> Option<String> {
         None
@@ -104,10 +115,19 @@ pub trait Stack {
     fn data_model_query(&self) -> Option<String> {
         None
     }
+    // names of data models declared by their use in another file, like
+    // EF Core's DbSet<T>. classes with those names become data models
+    fn data_model_registry_query(&self) -> Option<String> {
+        None
+    }
     // data model CONTAINS edge within a function
     fn data_model_within_query(&self) -> Option<String> {
         None
     }
+    // fields and properties declared in classes and data models
+    fn field_query(&self) -> Option<String> {
+        None
+    }
     fn data_model_path_filter(&self) -> Option<String> {
         None
     }
@@ -163,6 +183,16 @@ pub trait Stack {
     fn endpoint_group_find(&self) -> Option<String> {
         None
     }
+    // the prefix of an endpoint group that has no ENDPOINT capture, read
+    // from the tree around its route
+    fn endpoint_group_prefix(&self, _route: TreeNode, _code: &str) -> Option<String> {
+        None
+    }
+    // a prefix an endpoint picks up from the tree around its route, like
+    // the group variable it is registered on
+    fn endpoint_prefix(&self, _route: TreeNode, _code: &str) -> Option<String> {
+        None
+    }
     fn endpoint_path_filter(&self) -> Option<String> {
         None
     }
@@ -175,6 +205,35 @@ pub trait Stack {
     fn is_test_file(&self, _filename: &str) -> bool {
         false
     }
+    fn update_test(&self, _nd: &mut NodeData) {}
+    // doc comments (or docstrings) describing a definition
+    fn find_docs(&self, node: TreeNode, code: &str) -> Option<String> {
+        leading_comments(node, code, &|_| true)
+    }
+    // joins the parts of a qualified name
+    fn qualified_separator(&self) -> &'static str {
+        "."
+    }
+    // module or package that a file's definitions belong to
+    fn module_path(&self, file: &str, _code: &str) -> Option<String> {
+        module_from_path(file, self.qualified_separator())
+    }
+    // true if a call's operand is a type or module path rather than a variable
+    fn operand_is_path(&self) -> bool {
+        false
+    }
+    // the operand as it would appear in a qualified name
+    fn qualified_operand(&self, operand: &str) -> String {
+        operand.to_string()
+    }
+    // classes, impls, namespaces... around a definition, outermost first
+    fn enclosing_scopes(&self, node: TreeNode, code: &str) -> Vec<String> {
+        enclosing_scopes(node, code)
+    }
+    // complexity, nesting, parameters, size, calls and returns of a function definition
+    fn function_metrics(&self, node: TreeNode, code: &str) -> FunctionMetrics {
+        function_metrics(node, code)
+    }
     fn add_endpoint_verb(&self, _nd: &mut NodeData, _call: &Option<String>) {}
     fn update_endpoint_verb(&self, _nd: &mut NodeData, _call: &Option<String>) {}
     // this one should be the same for all langs?
@@ -276,6 +335,9 @@ pub fn treesitter_from_lsp_language(ll: LspLanguage) -> tree_sitter::Language {
         LspLanguage::Svelte => tree_sitter_svelte_ng::LANGUAGE.into(),
         LspLanguage::Angular => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
         LspLanguage::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+        LspLanguage::CSharp => tree_sitter_c_sharp::LANGUAGE.into(),
+        LspLanguage::C => tree_sitter_c::LANGUAGE.into(),
+        LspLanguage::Php => tree_sitter_php::LANGUAGE_PHP.into(),
         // _ => tree_sitter_bash::LANGUAGE.into(),
     }
 }
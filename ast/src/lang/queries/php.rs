@@ -0,0 +1,342 @@
+use super::super::*;
+use super::consts::*;
+use crate::lang::parse::{declared_package, trim_quotes};
+use anyhow::{Context, Result};
+use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
+
+pub struct Php(Language);
+
+impl Php {
+    pub fn new() -> Self {
+        Php(tree_sitter_php::LANGUAGE_PHP.into())
+    }
+}
+
+impl Stack for Php {
+    fn q(&self, q: &str, nt: &NodeType) -> Query {
+        if matches!(nt, NodeType::Library) {
+            Query::new(&tree_sitter_json::LANGUAGE.into(), q).unwrap()
+        } else {
+            Query::new(&self.0, q).unwrap()
+        }
+    }
+
+    fn parse(&self, code: &str, nt: &NodeType) -> Result<Tree> {
+        let mut parser = Parser::new();
+        // composer.json
+        if matches!(nt, NodeType::Library) {
+            parser.set_language(&tree_sitter_json::LANGUAGE.into())?;
+        } else {
+            parser.set_language(&self.0)?;
+        }
+        parser.parse(code, None).context("failed to parse")
+    }
+
+    fn lib_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (pair
+                key: (string (string_content) @require (#match? @require "^require(-dev)?$"))
+                value: (object
+                    (pair
+                        key: (string (string_content) @{LIBRARY_NAME}) (#match? @{LIBRARY_NAME} "/")
+                        value: (string (string_content) @{LIBRARY_VERSION})
+                    ) @{LIBRARY}
+                )
+            )
+            "#
+        ))
+    }
+
+    fn is_lib_file(&self, file_name: &str) -> bool {
+        file_name.contains("/vendor/")
+    }
+
+    fn imports_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (program
+                (namespace_use_declaration
+                    (namespace_use_clause
+                        [
+                            (qualified_name)
+                            (name)
+                        ] @{IMPORTS_NAME} @{IMPORTS_FROM}
+                    )
+                ) @{IMPORTS}
+            )
+            "#
+        ))
+    }
+
+    fn trait_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            [
+                (interface_declaration
+                    name: (name) @{TRAIT_NAME}
+                )
+                (trait_declaration
+                    name: (name) @{TRAIT_NAME}
+                )
+            ] @{TRAIT}
+            "#
+        ))
+    }
+
+    fn class_definition_query(&self) -> String {
+        format!(
+            r#"
+            (class_declaration
+                name: (name) @{CLASS_NAME}
+                (base_clause
+                    [
+                        (name)
+                        (qualified_name)
+                    ] @{CLASS_PARENT}
+                )?
+            ) @{CLASS_DEFINITION}
+            "#
+        )
+    }
+
+    fn function_definition_query(&self) -> String {
+        // abstract and interface methods have no body
+        format!(
+            r#"
+            [
+                (function_definition
+                    name: (name) @{FUNCTION_NAME}
+                    parameters: (formal_parameters) @{ARGUMENTS}
+                    return_type: (_)? @{RETURN_TYPES}
+                )
+                (method_declaration
+                    name: (name) @{FUNCTION_NAME}
+                    parameters: (formal_parameters) @{ARGUMENTS}
+                    return_type: (_)? @{RETURN_TYPES}
+                    body: (compound_statement)
+                )
+            ] @{FUNCTION_DEFINITION}
+            "#
+        )
+    }
+
+    // helper(), $this->users->find(), User::create() and $user?->save()
+    fn function_call_query(&self) -> String {
+        format!(
+            r#"
+            [
+                (function_call_expression
+                    function: [
+                        (name) @{FUNCTION_NAME}
+                        (qualified_name
+                            (name) @{FUNCTION_NAME}
+                        )
+                    ]
+                    arguments: (arguments) @{ARGUMENTS}
+                )
+                (member_call_expression
+                    object: (_) @{OPERAND}
+                    name: (name) @{FUNCTION_NAME}
+                    arguments: (arguments) @{ARGUMENTS}
+                )
+                (nullsafe_member_call_expression
+                    object: (_) @{OPERAND}
+                    name: (name) @{FUNCTION_NAME}
+                    arguments: (arguments) @{ARGUMENTS}
+                )
+                (scoped_call_expression
+                    scope: (_) @{OPERAND}
+                    name: (name) @{FUNCTION_NAME}
+                    arguments: (arguments) @{ARGUMENTS}
+                )
+            ] @{FUNCTION_CALL}
+            "#
+        )
+    }
+
+    fn find_function_parent(
+        &self,
+        node: TreeNode,
+        code: &str,
+        file: &str,
+        func_name: &str,
+        _callback: &dyn Fn(&str) -> Option<NodeData>,
+        _parent_type: Option<&str>,
+    ) -> Result<Option<Operand>> {
+        let mut parent = node.parent();
+        while parent.is_some() && parent.unwrap().kind() != "class_declaration" {
+            parent = parent.unwrap().parent();
+        }
+        let parent_of = match parent {
+            Some(p) => match p.child_by_field_name("name") {
+                Some(name) => Some(Operand {
+                    source: NodeKeys::at_node(name.utf8_text(code.as_bytes())?, file, &p),
+                    target: NodeKeys::at_node(func_name, file, &node),
+                }),
+                None => None,
+            },
+            None => None,
+        };
+        Ok(parent_of)
+    }
+
+    fn endpoint_finders(&self) -> Vec<String> {
+        // Route::get('/users', [UserController::class, 'index'])
+        // Route::get('/users', 'UserController@index')
+        vec![format!(
+            r#"
+            (scoped_call_expression
+                scope: (name) @facade (#eq? @facade "Route")
+                name: (name) @{ENDPOINT_VERB} (#match? @{ENDPOINT_VERB} "^(get|post|put|patch|delete|options)$")
+                arguments: (arguments
+                    .
+                    (argument
+                        (string) @{ENDPOINT}
+                    )
+                    .
+                    (argument
+                        [
+                            (array_creation_expression)
+                            (string)
+                        ] @{HANDLER}
+                    )
+                )
+            ) @{ROUTE}
+            "#
+        )]
+    }
+
+    fn update_endpoint_verb(&self, nd: &mut NodeData, _call: &Option<String>) {
+        // routes/api.php is served under /api by default
+        if nd.file.ends_with("routes/api.php") && !nd.name.starts_with("/api") {
+            nd.name = format!("/api/{}", nd.name.trim_start_matches('/'))
+                .trim_end_matches('/')
+                .to_string();
+        }
+        if let Some(handler) = nd.meta.get("handler").cloned() {
+            if let Some(handler) = controller_action(&handler) {
+                nd.add_handler(&handler);
+            }
+        }
+    }
+
+    fn use_handler_finder(&self) -> bool {
+        true
+    }
+
+    fn handler_finder(
+        &self,
+        endpoint: NodeData,
+        find_fn: &dyn Fn(&str, &str) -> Option<NodeData>,
+        _find_fns_in: &dyn Fn(&str) -> Vec<NodeData>,
+        _handler_params: HandlerParams,
+    ) -> Vec<(NodeData, Option<Edge>)> {
+        if let Some(handler) = endpoint.meta.get("handler") {
+            // UserController@index lives in UserController.php
+            if let Some((controller, action)) = handler.split_once('@') {
+                if let Some(nd) = find_fn(action, &format!("/{}.php", controller)) {
+                    let edge = Edge::handler(&endpoint, &nd);
+                    return vec![(endpoint, Some(edge))];
+                }
+            } else if let Some(nd) = find_fn(handler, &endpoint.file) {
+                let edge = Edge::handler(&endpoint, &nd);
+                return vec![(endpoint, Some(edge))];
+            }
+        }
+        vec![(endpoint, None)]
+    }
+
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_declaration
+                    name: (name) @{ENUM_NAME}
+                    body: (enum_declaration_list
+                        (enum_case
+                            name: (name) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
+    fn data_model_query(&self) -> Option<String> {
+        // Eloquent models
+        Some(format!(
+            r#"
+            (class_declaration
+                name: (name) @{STRUCT_NAME}
+                (base_clause
+                    (name) @model (#match? @model "^(Model|Authenticatable|Pivot)$")
+                )
+            ) @{STRUCT}
+            "#
+        ))
+    }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (property_declaration
+                    (visibility_modifier)? @{FIELD_MODIFIERS}
+                    type: (_)? @{FIELD_TYPE}
+                    (property_element
+                        (variable_name (name) @{FIELD_NAME})
+                    )
+                ) @{FIELD}
+            "#
+        ))
+    }
+
+    fn data_model_within_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            (name) @{STRUCT_NAME}
+            "#
+        ))
+    }
+
+    // PHPUnit runs public test* methods of *Test classes
+    fn is_test(&self, func_name: &str, func_file: &str) -> bool {
+        func_name.starts_with("test") && self.is_test_file(func_file)
+    }
+
+    fn is_test_file(&self, filename: &str) -> bool {
+        filename.contains("/tests/") || filename.ends_with("Test.php")
+    }
+
+    fn resolve_import_name(&self, import_name: &str) -> String {
+        import_name
+            .split('\\')
+            .next_back()
+            .unwrap_or(import_name)
+            .to_string()
+    }
+
+    fn resolve_import_path(&self, import_path: &str, _current_file: &str) -> String {
+        import_path.replace('\\', "/")
+    }
+    fn qualified_separator(&self) -> &'static str {
+        "\\"
+    }
+    fn module_path(&self, _file: &str, code: &str) -> Option<String> {
+        declared_package(code, "namespace")
+    }
+}
+
+// "[UserController::class, 'index']" or "App\Http\Controllers\UserController@index"
+// become "UserController@index"
+fn controller_action(handler: &str) -> Option<String> {
+    let (controller, action) = if handler.starts_with('[') {
+        let inner = handler.trim_start_matches('[').trim_end_matches(']');
+        let (class, action) = inner.split_once(',')?;
+        (
+            class.trim().trim_end_matches("::class"),
+            trim_quotes(action),
+        )
+    } else {
+        handler.split_once('@')?
+    };
+    let controller = controller.rsplit('\\').next().unwrap_or(controller);
+    Some(format!("{}@{}", controller, action))
+}
@@ -1,5 +1,6 @@
 use super::super::*;
 use super::consts::*;
+use crate::lang::parse::{leading_comments, module_from_path};
 use anyhow::{Context, Result};
 use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
 
@@ -111,8 +112,8 @@ impl Stack for Python {
                 let query = self.q(&self.identifier_query(), &NodeType::Class);
                 match query_to_ident(query, p, code)? {
                     Some(parent_name) => Some(Operand {
-                        source: NodeKeys::new(&parent_name, file, p.start_position().row),
-                        target: NodeKeys::new(func_name, file, node.start_position().row),
+                        source: NodeKeys::at_node(&parent_name, file, &p),
+                        target: NodeKeys::at_node(func_name, file, &node),
                     }),
                     None => None,
                 }
@@ -253,6 +254,22 @@ impl Stack for Python {
              ) @{STRUCT}"
         ))
     }
+    // class attributes, like sqlalchemy columns or pydantic fields
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            "(class_definition
+                body: (block
+                    (expression_statement
+                        (assignment
+                            left: (identifier) @{FIELD_NAME}
+                            type: (_)? @{FIELD_TYPE}
+                            right: (_)? @{FIELD_DEFAULT}
+                        ) @{FIELD}
+                    )
+                )
+            )"
+        ))
+    }
 
     fn data_model_within_query(&self) -> Option<String> {
         Some(format!(
@@ -280,6 +297,39 @@ impl Stack for Python {
     fn is_test(&self, func_name: &str, _func_file: &str) -> bool {
         func_name.starts_with("test_")
     }
+    // the docstring is the first statement of the body
+    fn find_docs(&self, node: TreeNode, code: &str) -> Option<String> {
+        let def = match node.kind() {
+            "decorated_definition" => node.child_by_field_name("definition")?,
+            _ => node,
+        };
+        let docstring = def
+            .child_by_field_name("body")
+            .and_then(|b| b.named_child(0))
+            .filter(|s| s.kind() == "expression_statement")
+            .and_then(|s| s.named_child(0))
+            .filter(|s| s.kind() == "string");
+        match docstring {
+            Some(ds) => {
+                let text = ds.utf8_text(code.as_bytes()).ok()?;
+                let text = text
+                    .trim_start_matches(|c| "rRuUbB".contains(c))
+                    .trim_matches(|c| c == '"' || c == '\'');
+                let docs = text
+                    .lines()
+                    .map(|l| l.trim())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                Some(docs.trim().to_string()).filter(|d| !d.is_empty())
+            }
+            None => leading_comments(node, code, &|_| true),
+        }
+    }
+    // a package's __init__.py stands for the package
+    fn module_path(&self, file: &str, _code: &str) -> Option<String> {
+        let file = file.trim_end_matches("__init__.py").trim_end_matches('/');
+        module_from_path(file, ".")
+    }
     fn handler_finder(
         &self,
         endpoint: NodeData,
@@ -124,6 +124,9 @@ impl Stack for ReactTs {
         format!(
             "(class_declaration
                 name: (type_identifier) @{CLASS_NAME}
+                (class_heritage
+                    (implements_clause) @{CLASS_IMPLEMENTS}
+                )?
             ) @{CLASS_DEFINITION}"
         )
     }
@@ -260,15 +263,38 @@ impl Stack for ReactTs {
             ] @{FUNCTION_DEFINITION}"#
         )
     }
+    fn interface_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (interface_declaration
+                    name: (type_identifier) @{INTERFACE_NAME}
+                ) @{INTERFACE}
+            "#
+        ))
+    }
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_declaration
+                    name: (identifier) @{ENUM_NAME}
+                    body: (enum_body
+                        [
+                            (property_identifier) @{ENUM_VARIANT}
+                            (enum_assignment
+                                name: (property_identifier) @{ENUM_VARIANT}
+                            )
+                        ]*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             r#"[
                 (type_alias_declaration
                     name: (type_identifier) @{STRUCT_NAME}
                 ) @{STRUCT}
-                (interface_declaration
-                    name: (type_identifier) @{STRUCT_NAME}
-                ) @{STRUCT}
                 ;; sequelize
                 (class_declaration
                     name: (type_identifier) @{STRUCT_NAME}
@@ -292,6 +318,22 @@ impl Stack for ReactTs {
             ]"#
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (public_field_definition
+                    (accessibility_modifier)? @{FIELD_MODIFIERS}
+                    name: (_) @{FIELD_NAME}
+                    type: (type_annotation (_) @{FIELD_TYPE})?
+                    value: (_)? @{FIELD_DEFAULT}
+                ) @{FIELD}
+                (property_signature
+                    name: (_) @{FIELD_NAME}
+                    type: (type_annotation (_) @{FIELD_TYPE})?
+                ) @{FIELD}
+            "#
+        ))
+    }
     fn data_model_within_query(&self) -> Option<String> {
         Some(format!(
             r#"(
@@ -475,8 +517,8 @@ impl Stack for ReactTs {
                 let query = self.q("(type_identifier) @class_name", &NodeType::Class);
                 match query_to_ident(query, p, code)? {
                     Some(parent_name) => Some(Operand {
-                        source: NodeKeys::new(&parent_name, file, p.start_position().row),
-                        target: NodeKeys::new(func_name, file, node.start_position().row),
+                        source: NodeKeys::at_node(&parent_name, file, &p),
+                        target: NodeKeys::at_node(func_name, file, &node),
                     }),
                     None => None,
                 }
@@ -1,6 +1,7 @@
 use super::super::*;
 use super::consts::*;
 use crate::builder::get_page_name;
+use crate::lang::metrics::{function_metrics_with, FunctionMetrics};
 use crate::lang::parse::trim_quotes;
 use crate::lang::queries::rails_routes;
 use anyhow::{Context, Result};
@@ -9,7 +10,7 @@ use inflection_rs::inflection;
 use std::collections::BTreeMap;
 use std::path::Path;
 use tracing::debug;
-use tree_sitter::{Language, Parser, Query, Tree};
+use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
 
 pub struct Ruby(Language);
 
@@ -166,8 +167,8 @@ impl Stack for Ruby {
                 let query = self.q(&self.identifier_query(), &NodeType::Class);
                 match query_to_ident(query, p, code)? {
                     Some(parent_name) => Some(Operand {
-                        source: NodeKeys::new(&parent_name, file, p.start_position().row),
-                        target: NodeKeys::new(func_name, file, node.start_position().row),
+                        source: NodeKeys::at_node(&parent_name, file, &p),
+                        target: NodeKeys::at_node(func_name, file, &node),
                     }),
                     None => None,
                 }
@@ -210,6 +211,27 @@ impl Stack for Ruby {
             )"#
         ))
     }
+    // columns in db/schema.rb, and attr_accessor and friends
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (call
+                    receiver: (identifier) @table (#eq? @table "t")
+                    method: (identifier) @{FIELD_TYPE}
+                    arguments: (argument_list
+                        .
+                        (string (string_content) @{FIELD_NAME})
+                    )
+                ) @{FIELD}
+                (call
+                    method: (identifier) @attr (#match? @attr "^attr_(accessor|reader|writer)$")
+                    arguments: (argument_list
+                        (simple_symbol) @{FIELD_NAME}
+                    )
+                ) @{FIELD}
+            "#
+        ))
+    }
     fn data_model_path_filter(&self) -> Option<String> {
         Some("db/schema.rb".to_string())
     }
@@ -533,6 +555,21 @@ impl Stack for Ruby {
 
         inflection::camelize(name)
     }
+    // constants are namespaced by their modules and classes, not by file
+    fn qualified_separator(&self) -> &'static str {
+        "::"
+    }
+    fn module_path(&self, _file: &str, _code: &str) -> Option<String> {
+        None
+    }
+    // `return if done`, `save unless valid?`, `retry while busy?`
+    fn function_metrics(&self, node: TreeNode, code: &str) -> FunctionMetrics {
+        function_metrics_with(
+            node,
+            code,
+            &["if_modifier", "unless_modifier", "while_modifier"],
+        )
+    }
 }
 
 fn remove_all_extensions(path: &Path) -> String {
@@ -1,8 +1,9 @@
 use super::super::*;
 use super::consts::*;
+use crate::lang::parse::{leading_comments, module_from_path, trim_quotes};
 use anyhow::{Context, Result};
 use toml::Toml;
-use tree_sitter::{Language, Parser, Query, Tree};
+use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
 pub struct Rust(Language);
 
 impl Rust {
@@ -140,24 +141,51 @@ impl Stack for Rust {
             "#
         )
     }
+    fn test_query(&self) -> Option<String> {
+        // #[test], #[tokio::test], #[rstest]... helpers in #[cfg(test)] modules stay functions
+        Some(format!(
+            r#"
+            (
+                (attribute_item
+                    (attribute
+                        [
+                            (identifier) @test-attr
+                            (scoped_identifier
+                                name: (identifier) @test-attr
+                            )
+                        ]
+                    )
+                )
+                .
+                (attribute_item)*
+                .
+                (function_item
+                    name: (identifier) @{FUNCTION_NAME}
+                ) @{FUNCTION_DEFINITION}
+                (#match? @test-attr "^(test|rstest|test_case)$")
+            )
+            "#
+        ))
+    }
+
     fn function_call_query(&self) -> String {
         format!(
             r#"
                 (call_expression
                     function: [
-                        (identifier) @FUNCTION_NAME
+                        (identifier) @{FUNCTION_NAME}
                         ;; module method
                         (scoped_identifier
-                            path: (identifier) @PARENT_NAME
-                            name: (identifier) @FUNCTION_NAME
+                            path: (_) @{OPERAND}
+                            name: (identifier) @{FUNCTION_NAME}
                         )
                         ;; chained call
                         (field_expression
-                            field: (field_identifier) @FUNCTION_NAME
+                            field: (field_identifier) @{FUNCTION_NAME}
                         )
                     ]
-                    arguments: (arguments) @ARGUMENTS
-                ) @FUNCTION_CALL
+                    arguments: (arguments) @{ARGUMENTS}
+                ) @{FUNCTION_CALL}
                 "#
         )
     }
@@ -193,20 +221,6 @@ impl Stack for Rust {
                 ) @direct_method_route
         "#
             ),
-            // Nested routes (.nest("/base", Router...))
-            format!(
-                r#"
-                (call_expression
-                    function: (field_expression
-                        field: (field_identifier) @nest_method (#eq? @nest_method "nest")
-                    )
-                    arguments: (arguments
-                        (string_literal) @base_path
-                        (_) @nested_router
-                    )
-                ) @nested_route
-                "#
-            ),
             // Actix endpoint finder (#[get("/path")])
             format!(
                 r#"
@@ -229,6 +243,73 @@ impl Stack for Rust {
         ]
     }
 
+    // routers and handlers mounted under a prefix
+    fn endpoint_group_find(&self) -> Option<String> {
+        Some(format!(
+            r#"
+            ; axum: .nest("/api", create_router())
+            (call_expression
+                function: (field_expression
+                    field: (field_identifier) @{ENDPOINT_VERB} (#eq? @{ENDPOINT_VERB} "nest")
+                )
+                arguments: (arguments
+                    (string_literal) @{ENDPOINT}
+                    (call_expression
+                        function: [
+                            (identifier) @{ENDPOINT_GROUP}
+                            (scoped_identifier
+                                name: (identifier) @{ENDPOINT_GROUP}
+                            )
+                        ]
+                    )
+                )
+            ) @{ROUTE}
+
+            ; actix: web::scope("/api").service(get_person).service(create_person),
+            ; with the prefix read by endpoint_group_prefix
+            (call_expression
+                function: (field_expression
+                    value: (_) @scope-chain (#match? @scope-chain "^(web::)?scope\\(")
+                    field: (field_identifier) @{ENDPOINT_VERB} (#eq? @{ENDPOINT_VERB} "service")
+                )
+                arguments: (arguments
+                    (identifier) @{ENDPOINT_GROUP}
+                )
+            ) @{ROUTE}
+
+            ; rocket: .mount("/api", routes![get_person, create_person])
+            (call_expression
+                function: (field_expression
+                    field: (field_identifier) @{ENDPOINT_VERB} (#eq? @{ENDPOINT_VERB} "mount")
+                )
+                arguments: (arguments
+                    (string_literal) @{ENDPOINT}
+                    (macro_invocation
+                        macro: (identifier) @routes-macro (#eq? @routes-macro "routes")
+                        (token_tree
+                            (identifier) @{ENDPOINT_GROUP}
+                        )
+                    )
+                )
+            ) @{ROUTE}
+            "#
+        ))
+    }
+
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_item
+                    name: (type_identifier) @{ENUM_NAME}
+                    body: (enum_variant_list
+                        (enum_variant
+                            name: (identifier) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             r#"
@@ -238,6 +319,17 @@ impl Stack for Rust {
             "#
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (field_declaration
+                    (visibility_modifier)? @{FIELD_MODIFIERS}
+                    name: (field_identifier) @{FIELD_NAME}
+                    type: (_) @{FIELD_TYPE}
+                ) @{FIELD}
+            "#
+        ))
+    }
     fn data_model_within_query(&self) -> Option<String> {
         Some(format!(
             r#"
@@ -291,6 +383,82 @@ impl Stack for Rust {
         }
     }
 
+    // actix scopes nest, so the prefix joins the scope the .service() chain
+    // starts with and those of every chain it is passed into
+    fn endpoint_group_prefix(&self, route: TreeNode, code: &str) -> Option<String> {
+        let mut prefixes = vec![chain_scope(route, code)?];
+        let mut node = route;
+        while let Some(parent) = node.parent() {
+            if parent.kind() == "arguments" {
+                if let Some(prefix) = parent.parent().and_then(|c| chain_scope(c, code)) {
+                    prefixes.push(prefix);
+                }
+            }
+            node = parent;
+        }
+        prefixes.reverse();
+        Some(prefixes.concat())
+    }
+
+    fn update_endpoint_verb(&self, endpoint: &mut NodeData, _call: &Option<String>) {
+        if !endpoint.meta.contains_key("group") {
+            return;
+        }
+        // mount("/", ...) shouldn't double the slash
+        endpoint.name = endpoint.name.trim_end_matches('/').to_string();
+    }
+
+    // only /// and /** */ are doc comments
+    fn find_docs(&self, node: TreeNode, code: &str) -> Option<String> {
+        leading_comments(node, code, &|c| {
+            (c.starts_with("///") && !c.starts_with("////")) || c.starts_with("/**")
+        })
+    }
+
+    fn qualified_separator(&self) -> &'static str {
+        "::"
+    }
+    // src/routes/mod.rs => routes, and main.rs or lib.rs are the crate root
+    fn module_path(&self, file: &str, _code: &str) -> Option<String> {
+        let path = match file.rfind("src/") {
+            Some(i) if i == 0 || file[..i].ends_with('/') => &file[i + 4..],
+            _ => file,
+        };
+        let module = module_from_path(path, "::")?;
+        let mut parts = module.split("::").collect::<Vec<_>>();
+        if matches!(parts.last(), Some(&("mod" | "lib" | "main"))) {
+            parts.pop();
+        }
+        (!parts.is_empty()).then(|| parts.join("::"))
+    }
+
+    fn operand_is_path(&self) -> bool {
+        true
+    }
+    // crate::db::Database => db::Database, and Vec::<u8> => Vec
+    fn qualified_operand(&self, operand: &str) -> String {
+        let operand = operand.split('<').next().unwrap_or(operand);
+        operand
+            .trim_end_matches("::")
+            .split("::")
+            .skip_while(|p| matches!(*p, "crate" | "super" | "self"))
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    // integration tests live in their own crates under tests/
+    fn is_test_file(&self, filename: &str) -> bool {
+        filename.starts_with("tests/") || filename.contains("/tests/")
+    }
+
+    fn update_test(&self, nd: &mut NodeData) {
+        if self.is_test_file(&nd.file) {
+            nd.add_test_type("integration");
+        } else {
+            nd.add_test_type("unit");
+        }
+    }
+
     fn clean_graph(&self, callback: &mut dyn FnMut(NodeType, NodeType, &str)) {
         callback(NodeType::Class, NodeType::Function, "operand");
     }
@@ -300,3 +468,26 @@ impl Stack for Rust {
         path
     }
 }
+
+// web::scope("/api").service(a).service(b) -> /api, from the call the
+// method chain starts with
+fn chain_scope(call: TreeNode, code: &str) -> Option<String> {
+    let mut call = call;
+    loop {
+        if call.kind() != "call_expression" {
+            return None;
+        }
+        let function = call.child_by_field_name("function")?;
+        if function.kind() == "field_expression" {
+            call = function.child_by_field_name("value")?;
+            continue;
+        }
+        let name = function.utf8_text(code.as_bytes()).ok()?;
+        if name != "scope" && !name.ends_with("::scope") {
+            return None;
+        }
+        let arguments = call.child_by_field_name("arguments")?;
+        let path = arguments.named_child(0)?;
+        return Some(trim_quotes(path.utf8_text(code.as_bytes()).ok()?).to_string());
+    }
+}
@@ -95,8 +95,8 @@ impl Stack for Svelte {
                 let query = self.q("(type_identifier) @class_name", &NodeType::Class);
                 match query_to_ident(query, p, code)? {
                     Some(parent_name) => Some(Operand {
-                        source: NodeKeys::new(&parent_name, file, p.start_position().row),
-                        target: NodeKeys::new(func_name, file, node.start_position().row),
+                        source: NodeKeys::at_node(&parent_name, file, &p),
+                        target: NodeKeys::at_node(func_name, file, &node),
                     }),
                     None => None,
                 }
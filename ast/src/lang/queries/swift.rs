@@ -1,5 +1,6 @@
 use super::super::*;
 use super::consts::*;
+use crate::lang::metrics::{function_metrics_with, FunctionMetrics};
 use anyhow::{Context, Result};
 use tree_sitter::{Language, Node as TreeNode, Parser, Query, Tree};
 
@@ -97,8 +98,8 @@ impl Stack for Swift {
                 let query = self.q("(type_identifier) @class-name", &NodeType::Class);
                 match query_to_ident(query, p, code)? {
                     Some(parent_name) => Some(Operand {
-                        source: NodeKeys::new(&parent_name, file, p.start_position().row),
-                        target: NodeKeys::new(func_name, file, node.start_position().row),
+                        source: NodeKeys::at_node(&parent_name, file, &p),
+                        target: NodeKeys::at_node(func_name, file, &node),
                     }),
                     None => None,
                 }
@@ -152,6 +153,30 @@ impl Stack for Swift {
         }
     }
 
+    fn interface_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (protocol_declaration
+                    name: (type_identifier) @{INTERFACE_NAME}
+                ) @{INTERFACE}
+            "#
+        ))
+    }
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (class_declaration
+                    declaration_kind: "enum"
+                    name: (type_identifier) @{ENUM_NAME}
+                    body: (enum_class_body
+                        (enum_entry
+                            name: (simple_identifier) @{ENUM_VARIANT}
+                        )*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
     fn data_model_query(&self) -> Option<String> {
         Some(format!(
             r#"
@@ -162,6 +187,20 @@ impl Stack for Swift {
         "#
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (class_body
+                    (property_declaration
+                        (modifiers)? @{FIELD_MODIFIERS}
+                        name: (pattern (simple_identifier) @{FIELD_NAME})
+                        (type_annotation (_) @{FIELD_TYPE})?
+                        value: (_)? @{FIELD_DEFAULT}
+                    ) @{FIELD}
+                )
+            "#
+        ))
+    }
 
     fn data_model_path_filter(&self) -> Option<String> {
         Some("CoreData".to_string())
@@ -184,4 +223,7 @@ impl Stack for Swift {
     fn is_test(&self, func_name: &str, _func_file: &str) -> bool {
         func_name.starts_with("test")
     }
+    fn function_metrics(&self, node: TreeNode, code: &str) -> FunctionMetrics {
+        function_metrics_with(node, code, &["switch_entry"])
+    }
 }
@@ -124,9 +124,7 @@ impl Stack for TypeScript {
             (class_declaration
                 name: (type_identifier) @{CLASS_NAME}
                 (class_heritage
-                    (implements_clause
-                    (type_identifier) @{PARENT_NAME}
-                    )?
+                    (implements_clause) @{CLASS_IMPLEMENTS}
                 )?
             ) @{CLASS_DEFINITION}
             "#
@@ -193,14 +191,35 @@ impl Stack for TypeScript {
         }
     }
 
-    fn data_model_query(&self) -> Option<String> {
+    fn interface_query(&self) -> Option<String> {
         Some(format!(
             r#"
-                ;; Find TypeScript interfaces related to the model
                 (interface_declaration
-                    name: (type_identifier) @{STRUCT_NAME} 
-                    body: (interface_body) @{STRUCT}
-                )
+                    name: (type_identifier) @{INTERFACE_NAME}
+                ) @{INTERFACE}
+            "#
+        ))
+    }
+    fn enum_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (enum_declaration
+                    name: (identifier) @{ENUM_NAME}
+                    body: (enum_body
+                        [
+                            (property_identifier) @{ENUM_VARIANT}
+                            (enum_assignment
+                                name: (property_identifier) @{ENUM_VARIANT}
+                            )
+                        ]*
+                    )
+                ) @{ENUM}
+            "#
+        ))
+    }
+    fn data_model_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
                 ;; sequelize
                 (class_declaration
                     name: (type_identifier) @{STRUCT_NAME}
@@ -224,6 +243,22 @@ impl Stack for TypeScript {
              "#
         ))
     }
+    fn field_query(&self) -> Option<String> {
+        Some(format!(
+            r#"
+                (public_field_definition
+                    (accessibility_modifier)? @{FIELD_MODIFIERS}
+                    name: (_) @{FIELD_NAME}
+                    type: (type_annotation (_) @{FIELD_TYPE})?
+                    value: (_)? @{FIELD_DEFAULT}
+                ) @{FIELD}
+                (property_signature
+                    name: (_) @{FIELD_NAME}
+                    type: (type_annotation (_) @{FIELD_TYPE})?
+                ) @{FIELD}
+            "#
+        ))
+    }
     fn data_model_within_query(&self) -> Option<String> {
         Some(format!(
             r#"(
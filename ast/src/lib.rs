@@ -1,5 +1,6 @@
 mod builder;
-mod gat;
+pub mod export;
+pub mod gat;
 pub mod lang;
 pub mod repo;
 pub mod utils;
@@ -43,6 +43,21 @@ fn main() -> Result<()> {
         println!("{}\n", dm_query);
     }
 
+    if let Some(iq) = lang.lang().interface_query() {
+        println!("=======> Interface query <=======");
+        println!("{}\n", iq);
+    }
+
+    if let Some(eq) = lang.lang().enum_query() {
+        println!("=======> Enum query <=======");
+        println!("{}\n", eq);
+    }
+
+    if let Some(fq) = lang.lang().field_query() {
+        println!("=======> Field query <=======");
+        println!("{}\n", fq);
+    }
+
     if let Some(dm_finder) = lang.lang().data_model_within_query() {
         println!("=======> Data model finder <=======");
         println!("{}\n", dm_finder);
@@ -1,4 +1,7 @@
+use crate::gat::FileChange;
 use crate::lang::graphs::Graph;
+use crate::lang::dependencies::LayerConfig;
+use crate::lang::features::FeatureConfig;
 use crate::lang::{linker, ArrayGraph, BTreeMapGraph, Lang};
 use anyhow::{anyhow, Context, Result};
 use git_url_parse::GitUrl;
@@ -10,6 +13,7 @@ use tracing::{info, warn};
 use walkdir::{DirEntry, WalkDir};
 
 const CONF_FILE_PATH: &str = ".ast.json";
+const CPP_SOURCE_EXTS: [&str; 5] = ["cpp", "cc", "cxx", "hpp", "hh"];
 
 pub async fn clone_repo(
     url: &str,
@@ -40,7 +44,7 @@ impl Repos {
         self.build_graphs_inner::<BTreeMapGraph>().await
     }
     pub async fn build_graphs_inner<G: Graph>(&self) -> Result<G> {
-        let mut graph = G::new();
+        let mut graph = G::try_new()?;
         for repo in &self.0 {
             info!("building graph for {:?}", repo);
             let subgraph = repo.build_graph_inner().await?;
@@ -54,6 +58,7 @@ impl Repos {
 
         let (nodes_size, edges_size) = graph.get_graph_size();
         println!("Final Graph: {} nodes and {} edges", nodes_size, edges_size);
+        graph.check_writes()?;
         Ok(graph)
     }
 }
@@ -67,6 +72,10 @@ pub struct AstConfig {
     pub only_include_files: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_empty")]
     pub skip_file_ends: Option<Vec<String>>,
+    // feature detection only runs when this is set
+    pub features: Option<FeatureConfig>,
+    // import rules between parts of the repo, checked by analyze_dependencies
+    pub layers: Option<LayerConfig>,
 }
 
 // actual config (merged with lang-specific configs)
@@ -178,7 +187,12 @@ impl Repo {
                     .any(|pkg_file| fname.ends_with(pkg_file));
                 found_pkg_file
             });
-            if has_pkg_file {
+            // a Makefile alone doesn't make a C project, it needs .c sources
+            let has_sources = l != Language::C
+                || source_files
+                    .iter()
+                    .any(|f| f.extension().is_some_and(|e| e == "c"));
+            if has_pkg_file && has_sources {
                 // Don't add duplicate languages
                 if !detected_langs.iter().any(|lang| lang == &l) {
                     detected_langs.push(l);
@@ -298,6 +312,9 @@ impl Repo {
             only_include_files.extend(self.files_filter.clone());
         }
         let mut exts = self.lang.kind.exts();
+        if !owns_headers(&self.lang.kind, &self.root) {
+            exts.retain(|e| *e != "h");
+        }
         exts.push("md");
         Config {
             skip_dirs,
@@ -320,7 +337,7 @@ impl Repo {
             .collect();
         Ok(dirs)
     }
-    fn read_config_file(&self) -> Option<AstConfig> {
+    pub fn read_config_file(&self) -> Option<AstConfig> {
         let config_path = self.root.join(CONF_FILE_PATH);
         match std::fs::read_to_string(&config_path) {
             Ok(s) => match serde_json::from_str::<AstConfig>(&s) {
@@ -384,6 +401,28 @@ fn walk_dirs(dir: &PathBuf, conf: &Config) -> Result<Vec<PathBuf>> {
     Ok(dirs)
 }
 
+// .h headers are shared by C and C++: they go to Cpp if the repo has any C++ sources, otherwise to C
+fn owns_headers(lang: &Language, root: &PathBuf) -> bool {
+    let has_cpp_sources = || {
+        WalkDir::new(root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|e| !skip_dir(e, &Vec::new()))
+            .filter_map(|e| e.ok())
+            .any(|e| {
+                e.path()
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| CPP_SOURCE_EXTS.contains(&ext))
+            })
+    };
+    match lang {
+        Language::C => !has_cpp_sources(),
+        Language::Cpp => has_cpp_sources(),
+        _ => true,
+    }
+}
+
 fn is_hidden(entry: &DirEntry) -> bool {
     entry
         .file_name()
@@ -508,6 +547,18 @@ pub fn check_revs_files(repo_path: &str, mut revs: Vec<String>) -> Option<Vec<St
     crate::gat::get_changed_files(repo_path, old_rev, new_rev).ok()
 }
 
+pub fn check_revs_changes(repo_path: &str, mut revs: Vec<String>) -> Option<Vec<FileChange>> {
+    if revs.len() == 0 {
+        return None;
+    }
+    if revs.len() == 1 {
+        revs.push("HEAD".into());
+    }
+    let old_rev = revs.get(0)?;
+    let new_rev = revs.get(1)?;
+    crate::gat::get_file_changes(repo_path, old_rev, new_rev).ok()
+}
+
 fn walk_files_arbitrary(dir: &PathBuf, directive: impl Fn(&str) -> bool) -> Result<Vec<String>> {
     let mut source_files: Vec<String> = Vec::new();
     for entry in WalkDir::new(dir).min_depth(1).into_iter() {
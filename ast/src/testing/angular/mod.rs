@@ -17,8 +17,8 @@ pub async fn test_angular_generic<G: Graph>() -> Result<(), anyhow::Error> {
     let graph = repo.build_graph_inner::<G>().await?;
 
     let (num_nodes, num_edges) = graph.get_graph_size();
-    assert_eq!(num_nodes, 80, "Expected 80 nodes");
-    assert_eq!(num_edges, 90, "Expected 90 edges");
+    assert_eq!(num_nodes, 90, "Expected 90 nodes");
+    assert_eq!(num_edges, 100, "Expected 100 edges");
 
     let imports = graph.find_nodes_by_type(NodeType::Import);
     assert_eq!(imports.len(), 10, "Expected 10 imports");
@@ -42,10 +42,12 @@ import {{ AppComponent }} from './app/app.component';"#
     assert_eq!(classes.len(), 5, "Expected 5 classes");
 
     let data_models = graph.find_nodes_by_type(NodeType::DataModel);
-    assert_eq!(data_models.len(), 1, "Expected 1 data model");
+    assert_eq!(data_models.len(), 0, "Interfaces are not data models");
+    let interfaces = graph.find_nodes_by_type(NodeType::Interface);
+    assert_eq!(interfaces.len(), 1, "Expected 1 interface");
     assert_eq!(
-        data_models[0].name, "Person",
-        "Data model name should be 'Person'"
+        interfaces[0].name, "Person",
+        "Interface name should be 'Person'"
     );
 
     let functions = graph.find_nodes_by_type(NodeType::Function);
@@ -74,16 +76,16 @@ import {{ AppComponent }} from './app/app.component';"#
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_angular() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_angular_generic::<ArrayGraph>().await.unwrap();
     test_angular_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_angular_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_angular_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
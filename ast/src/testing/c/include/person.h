@@ -0,0 +1,25 @@
+#ifndef PERSON_H
+#define PERSON_H
+
+#define MAX_NAME 64
+#define PERSON_VALID(p) ((p) != NULL && (p)->id > 0)
+
+struct person {
+    int id;
+    char name[MAX_NAME];
+};
+
+union person_key {
+    int id;
+    char name[MAX_NAME];
+};
+
+typedef struct {
+    struct person *items;
+    int count;
+} person_list;
+
+void person_init(struct person *p, int id, const char *name);
+void person_print(const struct person *p);
+
+#endif
@@ -0,0 +1,13 @@
+#ifndef STORAGE_H
+#define STORAGE_H
+
+#include "person.h"
+
+struct storage_ops {
+    int (*save)(const struct person *p);
+    struct person *(*load)(int id);
+};
+
+extern const struct storage_ops memory_storage;
+
+#endif
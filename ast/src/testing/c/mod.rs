@@ -0,0 +1,170 @@
+use crate::lang::graphs::{EdgeType, NodeType};
+use crate::lang::Graph;
+use crate::{lang::Lang, repo::Repo};
+use std::str::FromStr;
+
+pub async fn test_c_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let repo = Repo::new(
+        "src/testing/c",
+        Lang::from_str("c").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+
+    let graph = repo.build_graph_inner::<G>().await?;
+
+    let (num_nodes, num_edges) = graph.get_graph_size();
+    assert_eq!(num_nodes, 43, "Expected 43 nodes");
+    assert_eq!(num_edges, 57, "Expected 57 edges");
+
+    let language_nodes = graph.find_nodes_by_type(NodeType::Language);
+    assert_eq!(language_nodes.len(), 1, "Expected 1 language node");
+    assert_eq!(
+        language_nodes[0].name, "c",
+        "Language node name should be 'c'"
+    );
+
+    let files = graph.find_nodes_by_type(NodeType::File);
+    assert!(
+        files
+            .iter()
+            .any(|f| f.name == "Makefile" && f.file == "src/testing/c/Makefile"),
+        "Makefile should be a package file"
+    );
+    assert!(
+        files.iter().any(|f| f.name == "person.h"),
+        "Headers should belong to C when there are no C++ sources"
+    );
+
+    let imports = graph.find_nodes_by_type(NodeType::Import);
+    assert_eq!(imports.len(), 5, "Expected 5 imports");
+
+    let main_import_body = format!(
+        r#"#include <stdio.h>
+
+#include "person.h"
+
+#include "storage.h"
+"#
+    );
+    let main_imports = imports
+        .iter()
+        .find(|i| i.file == "src/testing/c/src/main.c")
+        .unwrap();
+    assert_eq!(
+        main_imports.body, main_import_body,
+        "Main import body is incorrect"
+    );
+    assert!(
+        imports
+            .iter()
+            .any(|i| i.file == "src/testing/c/include/storage.h"),
+        "Includes inside an include guard should be found"
+    );
+
+    let variables = graph.find_nodes_by_type(NodeType::Var);
+    assert_eq!(variables.len(), 4, "Expected 4 variables");
+    assert!(
+        variables
+            .iter()
+            .any(|v| v.name == "MAX_NAME" && v.file == "src/testing/c/include/person.h"),
+        "Expected MAX_NAME macro not found"
+    );
+    assert!(
+        variables.iter().any(|v| v.name == "PERSON_VALID"),
+        "Expected PERSON_VALID function-like macro not found"
+    );
+    assert!(
+        variables
+            .iter()
+            .any(|v| v.name == "memory_storage" && v.file == "src/testing/c/src/memory_storage.c"),
+        "Expected memory_storage global not found"
+    );
+
+    let classes = graph.find_nodes_by_type(NodeType::Class);
+    assert_eq!(classes.len(), 1, "Expected 1 class");
+    assert_eq!(
+        classes[0].name, "storage_ops",
+        "Struct of function pointers should be a class"
+    );
+
+    let functions = graph.find_nodes_by_type(NodeType::Function);
+    assert_eq!(functions.len(), 8, "Expected 8 functions");
+    assert!(
+        functions
+            .iter()
+            .any(|f| f.name == "save_person" && f.file == "src/testing/c/src/main.c"),
+        "Expected save_person function not found"
+    );
+
+    let tests = graph.find_nodes_by_type(NodeType::Test);
+    assert_eq!(tests.len(), 1, "Expected 1 test");
+    assert_eq!(tests[0].name, "test_person_init");
+    assert_eq!(tests[0].file, "src/testing/c/tests/test_person.c");
+
+    let data_models = graph.find_nodes_by_type(NodeType::DataModel);
+    assert_eq!(data_models.len(), 4, "Expected 4 data models");
+    assert!(
+        data_models
+            .iter()
+            .any(|dm| dm.name == "person" && dm.file == "src/testing/c/include/person.h"),
+        "Expected person data model not found"
+    );
+    assert!(
+        data_models.iter().any(|dm| dm.name == "person_list"),
+        "Expected typedef struct person_list not found"
+    );
+    assert!(
+        data_models.iter().any(|dm| dm.name == "person_key"),
+        "Expected union person_key not found"
+    );
+
+    let fields = graph.find_nodes_by_type(NodeType::Field);
+    assert_eq!(fields.len(), 8, "Expected 8 fields");
+    let field_edges =
+        graph.find_nodes_with_edge_type(NodeType::DataModel, NodeType::Field, EdgeType::Contains);
+    assert!(
+        field_edges
+            .iter()
+            .any(|(dm, f)| dm.name == "person_key" && f.name == "name"),
+        "person_key should contain the name array"
+    );
+    let items = field_edges
+        .iter()
+        .find(|(dm, f)| dm.name == "person_list" && f.name == "items")
+        .map(|(_, f)| f)
+        .expect("person_list.items field not found");
+    assert_eq!(items.data_type, Some("struct person".to_string()));
+    assert!(
+        field_edges
+            .iter()
+            .any(|(dm, f)| dm.name == "storage_ops" && f.name == "load"),
+        "Function pointer returning a pointer should be a field"
+    );
+
+    let calls_edges_count = graph.count_edges_of_type(EdgeType::Calls);
+    assert_eq!(calls_edges_count, 5, "Expected 5 calls edges");
+
+    let contains_edges_count = graph.count_edges_of_type(EdgeType::Contains);
+    assert_eq!(contains_edges_count, 52, "Expected 52 contains edges");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_c() {
+    use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
+    test_c_generic::<ArrayGraph>().await.unwrap();
+    test_c_generic::<BTreeMapGraph>().await.unwrap();
+
+    #[cfg(feature = "neo4j")]
+    {
+        let mut graph = Neo4jGraph::default();
+        graph.clear().await.unwrap();
+        test_c_generic::<Neo4jStagingGraph>().await.unwrap();
+    }
+}
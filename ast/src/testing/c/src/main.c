@@ -0,0 +1,25 @@
+#include <stdio.h>
+#include "person.h"
+#include "storage.h"
+
+static void on_saved(const struct person *p)
+{
+    person_print(p);
+}
+
+static void save_person(const struct storage_ops *ops, const struct person *p,
+                        void (*callback)(const struct person *))
+{
+    if (ops->save(p) == 0) {
+        (*callback)(p);
+    }
+}
+
+int main(void)
+{
+    struct person alice;
+    person_init(&alice, 1, "alice");
+    save_person(&memory_storage, &alice, on_saved);
+    person_print(memory_storage.load(1));
+    return 0;
+}
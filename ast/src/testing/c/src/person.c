@@ -0,0 +1,17 @@
+#include <stdio.h>
+#include <string.h>
+#include "person.h"
+
+void person_init(struct person *p, int id, const char *name)
+{
+    p->id = id;
+    strncpy(p->name, name, MAX_NAME - 1);
+    p->name[MAX_NAME - 1] = '\0';
+}
+
+void person_print(const struct person *p)
+{
+    if (PERSON_VALID(p)) {
+        printf("%d: %s\n", p->id, p->name);
+    }
+}
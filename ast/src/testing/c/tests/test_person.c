@@ -0,0 +1,17 @@
+#include <assert.h>
+#include <string.h>
+#include "person.h"
+
+void test_person_init(void)
+{
+    struct person p;
+    person_init(&p, 7, "bob");
+    assert(p.id == 7);
+    assert(strcmp(p.name, "bob") == 0);
+}
+
+int main(void)
+{
+    test_person_init();
+    return 0;
+}
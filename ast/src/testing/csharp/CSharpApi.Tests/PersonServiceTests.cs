@@ -0,0 +1,34 @@
+using CSharpApi.Data;
+using CSharpApi.Models;
+using CSharpApi.Services;
+using Microsoft.EntityFrameworkCore;
+using NUnit.Framework;
+using Xunit;
+
+namespace CSharpApi.Tests;
+
+public class PersonServiceTests
+{
+    private static PersonService CreateService()
+    {
+        var options = new DbContextOptionsBuilder<AppDbContext>()
+            .UseSqlite("Data Source=:memory:")
+            .Options;
+        return new PersonService(new AppDbContext(options));
+    }
+
+    [Fact]
+    public void AddPerson_ReturnsPerson()
+    {
+        var service = CreateService();
+        var person = service.AddPerson(new PersonDto("Alice", "alice@example.com"));
+        Xunit.Assert.Equal("Alice", person.Name);
+    }
+
+    [Test]
+    public void FindPerson_MissingReturnsNull()
+    {
+        var service = CreateService();
+        NUnit.Framework.Assert.That(service.FindPerson(42), Is.Null);
+    }
+}
@@ -0,0 +1,35 @@
+using CSharpApi.Models;
+using CSharpApi.Services;
+using Microsoft.AspNetCore.Mvc;
+
+namespace CSharpApi.Controllers;
+
+[ApiController]
+[Route("api/person")]
+public class PersonController : ControllerBase
+{
+    private readonly IPersonService _service;
+
+    public PersonController(IPersonService service)
+    {
+        _service = service;
+    }
+
+    [HttpGet("{id}")]
+    public ActionResult<Person> GetPerson(int id)
+    {
+        var person = _service.FindPerson(id);
+        if (person == null)
+        {
+            return NotFound();
+        }
+        return Ok(person);
+    }
+
+    [HttpPost]
+    public ActionResult<Person> CreatePerson(PersonDto dto)
+    {
+        var person = _service.AddPerson(dto);
+        return CreatedAtAction(nameof(GetPerson), new { id = person.Id }, person);
+    }
+}
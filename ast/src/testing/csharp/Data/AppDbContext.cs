@@ -0,0 +1,14 @@
+using CSharpApi.Models;
+using Microsoft.EntityFrameworkCore;
+
+namespace CSharpApi.Data;
+
+public class AppDbContext : DbContext
+{
+    public AppDbContext(DbContextOptions<AppDbContext> options) : base(options)
+    {
+    }
+
+    public DbSet<Person> People => Set<Person>();
+    public DbSet<Address> Addresses => Set<Address>();
+}
@@ -0,0 +1,20 @@
+namespace CSharpApi.Endpoints;
+
+public static class HealthEndpoints
+{
+    public static void MapHealthEndpoints(this WebApplication app)
+    {
+        app.MapGet("/health", GetHealth);
+        app.MapDelete("/cache", ClearCache);
+    }
+
+    public static IResult GetHealth()
+    {
+        return Results.Ok("healthy");
+    }
+
+    public static IResult ClearCache()
+    {
+        return Results.NoContent();
+    }
+}
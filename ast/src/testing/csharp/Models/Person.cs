@@ -0,0 +1,28 @@
+using System.ComponentModel.DataAnnotations;
+
+namespace CSharpApi.Models;
+
+public class Person
+{
+    [Key]
+    public int Id { get; set; }
+
+    [Required]
+    public string Name { get; set; } = string.Empty;
+
+    public string Email { get; set; } = string.Empty;
+}
+
+public class Address
+{
+    public int AddressId { get; set; }
+    public string Street { get; set; } = string.Empty;
+}
+
+public class PhoneNumber
+{
+    public int CountryId { get; set; }
+    public string Number { get; set; } = string.Empty;
+}
+
+public record PersonDto(string Name, string Email);
@@ -0,0 +1,17 @@
+using CSharpApi.Data;
+using CSharpApi.Endpoints;
+using CSharpApi.Services;
+using Microsoft.EntityFrameworkCore;
+
+var builder = WebApplication.CreateBuilder(args);
+
+builder.Services.AddDbContext<AppDbContext>(options => options.UseSqlite("Data Source=people.db"));
+builder.Services.AddScoped<IPersonService, PersonService>();
+builder.Services.AddControllers();
+
+var app = builder.Build();
+
+app.MapControllers();
+app.MapHealthEndpoints();
+
+app.Run();
@@ -0,0 +1,9 @@
+using CSharpApi.Models;
+
+namespace CSharpApi.Services;
+
+public interface IPersonService
+{
+    Person? FindPerson(int id);
+    Person AddPerson(PersonDto dto);
+}
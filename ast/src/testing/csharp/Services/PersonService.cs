@@ -0,0 +1,27 @@
+using CSharpApi.Data;
+using CSharpApi.Models;
+
+namespace CSharpApi.Services;
+
+public class PersonService : IPersonService
+{
+    private readonly AppDbContext _db;
+
+    public PersonService(AppDbContext db)
+    {
+        _db = db;
+    }
+
+    public Person? FindPerson(int id)
+    {
+        return _db.People.Find(id);
+    }
+
+    public Person AddPerson(PersonDto dto)
+    {
+        var person = new Person { Name = dto.Name, Email = dto.Email };
+        _db.People.Add(person);
+        _db.SaveChanges();
+        return person;
+    }
+}
@@ -0,0 +1,176 @@
+use crate::lang::graphs::{EdgeType, NodeType};
+use crate::lang::Graph;
+use crate::{lang::Lang, repo::Repo};
+use std::str::FromStr;
+
+pub async fn test_csharp_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let repo = Repo::new(
+        "src/testing/csharp",
+        Lang::from_str("csharp").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+
+    let graph = repo.build_graph_inner::<G>().await?;
+
+    let (num_nodes, num_edges) = graph.get_graph_size();
+    assert_eq!(num_nodes, 68, "Expected 68 nodes");
+    assert_eq!(num_edges, 86, "Expected 86 edges");
+
+    let language_nodes = graph.find_nodes_by_type(NodeType::Language);
+    assert_eq!(language_nodes.len(), 1, "Expected 1 language node");
+    assert_eq!(
+        language_nodes[0].name, "csharp",
+        "Language node name should be 'csharp'"
+    );
+
+    let libraries = graph.find_nodes_by_type(NodeType::Library);
+    assert_eq!(libraries.len(), 6, "Expected 6 libraries");
+    let ef_core = libraries
+        .iter()
+        .find(|l| l.name == "Microsoft.EntityFrameworkCore")
+        .expect("EF Core library not found");
+    assert_eq!(ef_core.file, "src/testing/csharp/CSharpApi.csproj");
+    assert_eq!(ef_core.meta.get("version"), Some(&"8.0.4".to_string()));
+
+    let imports = graph.find_nodes_by_type(NodeType::Import);
+    assert_eq!(imports.len(), 7, "Expected 7 imports");
+
+    let controller_import_body = format!(
+        r#"using CSharpApi.Models;
+using CSharpApi.Services;
+using Microsoft.AspNetCore.Mvc;"#
+    );
+    let controller_imports = imports
+        .iter()
+        .find(|i| i.file == "src/testing/csharp/Controllers/PersonController.cs")
+        .unwrap();
+    assert_eq!(
+        controller_imports.body, controller_import_body,
+        "Controller import body is incorrect"
+    );
+
+    let classes = graph.find_nodes_by_type(NodeType::Class);
+    assert_eq!(classes.len(), 8, "Expected 8 classes");
+    let controller = classes
+        .iter()
+        .find(|c| c.name == "PersonController")
+        .expect("PersonController class not found");
+    assert_eq!(
+        controller.meta.get("parent"),
+        Some(&"ControllerBase".to_string())
+    );
+
+    let traits = graph.find_nodes_by_type(NodeType::Trait);
+    assert_eq!(traits.len(), 1, "Expected 1 interface");
+    assert_eq!(traits[0].name, "IPersonService");
+
+    let functions = graph.find_nodes_by_type(NodeType::Function);
+    assert_eq!(functions.len(), 8, "Expected 8 functions");
+    assert!(
+        !functions
+            .iter()
+            .any(|f| f.file == "src/testing/csharp/Services/IPersonService.cs"),
+        "Interface methods should not be functions"
+    );
+
+    let tests = graph.find_nodes_by_type(NodeType::Test);
+    assert_eq!(tests.len(), 2, "Expected 2 tests");
+    assert!(
+        tests.iter().any(|t| t.name == "AddPerson_ReturnsPerson"),
+        "Expected xUnit test not found"
+    );
+    assert!(
+        tests
+            .iter()
+            .any(|t| t.name == "FindPerson_MissingReturnsNull"),
+        "Expected NUnit test not found"
+    );
+    assert!(
+        functions.iter().any(|f| f.name == "CreateService"),
+        "Helpers in test classes are functions"
+    );
+
+    let data_models = graph.find_nodes_by_type(NodeType::DataModel);
+    assert_eq!(data_models.len(), 2, "Expected 2 data models");
+    assert!(
+        data_models
+            .iter()
+            .any(|dm| dm.name == "Person" && dm.file == "src/testing/csharp/Models/Person.cs"),
+        "Expected Person data model not found"
+    );
+    assert!(
+        data_models.iter().any(|dm| dm.name == "Address"),
+        "Expected DbSet<Address> to make Address a data model"
+    );
+    assert!(
+        !data_models
+            .iter()
+            .any(|dm| dm.name == "PersonDto" || dm.name == "PhoneNumber"),
+        "Value objects outside of a DbSet are not entities"
+    );
+
+    let fields = graph.find_nodes_by_type(NodeType::Field);
+    assert_eq!(fields.len(), 11, "Expected 11 fields");
+    let name = fields
+        .iter()
+        .find(|f| f.meta.get("qualified_name") == Some(&"CSharpApi.Models.Person.Name".to_string()))
+        .expect("Person.Name field not found");
+    assert_eq!(name.data_type, Some("string".to_string()));
+    assert_eq!(
+        name.meta.get("annotations"),
+        Some(&"[Required]".to_string())
+    );
+    assert_eq!(
+        name.meta.get("default_value"),
+        Some(&"string.Empty".to_string())
+    );
+
+    let endpoints = graph.find_nodes_by_type(NodeType::Endpoint);
+    assert_eq!(endpoints.len(), 4, "Expected 4 endpoints");
+
+    let get_person = endpoints
+        .iter()
+        .find(|e| e.name == "/api/person/{id}" && e.meta.get("verb") == Some(&"GET".to_string()))
+        .expect("GET /api/person/{id} endpoint not found");
+    assert_eq!(
+        get_person.file,
+        "src/testing/csharp/Controllers/PersonController.cs"
+    );
+
+    let create_person = endpoints
+        .iter()
+        .find(|e| e.name == "/api/person" && e.meta.get("verb") == Some(&"POST".to_string()))
+        .expect("POST /api/person endpoint not found");
+    assert_eq!(
+        create_person.meta.get("handler"),
+        Some(&"CreatePerson".to_string())
+    );
+
+    let health = endpoints
+        .iter()
+        .find(|e| e.name == "/health" && e.meta.get("verb") == Some(&"GET".to_string()))
+        .expect("GET /health endpoint not found");
+    assert_eq!(
+        health.file,
+        "src/testing/csharp/Endpoints/HealthEndpoints.cs"
+    );
+
+    let handler_edges_count = graph.count_edges_of_type(EdgeType::Handler);
+    assert_eq!(handler_edges_count, 4, "Expected 4 handler edges");
+
+    let calls_edges_count = graph.count_edges_of_type(EdgeType::Calls);
+    assert_eq!(calls_edges_count, 6, "Expected 6 calls edges");
+
+    let operand_edges_count = graph.count_edges_of_type(EdgeType::Operand);
+    assert_eq!(operand_edges_count, 8, "Expected 8 operand edges");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_csharp() {
+    test_all_graphs!(test_csharp_generic);
+}
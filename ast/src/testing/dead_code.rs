@@ -0,0 +1,79 @@
+use crate::lang::dead_code::{find_dead_code, DeadCodeConfig};
+use crate::lang::graphs::NodeType;
+use crate::lang::Graph;
+use crate::testing::utils::rust_fixture_graph;
+
+pub async fn test_dead_code_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let graph = rust_fixture_graph::<G>().await?;
+
+    let report = find_dead_code(&graph, &DeadCodeConfig::default());
+    assert_eq!(report.repositories.len(), 1, "Expected 1 repository");
+    let rust = &report.repositories[0];
+    assert_eq!(rust.repository, "/Rust");
+    // 6 endpoints, 3 tests and main
+    assert_eq!(rust.entry_points, 10, "Expected 10 entry points");
+    assert_eq!(rust.reachable, 19, "Expected 19 reachable nodes");
+
+    let mut dead = report
+        .unreachable()
+        .map(|n| n.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    dead.sort();
+    // the servers are spawned by main, which isn't a call the graph sees
+    assert_eq!(
+        dead,
+        vec![
+            "config",
+            "create_rocket",
+            "create_router",
+            "start_actix_server",
+            "start_axum_server",
+            "start_rocket_server"
+        ]
+    );
+    assert!(report
+        .unreachable()
+        .all(|n| n.node_type == NodeType::Function));
+
+    let table = report.to_table();
+    assert!(table.starts_with("/Rust: 10 entry points, 19 reachable, 6 unreachable\n"));
+    assert!(table.contains("src/testing/rust/src/main.rs:49"));
+
+    // naming a server as an entry point brings its router back
+    let mut config = DeadCodeConfig::default();
+    config.functions.push("start_axum_server".to_string());
+    let report = find_dead_code(&graph, &config);
+    let dead = report
+        .unreachable()
+        .map(|n| n.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(dead.len(), 4, "Expected 4 unreachable functions");
+    assert!(!dead.contains(&"create_router"));
+
+    // pub functions are kept for a library
+    let config = DeadCodeConfig {
+        exported: true,
+        ..Default::default()
+    };
+    let report = find_dead_code(&graph, &config);
+    let mut dead = report
+        .unreachable()
+        .map(|n| n.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    dead.sort();
+    assert_eq!(
+        dead,
+        vec![
+            "start_actix_server",
+            "start_axum_server",
+            "start_rocket_server"
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_dead_code() {
+    test_all_graphs!(test_dead_code_generic);
+}
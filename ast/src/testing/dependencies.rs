@@ -0,0 +1,95 @@
+use crate::lang::dependencies::{analyze_dependencies, LayerConfig, LayerRule};
+use crate::lang::Graph;
+use crate::{lang::Lang, repo::Repo};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub async fn test_dependencies_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let repo = Repo::new(
+        "src/testing/typescript",
+        Lang::from_str("ts").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+    let graph = repo.build_graph_inner::<G>().await?;
+
+    let config = LayerConfig {
+        layers: BTreeMap::from([
+            ("http".to_string(), vec!["src/routes.ts".to_string()]),
+            ("service".to_string(), vec!["src/service.ts".to_string()]),
+            (
+                "db".to_string(),
+                vec!["src/config.ts".to_string(), "src/model.ts".to_string()],
+            ),
+        ]),
+        rules: vec![
+            LayerRule {
+                layer: "http".to_string(),
+                must_not_import: vec!["db".to_string()],
+            },
+            LayerRule {
+                layer: "service".to_string(),
+                must_not_import: vec!["db".to_string()],
+            },
+        ],
+    };
+    let report = analyze_dependencies(&graph, &config);
+
+    // config.ts and model.ts import each other
+    assert_eq!(
+        report.files.cycles,
+        vec![vec![
+            "src/testing/typescript/src/config.ts".to_string(),
+            "src/testing/typescript/src/model.ts".to_string()
+        ]]
+    );
+    assert_eq!(report.files.modules.len(), 5, "Expected 5 file modules");
+    let config_ts = report
+        .files
+        .modules
+        .iter()
+        .find(|m| m.module.ends_with("src/config.ts"))
+        .expect("config.ts not found");
+    assert_eq!((config_ts.fan_in, config_ts.fan_out), (3, 1));
+    let service_ts = report
+        .files
+        .modules
+        .iter()
+        .find(|m| m.module.ends_with("src/service.ts"))
+        .expect("service.ts not found");
+    assert_eq!((service_ts.fan_in, service_ts.fan_out), (1, 2));
+
+    // everything lives in src, so no directory edges
+    assert!(report.directories.modules.is_empty());
+    assert!(report.directories.cycles.is_empty());
+
+    // routes.ts only reaches the db through service.ts
+    assert!(report.violations.iter().all(|v| v.layer == "service"));
+    let violations = report
+        .violations
+        .iter()
+        .map(|v| {
+            (
+                v.import.target.node_data.name.as_str(),
+                v.import.code.as_deref().unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(violations.len(), 4, "Expected 4 layer violations");
+    assert!(violations.contains(&(
+        "prisma",
+        "import { AppDataSource, prisma } from \"./config.js\";"
+    )));
+    assert!(report.violations.iter().all(|v| v.import.line.is_some()));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_dependencies() {
+    use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    test_dependencies_generic::<ArrayGraph>().await.unwrap();
+    test_dependencies_generic::<BTreeMapGraph>().await.unwrap();
+}
@@ -0,0 +1,189 @@
+use crate::export::{
+    export_graph, graph_contents, keyed_edges, to_cypher, to_dot, to_graphml, write_csv,
+    ExportFormat,
+};
+use crate::lang::graphs::{EdgeType, Node, NodeType};
+use crate::lang::Graph;
+use crate::testing::utils::rust_fixture_graph;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub async fn test_export_generic<G: Graph + Serialize + 'static>() -> Result<(), anyhow::Error> {
+    let graph = rust_fixture_graph::<G>().await?;
+    let (num_nodes, _) = graph.get_graph_size();
+
+    let (nodes, edges) = graph_contents(&graph);
+    assert_eq!(nodes.len(), num_nodes as usize);
+    let keyed = keyed_edges(&nodes, &edges);
+    let calls = keyed
+        .iter()
+        .filter(|(_, _, e)| e.edge == EdgeType::Calls)
+        .count();
+    assert!(calls > 0, "calls should be exported");
+    let handlers = keyed
+        .iter()
+        .filter(|(_, _, e)| e.edge == EdgeType::Handler)
+        .count();
+    assert_eq!(handlers, graph.count_edges_of_type(EdgeType::Handler));
+
+    let graphml = to_graphml(&nodes, &edges);
+    assert!(graphml.starts_with("<?xml"));
+    assert_eq!(graphml.matches("<node id=").count(), nodes.len());
+    assert_eq!(graphml.matches("<edge id=").count(), keyed.len());
+    // a node that shows up twice is written once
+    let mut doubled = nodes.clone();
+    doubled.push(nodes[0].clone());
+    assert_eq!(
+        to_graphml(&doubled, &[]).matches("<node id=").count(),
+        nodes.len()
+    );
+    assert!(graphml.contains("<data key=\"node_type\">Endpoint</data>"));
+    // bodies are escaped
+    assert!(!graphml.contains("impl Responder>"));
+    assert!(graphml.contains("Result&lt;()&gt;"));
+
+    let dot = to_dot(&nodes, &edges, false);
+    assert!(dot.starts_with("digraph G {"));
+    assert!(dot.contains("[label=\"get_person_by_id\", shape=ellipse];"));
+    assert!(dot.contains("[label=\"Database\", shape=cylinder];"));
+    assert!(dot.contains("[label=\"HANDLER\"];"));
+    assert!(!dot.contains("subgraph"));
+    let clustered = to_dot(&nodes, &edges, true);
+    assert!(clustered.contains("label=\"src/testing/rust/src/routes\";"));
+    assert_eq!(
+        clustered.matches(" -> ").count(),
+        dot.matches(" -> ").count()
+    );
+
+    let cypher = to_cypher(&nodes, &edges);
+    let statements = cypher.lines().collect::<Vec<_>>();
+    assert_eq!(statements.len(), nodes.len() + keyed.len());
+    assert!(statements.iter().all(|s| s.ends_with(';')));
+    assert!(statements
+        .iter()
+        .any(|s| s.starts_with("MERGE (n:Function {key: 'function-getpersonbyid-")));
+    // call sites ride along on the CALLS relationships
+    assert!(statements
+        .iter()
+        .any(|s| s.contains("MERGE (s)-[r:CALLS]->(t) ON CREATE SET r.call_start = ")));
+
+    let dir = std::env::temp_dir().join("stakgraph-export-test");
+    let path = dir.join("rust.graphml");
+    export_graph(&graph, &ExportFormat::GraphMl, &path)?;
+    assert_eq!(std::fs::read_to_string(&path)?, graphml);
+    export_graph(&graph, &ExportFormat::Jsonl, &dir.join("rust"))?;
+    let node_lines = std::fs::read_to_string(dir.join("rust-nodes.jsonl"))?;
+    assert_eq!(node_lines.lines().count(), nodes.len());
+
+    let csv = write_csv(&nodes, &edges, &dir.join("csv"))?;
+    assert_eq!(csv.nodes.len(), nodes_by_type(&nodes).len());
+    let functions = std::fs::read_to_string(dir.join("csv/nodes_Function.csv"))?;
+    let rows = csv_records(&functions);
+    assert!(rows[0].join(",").starts_with("key:ID,name,file,start,end,"));
+    assert_eq!(rows[0].last().unwrap(), ":LABEL");
+    assert_eq!(rows.len() - 1, nodes_by_type(&nodes)[&NodeType::Function]);
+    assert!(rows[1..].iter().all(|r| r.len() == rows[0].len()));
+    // multiline bodies with quotes survive the quoting
+    let body = rows[0].iter().position(|c| c == "body").unwrap();
+    assert!(rows[1..].iter().any(|r| r[body].contains('\n')));
+    let calls_csv = std::fs::read_to_string(dir.join("csv/relationships_CALLS.csv"))?;
+    let calls_rows = csv_records(&calls_csv);
+    assert_eq!(
+        calls_rows[0].join(","),
+        ":START_ID,:END_ID,:TYPE,call_start,call_end,call_start_col,call_end_col,call_start_byte,call_end_byte"
+    );
+    assert!(calls_rows[1..]
+        .iter()
+        .any(|r| r[0].starts_with("function-") && !r[3].is_empty()));
+    let command = csv.import_command("neo4j");
+    assert!(command.starts_with("neo4j-admin database import full --multiline-fields=true"));
+    assert!(command.contains("nodes_Function.csv"));
+    assert!(command.ends_with(" neo4j"));
+    std::fs::remove_dir_all(&dir).ok();
+
+    let endpoints = nodes
+        .iter()
+        .filter(|n| n.node_type == NodeType::Endpoint)
+        .count();
+    assert_eq!(endpoints, 6);
+
+    Ok(())
+}
+
+fn nodes_by_type(nodes: &[Node]) -> BTreeMap<NodeType, usize> {
+    let mut counts = BTreeMap::new();
+    for node in nodes {
+        *counts.entry(node.node_type.clone()).or_default() += 1;
+    }
+    counts
+}
+
+// just enough CSV to read the export back: quoted fields with "" escapes
+fn csv_records(s: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => record.push(std::mem::take(&mut field)),
+            '\n' if !in_quotes => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            c => field.push(c),
+        }
+    }
+    records
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_export() {
+    use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    test_export_generic::<ArrayGraph>().await.unwrap();
+    test_export_generic::<BTreeMapGraph>().await.unwrap();
+}
+
+#[test]
+fn test_export_formats() {
+    assert_eq!(
+        ExportFormat::from_str("GraphML").unwrap(),
+        ExportFormat::GraphMl
+    );
+    assert_eq!(
+        ExportFormat::from_str("dot").unwrap(),
+        ExportFormat::Dot {
+            cluster_by_directory: false
+        }
+    );
+    assert!(ExportFormat::from_str("xml").is_err());
+    assert_eq!(
+        ExportFormat::Jsonl
+            .default_path("tribes")
+            .display()
+            .to_string(),
+        "ast/examples/tribes"
+    );
+    assert_eq!(
+        ExportFormat::Cypher
+            .default_path("tribes")
+            .display()
+            .to_string(),
+        "ast/examples/tribes.cypher"
+    );
+    assert_eq!(ExportFormat::from_str("CSV").unwrap(), ExportFormat::Csv);
+    assert_eq!(
+        ExportFormat::Csv
+            .default_path("tribes")
+            .display()
+            .to_string(),
+        "ast/examples/tribes-csv"
+    );
+}
@@ -0,0 +1,115 @@
+use crate::lang::features::{detect_features, feature_name, FeatureConfig};
+use crate::lang::graphs::{EdgeType, NodeType};
+use crate::lang::Graph;
+use crate::testing::utils::rust_fixture_graph;
+use crate::{lang::Lang, repo::Repo};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub async fn test_features_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let mut graph = rust_fixture_graph::<G>().await?;
+    let (num_nodes, num_edges) = graph.get_graph_size();
+
+    let count = detect_features(&mut graph, "src/testing/rust", &FeatureConfig::default())?;
+    assert_eq!(count, 1, "Expected 1 feature");
+
+    let features = graph.find_nodes_by_type(NodeType::Feature);
+    let mut names = features.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["person"]);
+
+    let includes =
+        graph.find_nodes_with_edge_type(NodeType::Feature, NodeType::Endpoint, EdgeType::Includes);
+    // the axum, actix and rocket routes all serve the same resource
+    let person_endpoints = includes
+        .iter()
+        .filter(|(f, _)| f.name == "person")
+        .map(|(_, e)| e.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(person_endpoints.len(), 6, "Expected 6 person endpoints");
+    assert!(person_endpoints.contains(&"/person/:id"));
+    assert!(person_endpoints.contains(&"/person/<id>"));
+
+    let handlers =
+        graph.find_nodes_with_edge_type(NodeType::Feature, NodeType::Function, EdgeType::Includes);
+    assert!(
+        handlers
+            .iter()
+            .any(|(f, h)| f.name == "person" && h.file.ends_with("rocket_routes.rs")),
+        "person feature should include the rocket handlers"
+    );
+    // handlers call into the database layer
+    assert!(
+        handlers
+            .iter()
+            .any(|(f, h)| f.name == "person" && h.file.ends_with("db.rs")),
+        "person feature should reach db.rs"
+    );
+
+    let (nodes_after, edges_after) = graph.get_graph_size();
+    assert_eq!(nodes_after, num_nodes + 1);
+    assert_eq!(
+        (edges_after - num_edges) as usize,
+        graph.count_edges_of_type(EdgeType::Includes)
+    );
+
+    // running it twice adds nothing new
+    detect_features(&mut graph, "src/testing/rust", &FeatureConfig::default())?;
+    assert_eq!(graph.get_graph_size(), (nodes_after, edges_after));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_features() {
+    test_all_graphs!(test_features_generic);
+}
+
+// detection runs from build_graph_inner when .ast.json has a "features" section
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_features_config() {
+    use crate::lang::graphs::BTreeMapGraph;
+    let repo = Repo::new(
+        "src/testing/rust_features",
+        Lang::from_str("rust").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+    let graph = repo.build_graph_inner::<BTreeMapGraph>().await.unwrap();
+
+    let features = graph.find_nodes_by_type(NodeType::Feature);
+    let mut names = features.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["order", "people"]);
+
+    let includes =
+        graph.find_nodes_with_edge_type(NodeType::Feature, NodeType::Function, EdgeType::Includes);
+    assert!(includes
+        .iter()
+        .any(|(f, func)| f.name == "people" && func.name == "load_person"));
+    assert!(!includes
+        .iter()
+        .any(|(f, func)| f.name == "people" && func.name == "load_order"));
+}
+
+#[test]
+fn test_feature_names() {
+    let mut config = FeatureConfig::default();
+    assert_eq!(
+        feature_name("/api/v2/person/:id", &config),
+        Some("person".to_string())
+    );
+    assert_eq!(
+        feature_name("/{tenant}/Invoices", &config),
+        Some("invoices".to_string())
+    );
+    assert_eq!(feature_name("/api/:id", &config), None);
+
+    config.groups = BTreeMap::from([("people".to_string(), vec!["/rocket".to_string()])]);
+    assert_eq!(
+        feature_name("/rocket/person/<id>", &config),
+        Some("people".to_string())
+    );
+}
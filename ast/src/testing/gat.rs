@@ -0,0 +1,109 @@
+use crate::gat::{
+    get_changed_files, get_changed_lines, get_file_changes, is_head, ChangedLines, FileChange,
+};
+use git2::{Oid, Repository, Signature};
+use std::fs;
+use std::path::Path;
+
+fn commit_all(repo: &Repository, message: &str) -> Oid {
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.update_all(["*"].iter(), None).unwrap();
+    index.write().unwrap();
+    let tree_id = index.write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let sig = Signature::now("test", "test@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents = parent.iter().collect::<Vec<_>>();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap()
+}
+
+#[test]
+fn test_file_changes() {
+    let dir = std::env::temp_dir().join(format!("ast-gat-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let repo = Repository::init(&dir).unwrap();
+
+    let write = |name: &str, body: &str| fs::write(Path::new(&dir).join(name), body).unwrap();
+    write("modified.rs", "fn a() {}\n");
+    write("deleted.rs", "fn b() {}\n");
+    write(
+        "neighbors.rs",
+        "fn e() {\n    1;\n}\n\nfn f() {\n    2;\n}\n\nfn g() {\n    3;\n}\n",
+    );
+    write(
+        "old_name.rs",
+        "fn c() {\n    println!(\"renamed without changes\");\n}\n",
+    );
+    let before = commit_all(&repo, "before");
+
+    write("modified.rs", "fn a() {\n    b();\n}\n");
+    fs::remove_file(dir.join("deleted.rs")).unwrap();
+    fs::rename(dir.join("old_name.rs"), dir.join("new_name.rs")).unwrap();
+    write("added.rs", "fn d() {}\n");
+    write(
+        "neighbors.rs",
+        "fn e() {\n    1;\n}\n\nfn f() {\n    22;\n}\n\nfn g() {\n    3;\n}\n",
+    );
+    let after = commit_all(&repo, "after");
+
+    let root = dir.to_str().unwrap();
+    let mut changes = get_file_changes(root, &before.to_string(), &after.to_string()).unwrap();
+    changes.sort_by_key(|c| format!("{:?}", c));
+    assert_eq!(
+        changes,
+        vec![
+            FileChange::Added("added.rs".to_string()),
+            FileChange::Deleted("deleted.rs".to_string()),
+            FileChange::Modified("modified.rs".to_string()),
+            FileChange::Modified("neighbors.rs".to_string()),
+            FileChange::Renamed {
+                from: "old_name.rs".to_string(),
+                to: "new_name.rs".to_string(),
+            },
+        ]
+    );
+
+    let mut changed = get_changed_files(root, &before.to_string(), &after.to_string()).unwrap();
+    changed.sort();
+    assert_eq!(
+        changed,
+        vec!["added.rs", "modified.rs", "neighbors.rs", "new_name.rs"]
+    );
+
+    // new_name.rs is a pure rename with no changed lines, deleted.rs is gone
+    let mut lines = get_changed_lines(root, &before.to_string(), &after.to_string()).unwrap();
+    lines.sort_by(|a, b| a.file.cmp(&b.file));
+    assert_eq!(
+        lines,
+        vec![
+            ChangedLines {
+                file: "added.rs".to_string(),
+                ranges: vec![(0, 0)],
+            },
+            ChangedLines {
+                file: "modified.rs".to_string(),
+                ranges: vec![(0, 2)],
+            },
+            ChangedLines {
+                file: "neighbors.rs".to_string(),
+                ranges: vec![(5, 5)],
+            },
+        ]
+    );
+    // only f changed, the functions either side of it are untouched
+    let neighbors = &lines[2];
+    assert!(!neighbors.overlaps(0, 2));
+    assert!(neighbors.overlaps(4, 6));
+    assert!(!neighbors.overlaps(8, 10));
+
+    assert!(is_head(root, &after.to_string()).unwrap());
+    assert!(is_head(root, "HEAD").unwrap());
+    assert!(!is_head(root, &before.to_string()).unwrap());
+
+    let _ = fs::remove_dir_all(&dir);
+}
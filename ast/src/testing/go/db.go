@@ -20,6 +20,13 @@ type Person struct {
 	Email string `json:"email"`
 }
 
+type Status int
+
+const (
+	Active Status = iota
+	Inactive
+)
+
 func (p *Person) TableName() string {
 	return "people"
 }
@@ -0,0 +1,34 @@
+package echo_app
+
+import (
+	"net/http"
+
+	"github.com/labstack/echo/v4"
+)
+
+func NewEchoServer() *echo.Echo {
+	e := echo.New()
+	e.GET("/status", echoStatus)
+	admin := e.Group("/admin")
+	registerAdminRoutes(admin)
+	v1 := e.Group("/v1")
+	v1.GET("/status", echoStatus)
+	return e
+}
+
+func registerAdminRoutes(g *echo.Group) {
+	g.PUT("/settings", echoUpdateSettings)
+	g.PATCH("/settings/:key", echoPatchSetting)
+}
+
+func echoStatus(c echo.Context) error {
+	return c.String(http.StatusOK, "ok")
+}
+
+func echoUpdateSettings(c echo.Context) error {
+	return c.NoContent(http.StatusNoContent)
+}
+
+func echoPatchSetting(c echo.Context) error {
+	return c.NoContent(http.StatusNoContent)
+}
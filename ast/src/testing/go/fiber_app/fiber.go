@@ -0,0 +1,29 @@
+package fiber_app
+
+import (
+	"github.com/gofiber/fiber/v2"
+)
+
+func NewFiberApp() *fiber.App {
+	app := fiber.New()
+	app.Get("/metrics", fiberMetrics)
+	registerOrderRoutes(app.Group("/shop"))
+	return app
+}
+
+func registerOrderRoutes(router fiber.Router) {
+	router.Get("/orders", fiberListOrders)
+	router.Post("/orders", fiberCreateOrder)
+}
+
+func fiberMetrics(c *fiber.Ctx) error {
+	return c.SendString("up")
+}
+
+func fiberListOrders(c *fiber.Ctx) error {
+	return c.JSON([]string{})
+}
+
+func fiberCreateOrder(c *fiber.Ctx) error {
+	return c.SendStatus(fiber.StatusCreated)
+}
@@ -0,0 +1,42 @@
+package gin_app
+
+import (
+	"net/http"
+
+	"github.com/gin-gonic/gin"
+)
+
+func NewGinRouter() *gin.Engine {
+	r := gin.Default()
+	r.GET("/ping", ginPing)
+	registerItemRoutes(r.Group("/api/v1"))
+	v2 := r.Group("/api/v2")
+	{
+		v2.GET("/items", ginListItems)
+		admin := v2.Group("/admin")
+		admin.DELETE("/items/:id", ginDeleteItem)
+	}
+	return r
+}
+
+func registerItemRoutes(rg *gin.RouterGroup) {
+	rg.GET("/items", ginListItems)
+	rg.POST("/items", ginCreateItem)
+	rg.DELETE("/items/:id", ginDeleteItem)
+}
+
+func ginPing(c *gin.Context) {
+	c.String(http.StatusOK, "pong")
+}
+
+func ginListItems(c *gin.Context) {
+	c.JSON(http.StatusOK, []string{})
+}
+
+func ginCreateItem(c *gin.Context) {
+	c.Status(http.StatusCreated)
+}
+
+func ginDeleteItem(c *gin.Context) {
+	c.Status(http.StatusNoContent)
+}
@@ -1,6 +1,6 @@
 module gogo
 
-go 1.2
+go 1.22
 
 require (
 	gorm.io/driver/postgres v1.5.11
@@ -9,5 +9,9 @@ require (
 
 require (
 	github.com/go-chi/chi v1.5.5
+	github.com/gin-gonic/gin v1.10.0
+	github.com/gofiber/fiber/v2 v2.52.5
+	github.com/gorilla/mux v1.8.1
+	github.com/labstack/echo/v4 v4.12.0
 	github.com/rs/cors v1.11.1
 )
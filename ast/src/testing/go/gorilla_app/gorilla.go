@@ -0,0 +1,31 @@
+package gorilla_app
+
+import (
+	"net/http"
+
+	"github.com/gorilla/mux"
+)
+
+func NewMuxRouter() *mux.Router {
+	r := mux.NewRouter()
+	r.HandleFunc("/books", muxListBooks).Methods("GET")
+	r.HandleFunc("/books", muxCreateBook).Methods(http.MethodPost)
+	registerAuthorRoutes(r.PathPrefix("/authors").Subrouter())
+	return r
+}
+
+func registerAuthorRoutes(s *mux.Router) {
+	s.HandleFunc("/{id}", muxGetAuthor).Methods("GET")
+}
+
+func muxListBooks(w http.ResponseWriter, r *http.Request) {
+	w.WriteHeader(http.StatusOK)
+}
+
+func muxCreateBook(w http.ResponseWriter, r *http.Request) {
+	w.WriteHeader(http.StatusCreated)
+}
+
+func muxGetAuthor(w http.ResponseWriter, r *http.Request) {
+	w.Write([]byte(mux.Vars(r)["id"]))
+}
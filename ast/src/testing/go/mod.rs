@@ -1,10 +1,24 @@
-use crate::lang::graphs::{EdgeType, NodeType};
+use crate::lang::graphs::{ArrayGraph, BTreeMapGraph, Edge, EdgeType, NodeType};
 use crate::lang::Graph;
+use crate::testing::utils::find_endpoint;
 use crate::utils::get_use_lsp;
 use crate::{lang::Lang, repo::Repo};
+use std::any::Any;
 use std::str::FromStr;
 
-pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
+// graphs that keep the call site on their edges
+fn call_edges<G: Graph + 'static>(graph: &G) -> Option<Vec<Edge>> {
+    let graph = graph as &dyn Any;
+    if let Some(array_graph) = graph.downcast_ref::<ArrayGraph>() {
+        Some(array_graph.edges.clone())
+    } else if let Some(btreemap_graph) = graph.downcast_ref::<BTreeMapGraph>() {
+        Some(btreemap_graph.to_array_graph_edges())
+    } else {
+        None
+    }
+}
+
+pub async fn test_go_generic<G: Graph + 'static>() -> Result<(), anyhow::Error> {
     let use_lsp = get_use_lsp();
     let repo = Repo::new(
         "src/testing/go",
@@ -21,11 +35,11 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
 
     let (num_nodes, num_edges) = graph.get_graph_size();
     if use_lsp == true {
-        assert_eq!(num_nodes, 65, "Expected 65 nodes");
-        assert_eq!(num_edges, 98, "Expected 98 edges");
+        assert_eq!(num_nodes, 137, "Expected 137 nodes");
+        assert_eq!(num_edges, 176, "Expected 176 edges");
     } else {
-        assert_eq!(num_nodes, 31, "Expected 31 nodes");
-        assert_eq!(num_edges, 49, "Expected 49 edges");
+        assert_eq!(num_nodes, 103, "Expected 103 nodes");
+        assert_eq!(num_edges, 127, "Expected 127 edges");
     }
 
     let language_nodes = graph.find_nodes_by_name(NodeType::Language, "go");
@@ -40,7 +54,7 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
     );
 
     let imports = graph.find_nodes_by_type(NodeType::Import);
-    assert_eq!(imports.len(), 3, "Expected 3 imports");
+    assert_eq!(imports.len(), 8, "Expected 8 imports");
 
     let main_import_body = format!(
         r#"import (
@@ -78,7 +92,7 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
     assert_eq!(class_function_edges.len(), 4, "Expected 4 methods");
 
     let data_models = graph.find_nodes_by_type(NodeType::DataModel);
-    assert_eq!(data_models.len(), 2, "Expected 2 data models");
+    assert_eq!(data_models.len(), 3, "Expected 3 data models");
     assert!(
         data_models
             .iter()
@@ -86,8 +100,30 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
         "Expected Person data model not found"
     );
 
+    // typed const block with iota
+    let enums = graph.find_nodes_by_type(NodeType::Enum);
+    assert_eq!(enums.len(), 1, "Expected 1 enum");
+    assert_eq!(enums[0].name, "Status");
+    assert_eq!(
+        enums[0].meta.get("variants"),
+        Some(&"Active,Inactive".to_string())
+    );
+
+    let field_edges =
+        graph.find_nodes_with_edge_type(NodeType::DataModel, NodeType::Field, EdgeType::Contains);
+    assert_eq!(field_edges.len(), 4, "Expected 4 struct fields");
+    let (_, email) = field_edges
+        .iter()
+        .find(|(dm, f)| dm.name == "Person" && f.name == "Email")
+        .expect("Person.Email field not found");
+    assert_eq!(email.data_type, Some("string".to_string()));
+    assert_eq!(
+        email.meta.get("annotations"),
+        Some(&"`json:\"email\"`".to_string())
+    );
+
     let endpoints = graph.find_nodes_by_type(NodeType::Endpoint);
-    assert_eq!(endpoints.len(), 2, "Expected 2 endpoints");
+    assert_eq!(endpoints.len(), 22, "Expected 22 endpoints");
 
     let get_endpoint = endpoints
         .iter()
@@ -101,11 +137,55 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
         .expect("POST endpoint not found");
     assert_eq!(post_endpoint.file, "src/testing/go/routes.go");
 
+    // gin: r.GET and a router group passed to a register function
+    find_endpoint(&endpoints, "/ping", "GET", "gin_app/gin.go");
+    let gin_create = find_endpoint(&endpoints, "/api/v1/items", "POST", "gin_app/gin.go");
+    assert_eq!(
+        gin_create.meta.get("handler"),
+        Some(&"ginCreateItem".to_string())
+    );
+    find_endpoint(&endpoints, "/api/v1/items/:id", "DELETE", "gin_app/gin.go");
+    // gin: a group variable used in the same function, and a group of it
+    find_endpoint(&endpoints, "/api/v2/items", "GET", "gin_app/gin.go");
+    find_endpoint(
+        &endpoints,
+        "/api/v2/admin/items/:id",
+        "DELETE",
+        "gin_app/gin.go",
+    );
+
+    // echo: the group is assigned to a variable first
+    find_endpoint(&endpoints, "/status", "GET", "echo_app/echo.go");
+    find_endpoint(&endpoints, "/v1/status", "GET", "echo_app/echo.go");
+    find_endpoint(&endpoints, "/admin/settings", "PUT", "echo_app/echo.go");
+    find_endpoint(
+        &endpoints,
+        "/admin/settings/:key",
+        "PATCH",
+        "echo_app/echo.go",
+    );
+
+    // fiber
+    find_endpoint(&endpoints, "/metrics", "GET", "fiber_app/fiber.go");
+    find_endpoint(&endpoints, "/shop/orders", "GET", "fiber_app/fiber.go");
+    find_endpoint(&endpoints, "/shop/orders", "POST", "fiber_app/fiber.go");
+
+    // gorilla/mux: verbs come from .Methods(...)
+    find_endpoint(&endpoints, "/books", "GET", "gorilla_app/gorilla.go");
+    find_endpoint(&endpoints, "/books", "POST", "gorilla_app/gorilla.go");
+    find_endpoint(&endpoints, "/authors/{id}", "GET", "gorilla_app/gorilla.go");
+
+    // net/http with go 1.22 method patterns
+    find_endpoint(&endpoints, "/tasks/{id}", "GET", "stdlib_app/stdlib.go");
+    find_endpoint(&endpoints, "/tasks", "POST", "stdlib_app/stdlib.go");
+    find_endpoint(&endpoints, "/healthz", "GET", "stdlib_app/stdlib.go");
+    find_endpoint(&endpoints, "/v2/notes", "GET", "stdlib_app/stdlib.go");
+
     let handler_edges_count = graph.count_edges_of_type(EdgeType::Handler);
-    assert_eq!(handler_edges_count, 2, "Expected 2 handler edges");
+    assert_eq!(handler_edges_count, 22, "Expected 22 handler edges");
 
     let function_calls = graph.count_edges_of_type(EdgeType::Calls);
-    assert_eq!(function_calls, 6, "Expected 6 function calls");
+    assert_eq!(function_calls, 12, "Expected 12 function calls");
 
     let operands = graph.count_edges_of_type(EdgeType::Operand);
     assert_eq!(operands, 4, "Expected 4 operands");
@@ -115,10 +195,55 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
 
     if use_lsp {
         let contains = graph.count_edges_of_type(EdgeType::Contains);
-        assert_eq!(contains, 38, "Expected 38 contains edges with lsp");
+        assert_eq!(contains, 90, "Expected 90 contains edges with lsp");
     } else {
         let contains = graph.count_edges_of_type(EdgeType::Contains);
-        assert_eq!(contains, 36, "Expected 36 contains edges");
+        assert_eq!(contains, 88, "Expected 88 contains edges");
+    }
+
+    let new_router = graph
+        .find_nodes_by_name(NodeType::Function, "NewRouter")
+        .pop()
+        .expect("NewRouter not found");
+    assert_eq!(
+        new_router.docs,
+        Some("NewRouter creates a chi router".to_string())
+    );
+    assert_eq!(
+        new_router.meta.get("qualified_name"),
+        Some(&"main.NewRouter".to_string())
+    );
+    // methods are qualified by their receiver type
+    let new_person = graph
+        .find_nodes_by_qualified_name(NodeType::Function, "main.database.NewPerson")
+        .pop()
+        .expect("main.database.NewPerson not found");
+    assert_eq!(new_person.name, "NewPerson");
+
+    let routes_code = std::fs::read_to_string("src/testing/go/routes.go")?;
+    assert_eq!((new_router.start, new_router.start_col), (17, 0));
+    assert_eq!(
+        &routes_code[new_router.start_byte..new_router.end_byte],
+        new_router.body,
+        "Byte range should cover the function body"
+    );
+
+    if let Some(edges) = call_edges(&graph) {
+        let init_chi = edges
+            .iter()
+            .find(|e| {
+                e.edge == EdgeType::Calls
+                    && e.source.node_data.name == "NewRouter"
+                    && e.target.node_data.name == "initChi"
+            })
+            .expect("NewRouter -> initChi call not found");
+        assert_eq!(init_chi.source.node_data.start, new_router.start);
+        let call_site = init_chi.call_site.as_ref().expect("call site not recorded");
+        assert_eq!((call_site.start, call_site.start_col), (18, 6));
+        assert_eq!(
+            &routes_code[call_site.start_byte..call_site.end_byte],
+            "initChi()"
+        );
     }
 
     let variables = graph.find_nodes_by_type(NodeType::Var);
@@ -133,15 +258,15 @@ pub async fn test_go_generic<G: Graph>() -> Result<(), anyhow::Error> {
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_go() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_go_generic::<ArrayGraph>().await.unwrap();
     test_go_generic::<BTreeMapGraph>().await.unwrap();
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_go_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_go_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
@@ -0,0 +1,36 @@
+package stdlib_app
+
+import (
+	"net/http"
+)
+
+func NewTaskServer() *http.ServeMux {
+	mux := http.NewServeMux()
+	mux.HandleFunc("GET /tasks/{id}", getTask)
+	mux.HandleFunc("POST /tasks", createTask)
+	mux.HandleFunc("/healthz", healthz)
+	mux.Handle("/v2/", http.StripPrefix("/v2", notesMux()))
+	return mux
+}
+
+func notesMux() *http.ServeMux {
+	m := http.NewServeMux()
+	m.HandleFunc("GET /notes", listNotes)
+	return m
+}
+
+func getTask(w http.ResponseWriter, r *http.Request) {
+	w.Write([]byte(r.PathValue("id")))
+}
+
+func createTask(w http.ResponseWriter, r *http.Request) {
+	w.WriteHeader(http.StatusCreated)
+}
+
+func healthz(w http.ResponseWriter, r *http.Request) {
+	w.WriteHeader(http.StatusOK)
+}
+
+func listNotes(w http.ResponseWriter, r *http.Request) {
+	w.WriteHeader(http.StatusOK)
+}
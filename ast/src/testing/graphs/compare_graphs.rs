@@ -8,7 +8,7 @@ use std::str::FromStr;
 use test_log::test;
 use tracing::{debug, info};
 
-const PROGRAMMING_LANGUAGES: [&str; 12] = [
+const PROGRAMMING_LANGUAGES: [&str; 15] = [
     "angular",
     "go",
     "kotlin",
@@ -21,6 +21,9 @@ const PROGRAMMING_LANGUAGES: [&str; 12] = [
     "java",
     "rust",
     "cpp",
+    "csharp",
+    "c",
+    "php",
 ];
 
 #[test(tokio::test(flavor = "multi_thread", worker_threads = 2))]
@@ -101,5 +104,63 @@ async fn compare_graphs_inner(lang_id: &str, repo_path: &str) -> Result<()> {
         );
     }
 
+    #[cfg(feature = "sled")]
+    compare_sled_graph(&repo, lang_id, &btree_map_graph).await?;
+
+    Ok(())
+}
+
+#[cfg(feature = "sled")]
+async fn compare_sled_graph(
+    repo: &Repo,
+    lang_id: &str,
+    btree_map_graph: &BTreeMapGraph,
+) -> Result<()> {
+    use crate::lang::graphs::{Edge, NodeType, SledGraph};
+
+    info!("SledGraph Analysis for {}", lang_id);
+    let sled_graph = repo.build_graph_inner::<SledGraph>().await?;
+
+    let (btree_map_graph_nodes, btree_map_graph_edges) = btree_map_graph.get_graph_keys();
+    let (sled_graph_nodes, sled_graph_edges) = sled_graph.get_graph_keys();
+    assert_eq!(
+        sled_graph_nodes, btree_map_graph_nodes,
+        "Node keys do not match between SledGraph and BTreeMapGraph"
+    );
+    assert_eq!(
+        sled_graph_edges, btree_map_graph_edges,
+        "Edge keys do not match between SledGraph and BTreeMapGraph"
+    );
+    assert_eq!(
+        sled_graph.get_graph_size(),
+        btree_map_graph.get_graph_size(),
+        "Graph sizes do not match between SledGraph and BTreeMapGraph"
+    );
+
+    // persist, reopen and query again
+    let path = std::env::temp_dir().join(format!("ast-sled-{}-{}", lang_id, std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    drop(sled_graph.save(&path)?);
+    let reopened = SledGraph::open(&path)?;
+    assert_eq!(reopened.get_graph_size(), sled_graph.get_graph_size());
+    for node_type in [NodeType::Function, NodeType::Class, NodeType::Endpoint] {
+        assert_eq!(
+            reopened.find_nodes_by_type(node_type.clone()),
+            btree_map_graph.find_nodes_by_type(node_type),
+        );
+    }
+    let call_sites = |edges: Vec<Edge>| {
+        edges
+            .into_iter()
+            .filter_map(|edge| edge.call_site)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        call_sites(reopened.all_edges()),
+        call_sites(btree_map_graph.to_array_graph_edges()),
+    );
+    drop(reopened);
+    let _ = std::fs::remove_dir_all(&path);
+
     Ok(())
 }
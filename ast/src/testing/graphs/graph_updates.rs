@@ -1,20 +1,21 @@
-use crate::lang::graphs::graph::Graph;
 #[cfg(feature = "neo4j")]
 use crate::lang::graphs::graph_ops::GraphOps;
 use crate::lang::{graphs::EdgeType, NodeType};
 use lsp::git::{checkout_commit, get_changed_files_between, git_pull_or_clone};
 use tracing::{debug, info};
 
-fn clear_neo4j() {
+async fn clear_neo4j() {
     let mut graph_ops = GraphOps::new();
-    graph_ops.connect().unwrap();
-    graph_ops.clear().unwrap();
+    graph_ops.connect().await.unwrap();
+    graph_ops.clear().await.unwrap();
 }
 
-fn assert_edge_exists(graph: &GraphOps, src: &str, tgt: &str) -> bool {
+async fn assert_edge_exists(graph: &GraphOps, src: &str, tgt: &str) -> bool {
     graph
         .graph
         .find_nodes_with_edge_type(NodeType::Function, NodeType::Function, EdgeType::Calls)
+        .await
+        .unwrap()
         .iter()
         .any(|(s, t)| s.name == src && t.name == tgt)
 }
@@ -31,34 +32,35 @@ async fn test_graph_update() {
     git_pull_or_clone(repo_url, repo_path, None, None)
         .await
         .unwrap();
-    clear_neo4j();
+    clear_neo4j().await;
 
     // --- BEFORE UPDATE ---
     checkout_commit(repo_path, before_commit).await.unwrap();
 
     let mut graph_ops = GraphOps::new();
-    graph_ops.connect().unwrap();
-    graph_ops.clear().unwrap();
+    graph_ops.connect().await.unwrap();
+    graph_ops.clear().await.unwrap();
 
     let (nodes_before, edges_before) = graph_ops
         .update_full(repo_url, repo_path, before_commit)
+        .await
         .unwrap();
 
     info!("Before: {} nodes and {} edges", nodes_before, edges_before);
 
-    graph_ops.graph.analysis();
+    graph_ops.graph.analysis().await.unwrap();
 
     // --- Assert initial state ---
     assert!(
-        assert_edge_exists(&graph_ops, "Alpha", "Beta"),
+        assert_edge_exists(&graph_ops, "Alpha", "Beta").await,
         "Before: Alpha should call Beta"
     );
     assert!(
-        assert_edge_exists(&graph_ops, "Alpha", "Gamma"),
+        assert_edge_exists(&graph_ops, "Alpha", "Gamma").await,
         "Before: Alpha should call Gamma"
     );
     assert!(
-        assert_edge_exists(&graph_ops, "Beta", "Alpha"),
+        assert_edge_exists(&graph_ops, "Beta", "Alpha").await,
         "Before: Beta should call Alpha"
     );
 
@@ -72,31 +74,32 @@ async fn test_graph_update() {
 
     let (nodes_after, edges_after) = graph_ops
         .update_incremental(repo_url, repo_path, after_commit, before_commit)
+        .await
         .unwrap();
 
     info!("After: {} nodes and {} edges", nodes_after, edges_after);
 
-    graph_ops.graph.analysis();
+    graph_ops.graph.analysis().await.unwrap();
     // --- Assert updated state ---
     // Alpha should now call Delta, not Beta or Gamma
     assert!(
-        assert_edge_exists(&graph_ops, "Alpha", "Delta"),
+        assert_edge_exists(&graph_ops, "Alpha", "Delta").await,
         "After: Alpha should call Delta"
     );
     assert!(
-        assert_edge_exists(&graph_ops, "Delta", "Alpha"),
+        assert_edge_exists(&graph_ops, "Delta", "Alpha").await,
         "After: Delta should call Alpha"
     );
     assert!(
-        !assert_edge_exists(&graph_ops, "Alpha", "Beta"),
+        !assert_edge_exists(&graph_ops, "Alpha", "Beta").await,
         "After: Alpha should NOT call Beta"
     );
     assert!(
-        !assert_edge_exists(&graph_ops, "Alpha", "Gamma"),
+        !assert_edge_exists(&graph_ops, "Alpha", "Gamma").await,
         "After: Alpha should NOT call Gamma"
     );
     assert!(
-        !assert_edge_exists(&graph_ops, "Beta", "Alpha"),
+        !assert_edge_exists(&graph_ops, "Beta", "Alpha").await,
         "After: Beta should NOT call Alpha"
     );
 }
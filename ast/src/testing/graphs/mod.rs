@@ -1,3 +1,4 @@
 pub mod compare_graphs;
 #[cfg(feature = "neo4j")]
 pub mod graph_updates;
+pub mod traversal;
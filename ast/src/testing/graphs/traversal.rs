@@ -0,0 +1,289 @@
+use crate::lang::asg::NodeData;
+use crate::lang::graphs::{Direction, Edge, EdgeType, NodeType};
+use crate::lang::{Graph, Node};
+use crate::testing::utils::rust_fixture_graph;
+
+pub async fn test_traversal_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let graph = rust_fixture_graph::<G>().await?;
+
+    let get_person = graph
+        .find_node_by_name_and_file_end_with(NodeType::Function, "get_person", "axum_routes.rs")
+        .expect("axum get_person not found");
+    let get_person_by_id = graph
+        .find_node_by_name_in_file(
+            NodeType::Function,
+            "get_person_by_id",
+            "src/testing/rust/src/db.rs",
+        )
+        .expect("get_person_by_id not found");
+
+    let callers = graph.find_neighbors(
+        NodeType::Function,
+        &get_person_by_id,
+        &[EdgeType::Calls],
+        Direction::Incoming,
+    );
+    assert_eq!(callers.len(), 3, "Expected 3 callers of get_person_by_id");
+    assert!(callers
+        .iter()
+        .all(|c| c.node_type == NodeType::Function && c.node_data.name == "get_person"));
+
+    let get_db = graph
+        .find_node_by_name_in_file(NodeType::Function, "get_db", "src/testing/rust/src/db.rs")
+        .expect("get_db not found");
+    let mut both = graph
+        .find_neighbors(
+            NodeType::Function,
+            &get_db,
+            &[EdgeType::Calls],
+            Direction::Both,
+        )
+        .into_iter()
+        .map(|n| n.node_data.name)
+        .collect::<Vec<_>>();
+    both.sort();
+    assert_eq!(both, vec!["get_person_by_id", "new_person"]);
+
+    let mut called = graph
+        .transitive_calls(&get_person)
+        .into_iter()
+        .map(|f| f.name)
+        .collect::<Vec<_>>();
+    called.sort();
+    assert_eq!(
+        called,
+        vec!["get_db", "get_person_by_id", "get_person_by_id_impl"]
+    );
+
+    let endpoint = graph
+        .find_nodes_by_name(NodeType::Endpoint, "/person/:id")
+        .pop()
+        .expect("/person/:id endpoint not found");
+    let endpoint = Node::new(NodeType::Endpoint, endpoint);
+    let flow = [EdgeType::Handler, EdgeType::Calls, EdgeType::Contains];
+
+    let reached = graph.bfs(&endpoint, &flow[..2], Direction::Outgoing, 2);
+    let reached = reached
+        .iter()
+        .map(|(n, d)| (n.node_data.name.as_str(), *d))
+        .collect::<Vec<_>>();
+    assert_eq!(reached, vec![("get_person", 1), ("get_person_by_id", 2)]);
+
+    let visited = graph.dfs(&endpoint, &flow[..2], Direction::Outgoing, 10);
+    assert_eq!(visited.len(), 4, "Expected 4 nodes below /person/:id");
+    assert_eq!(visited[0].0.node_data.name, "get_person");
+    assert!(visited
+        .iter()
+        .any(|(n, d)| n.node_data.name == "get_person_by_id_impl" && *d == 3));
+
+    // which endpoints eventually reach the Database data model
+    let database = graph
+        .find_nodes_by_name(NodeType::DataModel, "Database")
+        .pop()
+        .expect("Database data model not found");
+    let database = Node::new(NodeType::DataModel, database);
+    let endpoints = graph
+        .bfs(&database, &flow, Direction::Incoming, 10)
+        .into_iter()
+        .filter(|(n, _)| n.node_type == NodeType::Endpoint)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        endpoints.len(),
+        6,
+        "Expected all 6 endpoints to reach Database"
+    );
+
+    let path = graph
+        .shortest_path(&endpoint, &database, &flow, Direction::Outgoing, 10)
+        .expect("no path from /person/:id to Database");
+    let path = path
+        .iter()
+        .map(|n| n.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        path,
+        vec![
+            "/person/:id",
+            "get_person",
+            "get_person_by_id",
+            "get_db",
+            "Database"
+        ]
+    );
+    assert!(graph
+        .shortest_path(&endpoint, &database, &flow, Direction::Outgoing, 3)
+        .is_none());
+    assert!(graph
+        .shortest_path(&endpoint, &database, &flow, Direction::Outgoing, 0)
+        .is_none());
+    assert!(graph
+        .shortest_path(&endpoint, &database, &flow, Direction::Incoming, 10)
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_traversal() {
+    test_all_graphs!(test_traversal_generic);
+}
+
+// two closures on one line are told apart by their column
+fn test_same_line_generic<G: Graph>() {
+    let mut graph = G::new();
+    let file = "src/lib.rs";
+    let outer = NodeData::name_file("outer", file);
+    let mut first = NodeData::name_file("cb", file);
+    first.start = 1;
+    first.start_col = 4;
+    let mut second = first.clone();
+    second.start_col = 20;
+    for nd in [&outer, &first, &second] {
+        graph.add_node(NodeType::Function, nd.clone());
+    }
+    for nd in [&first, &second] {
+        graph.add_edge(Edge::calls(
+            NodeType::Function,
+            &outer,
+            NodeType::Function,
+            nd,
+        ));
+    }
+    let outer = Node::new(NodeType::Function, outer);
+    let reached = graph.bfs(&outer, &[EdgeType::Calls], Direction::Outgoing, 1);
+    let mut cols = reached
+        .iter()
+        .map(|(n, _)| n.node_data.start_col)
+        .collect::<Vec<_>>();
+    cols.sort();
+    assert_eq!(cols, vec![4, 20]);
+    assert_eq!(
+        graph
+            .dfs(&outer, &[EdgeType::Calls], Direction::Outgoing, 1)
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn test_same_line() {
+    use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    test_same_line_generic::<ArrayGraph>();
+    test_same_line_generic::<BTreeMapGraph>();
+}
+
+// the same traversals answered by Cypher, once the staged graph is flushed to the database
+#[cfg(feature = "neo4j")]
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_neo4j_traversal() {
+    use crate::lang::graphs::Neo4jStagingGraph;
+    let mut graph = rust_fixture_graph::<Neo4jStagingGraph>().await.unwrap();
+    graph.db.clear().await.unwrap();
+    graph.flush().await.unwrap();
+    let db = &graph.db;
+
+    let get_person_by_id = db
+        .find_node_by_name_in_file(
+            NodeType::Function,
+            "get_person_by_id",
+            "src/testing/rust/src/db.rs",
+        )
+        .await
+        .expect("get_person_by_id not found");
+    let callers = db
+        .find_neighbors(
+            NodeType::Function,
+            &get_person_by_id,
+            &[EdgeType::Calls],
+            Direction::Incoming,
+        )
+        .await
+        .unwrap();
+    assert_eq!(callers.len(), 3, "Expected 3 callers of get_person_by_id");
+    assert!(callers
+        .iter()
+        .all(|c| c.node_type == NodeType::Function && c.node_data.name == "get_person"));
+
+    let get_person = db
+        .find_nodes_by_name(NodeType::Function, "get_person")
+        .await
+        .into_iter()
+        .find(|f| f.file.ends_with("axum_routes.rs"))
+        .expect("axum get_person not found");
+    let mut called = db
+        .transitive_calls(&get_person)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|f| f.name)
+        .collect::<Vec<_>>();
+    called.sort();
+    assert_eq!(
+        called,
+        vec!["get_db", "get_person_by_id", "get_person_by_id_impl"]
+    );
+
+    let endpoint = db
+        .find_nodes_by_name(NodeType::Endpoint, "/person/:id")
+        .await
+        .pop()
+        .expect("/person/:id endpoint not found");
+    let endpoint = Node::new(NodeType::Endpoint, endpoint);
+    let flow = [EdgeType::Handler, EdgeType::Calls, EdgeType::Contains];
+
+    let reached = db
+        .bfs(&endpoint, &flow[..2], Direction::Outgoing, 2)
+        .await
+        .unwrap();
+    let reached = reached
+        .iter()
+        .map(|(n, d)| (n.node_data.name.as_str(), *d))
+        .collect::<Vec<_>>();
+    assert_eq!(reached, vec![("get_person", 1), ("get_person_by_id", 2)]);
+
+    let visited = db
+        .dfs(&endpoint, &flow[..2], Direction::Outgoing, 10)
+        .await
+        .unwrap();
+    assert_eq!(visited.len(), 4, "Expected 4 nodes below /person/:id");
+    assert_eq!(visited[0].0.node_data.name, "get_person");
+    assert!(visited
+        .iter()
+        .any(|(n, d)| n.node_data.name == "get_person_by_id_impl" && *d == 3));
+    assert!(db
+        .dfs(&endpoint, &flow[..2], Direction::Outgoing, 0)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let database = db
+        .find_nodes_by_name(NodeType::DataModel, "Database")
+        .await
+        .pop()
+        .expect("Database data model not found");
+    let database = Node::new(NodeType::DataModel, database);
+    let path = db
+        .shortest_path(&endpoint, &database, &flow, Direction::Outgoing, 10)
+        .await
+        .unwrap()
+        .expect("no path from /person/:id to Database");
+    let path = path
+        .iter()
+        .map(|n| n.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        path,
+        vec![
+            "/person/:id",
+            "get_person",
+            "get_person_by_id",
+            "get_db",
+            "Database"
+        ]
+    );
+    assert!(db
+        .shortest_path(&endpoint, &database, &flow, Direction::Outgoing, 3)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,106 @@
+use crate::gat::ChangedLines;
+use crate::lang::graphs::NodeType;
+use crate::lang::impact::analyze_impact;
+use crate::lang::Graph;
+use crate::testing::utils::rust_fixture_graph;
+
+pub async fn test_impact_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let graph = rust_fixture_graph::<G>().await?;
+
+    let line_in = |name: &str| {
+        let f = graph
+            .find_node_by_name_in_file(NodeType::Function, name, "src/testing/rust/src/db.rs")
+            .expect("function not found in db.rs");
+        f.start + 1
+    };
+
+    // a change inside get_person_by_id_impl reaches the three GET routes
+    let row = line_in("get_person_by_id_impl");
+    let changes = vec![ChangedLines {
+        file: "src/db.rs".to_string(),
+        ranges: vec![(row, row)],
+    }];
+    let report = analyze_impact(&graph, "src/testing/rust", &changes);
+    assert_eq!(report.changed.len(), 1, "Expected 1 changed node");
+    assert_eq!(report.changed[0].node_data.name, "get_person_by_id_impl");
+    let mut endpoints = report
+        .endpoints
+        .iter()
+        .map(|e| e.node.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    endpoints.sort();
+    assert_eq!(
+        endpoints,
+        vec!["/person/:id", "/person/<id>", "/person/{id}"]
+    );
+    let axum = report
+        .endpoints
+        .iter()
+        .find(|e| e.node.node_data.name == "/person/:id")
+        .unwrap();
+    let path = axum
+        .path
+        .iter()
+        .map(|n| n.node_data.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        path,
+        vec![
+            "get_person_by_id_impl",
+            "get_person_by_id",
+            "get_person",
+            "/person/:id"
+        ]
+    );
+    assert!(report.tests.is_empty(), "no test covers get_person_by_id");
+    assert!(report.pages.is_empty());
+
+    // new_person_impl is also behind the unit test
+    let row = line_in("new_person_impl");
+    let changes = vec![ChangedLines {
+        file: "src/db.rs".to_string(),
+        ranges: vec![(row, row)],
+    }];
+    let report = analyze_impact(&graph, "src/testing/rust", &changes);
+    assert_eq!(report.endpoints.len(), 3, "Expected the 3 POST routes");
+    assert!(report
+        .endpoints
+        .iter()
+        .all(|e| e.node.node_data.verb == Some("POST".to_string())));
+    assert_eq!(report.tests.len(), 1, "Expected 1 impacted test");
+    assert_eq!(report.tests[0].node.node_data.name, "test_new_person");
+    assert_eq!(report.test_files(), vec!["src/testing/rust/src/db.rs"]);
+
+    // lines outside of any function touch nothing
+    let changes = vec![ChangedLines {
+        file: "src/db.rs".to_string(),
+        ranges: vec![(0, 0)],
+    }];
+    let report = analyze_impact(&graph, "src/testing/rust", &changes);
+    assert!(report.changed.is_empty());
+    assert!(report.endpoints.is_empty());
+
+    // the same path under another root is a different file
+    let row = line_in("new_person_impl");
+    let changes = vec![ChangedLines {
+        file: "rust/src/db.rs".to_string(),
+        ranges: vec![(row, row)],
+    }];
+    let report = analyze_impact(&graph, "src/testing", &changes);
+    assert_eq!(report.changed.len(), 1);
+    let report = analyze_impact(&graph, "src/other", &changes);
+    assert!(report.changed.is_empty());
+    let changes = vec![ChangedLines {
+        file: "db.rs".to_string(),
+        ranges: vec![(row, row)],
+    }];
+    let report = analyze_impact(&graph, "src/testing/rust", &changes);
+    assert!(report.changed.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_impact() {
+    test_all_graphs!(test_impact_generic);
+}
@@ -19,8 +19,8 @@ pub async fn test_java_generic<G: Graph>() -> Result<(), anyhow::Error> {
     let (num_nodes, num_edges) = graph.get_graph_size();
 
     //graph.analysis();
-    assert_eq!(num_nodes, 38, "Expected 38 nodes");
-    assert_eq!(num_edges, 45, "Expected 45 edges");
+    assert_eq!(num_nodes, 44, "Expected 44 nodes");
+    assert_eq!(num_edges, 52, "Expected 52 edges");
 
     fn normalize_path(path: &str) -> String {
         path.replace("\\", "/")
@@ -98,30 +98,70 @@ import java.util.Optional;"#
     let data_models = graph.find_nodes_by_type(NodeType::DataModel);
     assert_eq!(data_models.len(), 1, "Expected 1 data model");
 
+    let interfaces = graph.find_nodes_by_type(NodeType::Interface);
+    assert_eq!(interfaces.len(), 1, "Expected 1 interface");
+    assert_eq!(interfaces[0].name, "PersonRepository");
+
+    let role = graph
+        .find_nodes_by_qualified_name(NodeType::Enum, "graph.stakgraph.java.model.Person.Role")
+        .pop()
+        .expect("Person.Role enum not found");
+    assert_eq!(role.meta.get("variants"), Some(&"ADMIN,MEMBER".to_string()));
+
+    let fields = graph.find_nodes_by_type(NodeType::Field);
+    assert_eq!(fields.len(), 4, "Expected 4 fields");
+    let id = fields
+        .iter()
+        .find(|f| f.name == "id")
+        .expect("Person.id field not found");
+    assert_eq!(id.data_type, Some("Long".to_string()));
+    assert_eq!(id.meta.get("visibility"), Some(&"private".to_string()));
+    assert_eq!(
+        id.meta.get("annotations"),
+        Some(&"@Id\n@GeneratedValue(strategy = GenerationType.IDENTITY)".to_string())
+    );
+    let class_field_edges =
+        graph.find_nodes_with_edge_type(NodeType::Class, NodeType::Field, EdgeType::Contains);
+    assert!(
+        class_field_edges
+            .iter()
+            .any(|(c, f)| c.name == "PersonController" && f.name == "repository"),
+        "PersonController should contain repository"
+    );
+
     let requests = graph.find_nodes_by_type(NodeType::Endpoint);
     assert_eq!(requests.len(), 2, "Expected 2 endpoints");
 
+    let get_person = functions
+        .iter()
+        .find(|f| f.name == "getPerson")
+        .expect("getPerson not found");
+    assert_eq!(
+        get_person.docs,
+        Some("Looks up a single person by id.".to_string())
+    );
+
     let calls_edges_count = graph.count_edges_of_type(EdgeType::Calls);
     assert_eq!(calls_edges_count, 2, "Expected at 2 calls edges");
 
     let import_edges_count = graph.count_edges_of_type(EdgeType::Imports);
-    assert_eq!(import_edges_count, 2, "Expected at 2 import edges");
+    assert_eq!(import_edges_count, 3, "Expected at 3 import edges");
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_java() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_java_generic::<ArrayGraph>().await.unwrap();
     test_java_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_java_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_java_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
@@ -16,6 +16,9 @@ public class PersonController {
         this.repository = repository;
     }
 
+    /**
+     * Looks up a single person by id.
+     */
     @GetMapping("/person/{id}")
     public ResponseEntity<Person> getPerson(@PathVariable Long id) {
         Optional<Person> person = getPersonById(id);
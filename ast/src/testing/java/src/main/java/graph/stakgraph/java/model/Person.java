@@ -12,6 +12,8 @@ public class Person {
     private Long id;
     private String name;
     private String email;
+
+    public enum Role { ADMIN, MEMBER }
     
     
     public Person() {}
@@ -119,16 +119,16 @@ import com.kotlintestapp.db.PersonDatabase"#
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_kotlin() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_kotlin_generic::<ArrayGraph>().await.unwrap();
     test_kotlin_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_kotlin_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_kotlin_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
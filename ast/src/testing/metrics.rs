@@ -0,0 +1,114 @@
+use crate::lang::graphs::NodeType;
+use crate::lang::metrics::{hotspots, FunctionMetrics};
+use crate::lang::{Graph, Lang};
+use crate::testing::utils::rust_fixture_graph;
+use std::str::FromStr;
+
+pub async fn test_metrics_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let graph = rust_fixture_graph::<G>().await?;
+
+    let functions = graph.find_nodes_by_type(NodeType::Function);
+    assert!(
+        functions
+            .iter()
+            .all(|f| FunctionMetrics::from_meta(f).is_some()),
+        "every function should have metrics"
+    );
+
+    // two nested ifs, an early return and a chain of builder calls
+    let init_db = graph
+        .find_node_by_name_in_file(NodeType::Function, "init_db", "src/testing/rust/src/db.rs")
+        .expect("init_db not found");
+    assert_eq!(
+        FunctionMetrics::from_meta(&init_db).unwrap(),
+        FunctionMetrics {
+            complexity: 3,
+            nesting_depth: 2,
+            param_count: 0,
+            loc: 26,
+            call_count: 13,
+            return_count: 1,
+        }
+    );
+
+    // a match with two arms
+    let get_person = graph
+        .find_node_by_name_and_file_end_with(NodeType::Function, "get_person", "actix_routes.rs")
+        .expect("actix get_person not found");
+    let metrics = FunctionMetrics::from_meta(&get_person).unwrap();
+    assert_eq!(metrics.complexity, 3);
+    assert_eq!(metrics.nesting_depth, 1);
+    assert_eq!(metrics.param_count, 1);
+    assert_eq!(get_person.meta.get("loc"), Some(&"10".to_string()));
+
+    let deepest = hotspots(&graph, "nesting_depth", 1);
+    assert_eq!(deepest.len(), 1);
+    assert_eq!(deepest[0].name, "init_db");
+    let most_calls = hotspots(&graph, "call_count", 3)
+        .into_iter()
+        .map(|f| f.name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        most_calls,
+        vec!["init_db", "create_person", "start_rocket_server"]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_metrics() {
+    test_all_graphs!(test_metrics_generic);
+}
+
+// metrics of the first `kind` node in a snippet
+fn snippet_metrics(lang: &str, kind: &str, code: &str) -> FunctionMetrics {
+    let lang = Lang::from_str(lang).unwrap();
+    let tree = lang.lang().parse(code, &NodeType::Function).unwrap();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.kind() == kind {
+            return lang.lang().function_metrics(node, code);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    panic!("no {} in the snippet", kind);
+}
+
+#[test]
+fn test_ruby_metrics() {
+    let code = r#"def save_all(people)
+  return if people.empty?
+  people.each { |p| p.save unless p.persisted? }
+  flush while pending?
+end
+"#;
+    let metrics = snippet_metrics("ruby", "method", code);
+    assert_eq!(metrics.complexity, 4, "each modifier adds a path");
+    assert_eq!(metrics.nesting_depth, 0, "modifiers don't nest");
+    assert_eq!(metrics.param_count, 1);
+    assert_eq!(metrics.return_count, 1);
+}
+
+#[test]
+fn test_java_metrics() {
+    let code = r#"class Picker {
+    int pick(int a, int b) {
+        if (a > 0) if (b > 0) return 1;
+        if (a < 0) {
+            return 2;
+        } else if (b < 0) {
+            return 3;
+        }
+        return 0;
+    }
+}
+"#;
+    let metrics = snippet_metrics("java", "method_declaration", code);
+    assert_eq!(metrics.complexity, 5);
+    // the braceless if inside an if nests, the else if doesn't
+    assert_eq!(metrics.nesting_depth, 2);
+    assert_eq!(metrics.param_count, 2);
+    assert_eq!(metrics.return_count, 4);
+}
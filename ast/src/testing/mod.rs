@@ -4,14 +4,42 @@ use std::env;
 use std::str::FromStr;
 // use tracing_test::traced_test;
 
+// runs a `*_generic` test against every graph that's compiled in
+macro_rules! test_all_graphs {
+    ($test:ident) => {{
+        $test::<crate::lang::graphs::ArrayGraph>().await.unwrap();
+        $test::<crate::lang::graphs::BTreeMapGraph>().await.unwrap();
+        #[cfg(feature = "sled")]
+        $test::<crate::lang::graphs::SledGraph>().await.unwrap();
+        #[cfg(feature = "neo4j")]
+        {
+            let mut graph = crate::lang::graphs::Neo4jGraph::default();
+            graph.clear().await.unwrap();
+            $test::<crate::lang::graphs::Neo4jStagingGraph>()
+                .await
+                .unwrap();
+        }
+    }};
+}
+
 pub mod angular;
+pub mod c;
 pub mod cpp;
+pub mod csharp;
+pub mod dead_code;
+pub mod dependencies;
+pub mod export;
+pub mod features;
+pub mod gat;
 pub mod go;
 pub mod graphs;
+pub mod impact;
 pub mod java;
 pub mod kotlin;
+pub mod metrics;
 #[cfg(feature = "neo4j")]
 pub mod neo4j;
+pub mod php;
 pub mod python;
 pub mod react;
 pub mod ruby;
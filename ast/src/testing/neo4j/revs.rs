@@ -1,4 +1,3 @@
-use crate::lang::Graph;
 use crate::repo::{check_revs_files, Repo};
 use crate::utils::logger;
 use anyhow::{Context, Result};
@@ -6,7 +5,7 @@ use std::env;
 use tracing::{debug, info};
 
 #[cfg(feature = "neo4j")]
-use crate::lang::graphs::Neo4jGraph;
+use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_neo4j_revs() -> Result<()> {
     logger();
@@ -98,15 +97,15 @@ async fn test_neo4j_revs() -> Result<()> {
 
     let mut complete_graph = Neo4jGraph::default();
     complete_graph.connect().await?;
-    complete_graph.clear();
+    complete_graph.clear().await?;
 
     println!("=====Building complete graph=====");
-    let complete_graph = repos_without_filter
-        .build_graphs_inner::<Neo4jGraph>()
+    let mut complete_graph = repos_without_filter
+        .build_graphs_inner::<Neo4jStagingGraph>()
         .await
         .context("Failed to build complete graph")?;
 
-    let (complete_nodes, complete_edges) = complete_graph.get_graph_size();
+    let (complete_nodes, complete_edges) = complete_graph.flush().await?;
     println!(
         "Complete graph: \n {} nodes \n{} edges",
         complete_nodes, complete_edges
@@ -133,15 +132,15 @@ async fn test_neo4j_revs() -> Result<()> {
 
     let mut filtered_graph = Neo4jGraph::default();
     filtered_graph.connect().await?;
-    filtered_graph.clear();
+    filtered_graph.clear().await?;
 
     println!("=====Building filtered graph with revs=====");
-    let filtered_graph = repos_with_filter
-        .build_graphs_inner::<Neo4jGraph>()
+    let mut filtered_graph = repos_with_filter
+        .build_graphs_inner::<Neo4jStagingGraph>()
         .await
         .context("Failed to build filtered graph")?;
 
-    let (filtered_nodes, filtered_edges) = filtered_graph.get_graph_size();
+    let (filtered_nodes, filtered_edges) = filtered_graph.flush().await?;
     println!(
         "Filtered graph: \n{} nodes \n{} edges",
         filtered_nodes, filtered_edges
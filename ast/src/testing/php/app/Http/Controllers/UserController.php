@@ -0,0 +1,39 @@
+<?php
+
+namespace App\Http\Controllers;
+
+use App\Services\UserService;
+use Illuminate\Http\Request;
+
+class UserController extends Controller
+{
+    public function __construct(private UserService $users)
+    {
+    }
+
+    public function index()
+    {
+        return response()->json($this->users->listUsers());
+    }
+
+    public function store(Request $request)
+    {
+        $data = $request->validate([
+            'first_name' => 'required|string',
+            'last_name' => 'required|string',
+            'email' => 'required|email',
+        ]);
+        return response()->json($this->users->createUser($data), 201);
+    }
+
+    public function show(int $id)
+    {
+        return response()->json($this->users->findUser($id));
+    }
+
+    public function destroy(int $id)
+    {
+        $this->users->deleteUser($id);
+        return response()->noContent();
+    }
+}
@@ -0,0 +1,19 @@
+<?php
+
+namespace App\Models;
+
+use Illuminate\Database\Eloquent\Factories\HasFactory;
+use Illuminate\Database\Eloquent\Model;
+use Illuminate\Database\Eloquent\Relations\HasMany;
+
+class User extends Model
+{
+    use HasFactory;
+
+    protected $fillable = ['first_name', 'last_name', 'email'];
+
+    public function posts(): HasMany
+    {
+        return $this->hasMany(Post::class);
+    }
+}
@@ -0,0 +1,34 @@
+<?php
+
+namespace App\Services;
+
+use App\Models\User;
+use App\Traits\LogsActivity;
+
+class UserService
+{
+    use LogsActivity;
+
+    public function listUsers()
+    {
+        return User::all();
+    }
+
+    public function createUser(array $data): User
+    {
+        $user = User::create($data);
+        $this->logActivity('created ' . format_name($user->first_name, $user->last_name));
+        return $user;
+    }
+
+    public function findUser(int $id): ?User
+    {
+        return User::find($id);
+    }
+
+    public function deleteUser(int $id): void
+    {
+        User::query()->whereKey($id)->delete();
+        $this->logActivity("deleted {$id}");
+    }
+}
@@ -0,0 +1,13 @@
+<?php
+
+namespace App\Traits;
+
+use Illuminate\Support\Facades\Log;
+
+trait LogsActivity
+{
+    public function logActivity(string $message): void
+    {
+        Log::info($message);
+    }
+}
@@ -0,0 +1,6 @@
+<?php
+
+function format_name(string $first, string $last): string
+{
+    return trim($first . ' ' . $last);
+}
@@ -0,0 +1,154 @@
+use crate::lang::graphs::{EdgeType, NodeType};
+use crate::lang::Graph;
+use crate::{lang::Lang, repo::Repo};
+use std::str::FromStr;
+
+pub async fn test_php_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let repo = Repo::new(
+        "src/testing/php",
+        Lang::from_str("php").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+
+    let graph = repo.build_graph_inner::<G>().await?;
+
+    let (num_nodes, num_edges) = graph.get_graph_size();
+    assert_eq!(num_nodes, 63, "Expected 63 nodes");
+    assert_eq!(num_edges, 89, "Expected 89 edges");
+
+    let language_nodes = graph.find_nodes_by_type(NodeType::Language);
+    assert_eq!(language_nodes.len(), 1, "Expected 1 language node");
+    assert_eq!(
+        language_nodes[0].name, "php",
+        "Language node name should be 'php'"
+    );
+
+    let libraries = graph.find_nodes_by_type(NodeType::Library);
+    assert_eq!(libraries.len(), 4, "Expected 4 libraries");
+    let laravel = libraries
+        .iter()
+        .find(|l| l.name == "laravel/framework")
+        .expect("laravel/framework library not found");
+    assert_eq!(laravel.file, "src/testing/php/composer.json");
+    assert_eq!(laravel.meta.get("version"), Some(&"^11.0".to_string()));
+    assert!(
+        libraries.iter().any(|l| l.name == "phpunit/phpunit"),
+        "Expected require-dev library not found"
+    );
+
+    let imports = graph.find_nodes_by_type(NodeType::Import);
+    assert_eq!(imports.len(), 7, "Expected 7 imports");
+
+    let controller_import_body = format!(
+        r#"use App\Services\UserService;
+use Illuminate\Http\Request;"#
+    );
+    let controller_imports = imports
+        .iter()
+        .find(|i| i.file == "src/testing/php/app/Http/Controllers/UserController.php")
+        .unwrap();
+    assert_eq!(
+        controller_imports.body, controller_import_body,
+        "Controller import body is incorrect"
+    );
+
+    let classes = graph.find_nodes_by_type(NodeType::Class);
+    assert_eq!(classes.len(), 6, "Expected 6 classes");
+    let controller = classes
+        .iter()
+        .find(|c| c.name == "UserController")
+        .expect("UserController class not found");
+    assert_eq!(
+        controller.meta.get("parent"),
+        Some(&"Controller".to_string())
+    );
+
+    let traits = graph.find_nodes_by_type(NodeType::Trait);
+    assert_eq!(traits.len(), 1, "Expected 1 trait");
+    assert_eq!(traits[0].name, "LogsActivity");
+
+    let functions = graph.find_nodes_by_type(NodeType::Function);
+    assert_eq!(functions.len(), 14, "Expected 14 functions");
+    assert!(
+        functions
+            .iter()
+            .any(|f| f.name == "format_name" && f.file == "src/testing/php/app/helpers.php"),
+        "Expected top-level format_name function not found"
+    );
+    assert!(
+        functions.iter().any(|f| f.name == "makeUser"),
+        "Helpers in test classes are not tests"
+    );
+
+    let tests = graph.find_nodes_by_type(NodeType::Test);
+    assert_eq!(tests.len(), 2, "Expected 2 tests");
+    assert!(
+        tests.iter().any(|t| t.name == "test_lists_users"),
+        "Expected PHPUnit test not found"
+    );
+
+    let data_models = graph.find_nodes_by_type(NodeType::DataModel);
+    assert_eq!(data_models.len(), 2, "Expected 2 data models");
+    assert!(
+        data_models
+            .iter()
+            .any(|dm| dm.name == "User" && dm.file == "src/testing/php/app/Models/User.php"),
+        "Expected User model not found"
+    );
+
+    let endpoints = graph.find_nodes_by_type(NodeType::Endpoint);
+    assert_eq!(endpoints.len(), 4, "Expected 4 endpoints");
+
+    let list_users = endpoints
+        .iter()
+        .find(|e| e.name == "/api/users" && e.meta.get("verb") == Some(&"GET".to_string()))
+        .expect("GET /api/users endpoint not found");
+    assert_eq!(list_users.file, "src/testing/php/routes/api.php");
+    assert_eq!(
+        list_users.meta.get("handler"),
+        Some(&"UserController@index".to_string())
+    );
+
+    let delete_user = endpoints
+        .iter()
+        .find(|e| e.name == "/api/users/{id}" && e.meta.get("verb") == Some(&"DELETE".to_string()))
+        .expect("DELETE /api/users/{id} endpoint not found");
+    assert_eq!(
+        delete_user.meta.get("handler"),
+        Some(&"UserController@destroy".to_string()),
+        "String handlers should resolve to the controller action"
+    );
+
+    let handler_edges_count = graph.count_edges_of_type(EdgeType::Handler);
+    assert_eq!(handler_edges_count, 4, "Expected 4 handler edges");
+
+    let calls_edges_count = graph.count_edges_of_type(EdgeType::Calls);
+    assert_eq!(calls_edges_count, 7, "Expected 7 calls edges");
+
+    let operand_edges_count = graph.count_edges_of_type(EdgeType::Operand);
+    assert_eq!(operand_edges_count, 12, "Expected 12 operand edges");
+
+    let parent_of_edges_count = graph.count_edges_of_type(EdgeType::ParentOf);
+    assert_eq!(parent_of_edges_count, 1, "Expected 1 parent of edge");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_php() {
+    use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
+    test_php_generic::<ArrayGraph>().await.unwrap();
+    test_php_generic::<BTreeMapGraph>().await.unwrap();
+
+    #[cfg(feature = "neo4j")]
+    {
+        let mut graph = Neo4jGraph::default();
+        graph.clear().await.unwrap();
+        test_php_generic::<Neo4jStagingGraph>().await.unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+<?php
+
+use App\Http\Controllers\UserController;
+use Illuminate\Support\Facades\Route;
+
+Route::get('/users', [UserController::class, 'index']);
+Route::post('/users', [UserController::class, 'store']);
+Route::get('/users/{id}', [UserController::class, 'show']);
+Route::delete('/users/{id}', 'App\Http\Controllers\UserController@destroy');
+
+Route::get('/health', function () {
+    return ['status' => 'ok'];
+});
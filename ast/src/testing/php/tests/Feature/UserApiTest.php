@@ -0,0 +1,34 @@
+<?php
+
+namespace Tests\Feature;
+
+use App\Models\User;
+use Tests\TestCase;
+
+class UserApiTest extends TestCase
+{
+    public function test_lists_users(): void
+    {
+        User::factory()->count(2)->create();
+
+        $response = $this->getJson('/api/users');
+
+        $response->assertStatus(200)->assertJsonCount(2);
+    }
+
+    public function test_creates_user(): void
+    {
+        $response = $this->postJson('/api/users', [
+            'first_name' => 'Ada',
+            'last_name' => 'Lovelace',
+            'email' => 'ada@example.com',
+        ]);
+
+        $response->assertStatus(201);
+    }
+
+    private function makeUser(): User
+    {
+        return User::factory()->create();
+    }
+}
@@ -16,8 +16,8 @@ pub async fn test_python_generic<G: Graph>() -> Result<(), anyhow::Error> {
     let graph = repo.build_graph_inner::<G>().await?;
 
     let (num_nodes, num_edges) = graph.get_graph_size();
-    assert_eq!(num_nodes, 86, "Expected 86 nodes");
-    assert_eq!(num_edges, 118, "Expected 118 edges");
+    assert_eq!(num_nodes, 96, "Expected 96 nodes");
+    assert_eq!(num_edges, 128, "Expected 128 edges");
 
     let language_nodes = graph.find_nodes_by_type(NodeType::Language);
     assert_eq!(language_nodes.len(), 1, "Expected 1 language node");
@@ -40,7 +40,7 @@ pub async fn test_python_generic<G: Graph>() -> Result<(), anyhow::Error> {
     assert_eq!(calls, 12, "Expected 12 call edges");
 
     let contains = graph.count_edges_of_type(EdgeType::Contains);
-    assert_eq!(contains, 93, "Expected 93 contains edges");
+    assert_eq!(contains, 103, "Expected 103 contains edges");
 
     let main_import_body = format!(
         r#"import os
@@ -77,6 +77,23 @@ from flask_app.routes import flask_bp"#
             .any(|c| c.name == "Person" && c.file == "src/testing/python/model.py"),
         "Expected Person class not found"
     );
+    let person = classes
+        .iter()
+        .find(|c| c.name == "Person" && c.file == "src/testing/python/model.py")
+        .unwrap();
+    assert_eq!(
+        person.docs,
+        Some("Person model for storing user details".to_string())
+    );
+    assert_eq!(
+        person.meta.get("qualified_name"),
+        Some(&"model.Person".to_string())
+    );
+    let repr = graph
+        .find_nodes_by_qualified_name(NodeType::Function, "model.Person.__repr__")
+        .pop()
+        .expect("model.Person.__repr__ not found");
+    assert_eq!(repr.name, "__repr__");
 
     let class_function_edges =
         graph.find_nodes_with_edge_type(NodeType::Class, NodeType::Function, EdgeType::Operand);
@@ -85,6 +102,18 @@ from flask_app.routes import flask_bp"#
     let data_models = graph.find_nodes_by_type(NodeType::DataModel);
     assert_eq!(data_models.len(), 3, "Expected 3 data models");
 
+    let fields = graph.find_nodes_by_type(NodeType::Field);
+    assert_eq!(fields.len(), 10, "Expected 10 fields");
+    let optional_id = fields
+        .iter()
+        .find(|f| f.meta.get("qualified_name") == Some(&"model.CreateOrEditPerson.id".to_string()))
+        .expect("CreateOrEditPerson.id field not found");
+    assert_eq!(optional_id.data_type, Some("Optional[int]".to_string()));
+    assert_eq!(
+        optional_id.meta.get("default_value"),
+        Some(&"None".to_string())
+    );
+
     let endpoints = graph.find_nodes_by_type(NodeType::Endpoint);
     assert_eq!(endpoints.len(), 6, "Expected 6 endpoints");
 
@@ -96,16 +125,16 @@ from flask_app.routes import flask_bp"#
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_python() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_python_generic::<ArrayGraph>().await.unwrap();
     test_python_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_python_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_python_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
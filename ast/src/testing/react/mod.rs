@@ -20,11 +20,11 @@ pub async fn test_react_typescript_generic<G: Graph>() -> Result<(), anyhow::Err
 
     let (num_nodes, num_edges) = graph.get_graph_size();
     if use_lsp == true {
-        assert_eq!(num_nodes, 67, "Expected 67 nodes");
-        assert_eq!(num_edges, 88, "Expected 88 edges");
+        assert_eq!(num_nodes, 70, "Expected 70 nodes");
+        assert_eq!(num_edges, 91, "Expected 91 edges");
     } else {
-        assert_eq!(num_nodes, 61, "Expected 61 nodes");
-        assert_eq!(num_edges, 74, "Expected 74 edges");
+        assert_eq!(num_nodes, 64, "Expected 64 nodes");
+        assert_eq!(num_edges, 77, "Expected 77 edges");
     }
 
     fn normalize_path(path: &str) -> String {
@@ -195,9 +195,9 @@ import NewPerson from "./components/NewPerson";"#
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_react_typescript() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_react_typescript_generic::<ArrayGraph>().await.unwrap();
     test_react_typescript_generic::<BTreeMapGraph>()
         .await
@@ -206,7 +206,9 @@ async fn test_react_typescript() {
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_react_typescript_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_react_typescript_generic::<Neo4jStagingGraph>()
+            .await
+            .unwrap();
     }
 }
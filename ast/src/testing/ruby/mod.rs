@@ -19,8 +19,8 @@ pub async fn test_ruby_generic<G: Graph>() -> Result<(), anyhow::Error> {
     graph.analysis();
 
     let (num_nodes, num_edges) = graph.get_graph_size();
-    assert_eq!(num_nodes, 67, "Expected 67 nodes");
-    assert_eq!(num_edges, 110, "Expected 110 edges");
+    assert_eq!(num_nodes, 76, "Expected 76 nodes");
+    assert_eq!(num_edges, 119, "Expected 119 edges");
 
     let language_nodes = graph.find_nodes_by_type(NodeType::Language);
     assert_eq!(language_nodes.len(), 1, "Expected 1 language node");
@@ -150,16 +150,16 @@ pub async fn test_ruby_generic<G: Graph>() -> Result<(), anyhow::Error> {
 
 #[test(tokio::test(flavor = "multi_thread", worker_threads = 2))]
 async fn test_ruby() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_ruby_generic::<ArrayGraph>().await.unwrap();
     test_ruby_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_ruby_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_ruby_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
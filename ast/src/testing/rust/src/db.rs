@@ -4,6 +4,7 @@ use sqlx::FromRow;
 use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
 use std::sync::OnceLock;
 
+/// A person stored in the people table.
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Person {
     #[serde(skip_deserializing)]
@@ -12,6 +13,24 @@ pub struct Person {
     pub email: String,
 }
 
+impl Person {
+    pub fn new(name: &str, email: &str) -> Self {
+        Person {
+            id: None,
+            name: name.to_string(),
+            email: email.to_string(),
+        }
+    }
+}
+
+/// What a person is allowed to do.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Role {
+    Admin,
+    Member,
+    Guest,
+}
+
 pub struct Database {
     pool: Pool<Sqlite>,
 }
@@ -42,7 +61,7 @@ pub async fn init_db() -> Result<()> {
     .await
     .context("failed to create table")?;
 
-    let db = Database { pool };
+    let db = Database::new(pool);
 
     if DB_INSTANCE.get().is_none() {
         if let Err(_) = DB_INSTANCE.set(db) {
@@ -54,6 +73,10 @@ pub async fn init_db() -> Result<()> {
 }
 
 impl Database {
+    fn new(pool: Pool<Sqlite>) -> Self {
+        Database { pool }
+    }
+
     async fn new_person_impl(&self, person: Person) -> Result<Person> {
         let id = sqlx::query("INSERT INTO people (name, email) VALUES (?, ?)")
             .bind(&person.name)
@@ -85,8 +108,33 @@ impl Database {
     pub async fn new_person(person: Person) -> Result<Person> {
         get_db().await.new_person_impl(person).await
     }
+    /// Looks up a person by id.
+    ///
+    /// Fails if the database was not initialized.
     pub async fn get_person_by_id(id: u32) -> Result<Person> {
         let result: Result<Person> = get_db().await.get_person_by_id_impl(id).await;
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_person() -> Person {
+        Person::new("Alice", "alice@example.com")
+    }
+
+    #[tokio::test]
+    async fn test_new_person() {
+        init_db().await.unwrap();
+        let person = Database::new_person(sample_person()).await.unwrap();
+        assert!(person.id.is_some());
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_db_before_init() {
+        let _ = DB_INSTANCE.get().expect("Database not initialized");
+    }
+}
@@ -0,0 +1,17 @@
+use serde_json::json;
+
+fn person_payload() -> serde_json::Value {
+    json!({ "name": "Bob", "email": "bob@example.com" })
+}
+
+#[tokio::test]
+async fn creates_person_over_http() {
+    let client = reqwest::Client::new();
+    let res = client
+        .post("http://localhost:5002/person")
+        .json(&person_payload())
+        .send()
+        .await
+        .unwrap();
+    assert!(res.status().is_success());
+}
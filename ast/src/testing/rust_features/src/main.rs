@@ -0,0 +1,23 @@
+use axum::{routing::get, Router};
+
+pub fn create_router() -> Router {
+    Router::new()
+        .route("/person/:id", get(get_person))
+        .route("/order/:id", get(get_order))
+}
+
+async fn get_person() -> String {
+    load_person()
+}
+
+fn load_person() -> String {
+    "person".to_string()
+}
+
+async fn get_order() -> String {
+    load_order()
+}
+
+fn load_order() -> String {
+    "order".to_string()
+}
@@ -0,0 +1,36 @@
+mod routes;
+
+use crate::routes::{
+    actix_routes::config, axum_routes::create_router, rocket_routes::create_rocket,
+};
+
+#[tokio::main]
+async fn main() {
+    tokio::select! {
+        _ = start_axum_server() => {},
+        _ = start_actix_server() => {},
+        _ = start_rocket_server() => {},
+    }
+}
+
+// the router is built in axum_routes.rs and nested here
+async fn start_axum_server() {
+    let app = axum::Router::new().nest("/v1", create_router());
+    axum::Server::bind(&"0.0.0.0:5002".parse().unwrap())
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+async fn start_actix_server() {
+    actix_web::HttpServer::new(|| actix_web::App::new().configure(config))
+        .bind("0.0.0.0:5004")
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+}
+
+async fn start_rocket_server() {
+    create_rocket().launch().await.unwrap();
+}
@@ -0,0 +1,31 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+
+#[get("/items/{id}")]
+async fn get_item(path: web::Path<u32>) -> impl Responder {
+    HttpResponse::Ok().body(path.into_inner().to_string())
+}
+
+#[post("/items")]
+async fn create_item(body: String) -> impl Responder {
+    HttpResponse::Created().body(body)
+}
+
+#[get("/health")]
+async fn health() -> impl Responder {
+    HttpResponse::Ok().finish()
+}
+
+#[get("/orders")]
+async fn list_orders() -> impl Responder {
+    HttpResponse::Ok().finish()
+}
+
+// a scope nested in another, next to a second top-level scope
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api")
+            .service(web::scope("/v2").service(get_item).service(create_item))
+            .service(health),
+    )
+    .service(web::scope("/shop").service(list_orders));
+}
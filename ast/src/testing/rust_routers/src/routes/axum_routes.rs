@@ -0,0 +1,9 @@
+use axum::{extract::Path, routing::get, Router};
+
+pub fn create_router() -> Router {
+    Router::new().route("/users/:id", get(get_user))
+}
+
+async fn get_user(Path(id): Path<u32>) -> String {
+    format!("user {}", id)
+}
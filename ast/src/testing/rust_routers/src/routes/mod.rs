@@ -0,0 +1,3 @@
+pub mod actix_routes;
+pub mod axum_routes;
+pub mod rocket_routes;
@@ -0,0 +1,17 @@
+use rocket::{get, routes};
+
+#[get("/<id>")]
+pub async fn get_store(id: u32) -> String {
+    format!("store {}", id)
+}
+
+#[get("/")]
+pub async fn index() -> &'static str {
+    "stores"
+}
+
+pub fn create_rocket() -> rocket::Rocket<rocket::Build> {
+    rocket::build()
+        .mount("/stores", routes![get_store])
+        .mount("/", routes![index])
+}
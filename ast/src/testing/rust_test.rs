@@ -1,5 +1,6 @@
 use crate::lang::graphs::{EdgeType, NodeType};
 use crate::lang::Graph;
+use crate::testing::utils::find_endpoint;
 use crate::{lang::Lang, repo::Repo};
 use std::str::FromStr;
 
@@ -16,8 +17,8 @@ pub async fn test_rust_generic<G: Graph>() -> Result<(), anyhow::Error> {
     let graph = repo.build_graph_inner::<G>().await?;
 
     let (num_nodes, num_edges) = graph.get_graph_size();
-    assert_eq!(num_nodes, 49, "Expected 49 nodes");
-    assert_eq!(num_edges, 72, "Expected 72 edges");
+    assert_eq!(num_nodes, 64, "Expected 64 nodes");
+    assert_eq!(num_edges, 108, "Expected 108 edges");
 
     let language_nodes = graph.find_nodes_by_type(NodeType::Language);
     assert_eq!(language_nodes.len(), 1, "Expected 1 language node");
@@ -30,10 +31,10 @@ pub async fn test_rust_generic<G: Graph>() -> Result<(), anyhow::Error> {
         "Language node file path is incorrect"
     );
     let files = graph.find_nodes_by_type(NodeType::File);
-    assert_eq!(files.len(), 7, "Expected 7 files");
+    assert_eq!(files.len(), 8, "Expected 8 files");
 
     let imports = graph.find_nodes_by_type(NodeType::Import);
-    assert_eq!(imports.len(), 5, "Expected 5 imports");
+    assert_eq!(imports.len(), 6, "Expected 6 imports");
 
     let main_import_body = format!(
         r#"use crate::db::init_db;
@@ -59,31 +60,224 @@ use std::net::SocketAddr;"#
 
     let data_models = graph.find_nodes_by_type(NodeType::DataModel);
     assert_eq!(data_models.len(), 2, "Expected 2 data models");
+    let person = data_models
+        .iter()
+        .find(|dm| dm.name == "Person")
+        .expect("Person data model not found");
+    assert_eq!(
+        person.docs,
+        Some("A person stored in the people table.".to_string())
+    );
+
+    let fields = graph.find_nodes_by_type(NodeType::Field);
+    assert_eq!(fields.len(), 4, "Expected 4 fields");
+    let id = fields
+        .iter()
+        .find(|f| f.name == "id")
+        .expect("Person.id field not found");
+    assert_eq!(id.data_type, Some("Option<i32>".to_string()));
+    assert_eq!(id.meta.get("visibility"), Some(&"pub".to_string()));
+    assert_eq!(
+        id.meta.get("annotations"),
+        Some(&"#[serde(skip_deserializing)]".to_string())
+    );
+    assert_eq!(
+        id.meta.get("qualified_name"),
+        Some(&"db::Person::id".to_string())
+    );
+    let pool = fields
+        .iter()
+        .find(|f| f.name == "pool")
+        .expect("Database.pool field not found");
+    assert_eq!(pool.meta.get("visibility"), None);
+    let field_edges =
+        graph.find_nodes_with_edge_type(NodeType::DataModel, NodeType::Field, EdgeType::Contains);
+    assert_eq!(field_edges.len(), 4, "Expected 4 data model field edges");
+    assert!(
+        field_edges
+            .iter()
+            .any(|(dm, f)| dm.name == "Database" && f.name == "pool"),
+        "Database should contain pool"
+    );
+
+    let enums = graph.find_nodes_by_type(NodeType::Enum);
+    assert_eq!(enums.len(), 1, "Expected 1 enum");
+    let role = &enums[0];
+    assert_eq!(role.name, "Role");
+    assert_eq!(
+        role.meta.get("variants"),
+        Some(&"Admin,Member,Guest".to_string())
+    );
+    assert_eq!(
+        role.docs,
+        Some("What a person is allowed to do.".to_string())
+    );
 
     let endpoints = graph.find_nodes_by_type(NodeType::Endpoint);
     assert_eq!(endpoints.len(), 6, "Expected 6 endpoints");
 
+    find_endpoint(&endpoints, "/person/:id", "GET", "axum_routes.rs");
+    find_endpoint(&endpoints, "/person", "POST", "axum_routes.rs");
+    find_endpoint(&endpoints, "/person/{id}", "GET", "actix_routes.rs");
+    find_endpoint(&endpoints, "/person", "POST", "actix_routes.rs");
+    // mount("/") adds nothing
+    find_endpoint(&endpoints, "/person/<id>", "GET", "rocket_routes.rs");
+    find_endpoint(&endpoints, "/person", "POST", "rocket_routes.rs");
+
+    let handler_edges = graph.count_edges_of_type(EdgeType::Handler);
+    assert_eq!(handler_edges, 6, "Expected 6 handler edges");
+
     let imported_edges = graph.count_edges_of_type(EdgeType::Imports);
     assert_eq!(imported_edges, 4, "Expected 4 import edges");
 
-    let calls_edges = graph.count_edges_of_type(EdgeType::Contains);
-    assert_eq!(calls_edges, 62, "Expected 62 contains edges");
+    let contains_edges = graph.count_edges_of_type(EdgeType::Contains);
+    assert_eq!(contains_edges, 79, "Expected 79 contains edges");
+
+    let calls_edges = graph.count_edges_of_type(EdgeType::Calls);
+    assert_eq!(calls_edges, 19, "Expected 19 calls edges");
+
+    let tests = graph.find_nodes_by_type(NodeType::Test);
+    assert_eq!(tests.len(), 3, "Expected 3 tests");
+    let unit_test = tests
+        .iter()
+        .find(|t| t.name == "test_new_person")
+        .expect("#[tokio::test] test not found");
+    assert_eq!(unit_test.file, "src/testing/rust/src/db.rs");
+    assert_eq!(unit_test.meta.get("test_type"), Some(&"unit".to_string()));
+    assert!(
+        tests.iter().any(|t| t.name == "get_db_before_init"),
+        "#[test] with a second attribute not found"
+    );
+    let integration_test = tests
+        .iter()
+        .find(|t| t.name == "creates_person_over_http")
+        .expect("integration test not found");
+    assert_eq!(
+        integration_test.meta.get("test_type"),
+        Some(&"integration".to_string())
+    );
+
+    let functions = graph.find_nodes_by_type(NodeType::Function);
+    assert!(
+        !functions.iter().any(|f| f.name == "test_new_person"),
+        "Tests should not be functions"
+    );
+    assert!(
+        functions.iter().any(|f| f.name == "person_payload"),
+        "Integration test helpers are functions"
+    );
+    assert!(
+        functions.iter().any(|f| f.name == "sample_person"),
+        "Helpers in a cfg(test) module are functions"
+    );
+
+    let get_person_by_id = functions
+        .iter()
+        .find(|f| f.name == "get_person_by_id")
+        .expect("get_person_by_id not found");
+    assert_eq!(
+        get_person_by_id.docs,
+        Some("Looks up a person by id.\n\nFails if the database was not initialized.".to_string())
+    );
+    // a plain comment above the attributes is not a doc comment
+    assert!(
+        functions
+            .iter()
+            .filter(|f| f.file.ends_with("rocket_routes.rs"))
+            .all(|f| f.docs.is_none()),
+        "Rocket handlers should have no docs"
+    );
+
+    assert_eq!(
+        get_person_by_id.meta.get("qualified_name"),
+        Some(&"db::Database::get_person_by_id".to_string())
+    );
+    let person = graph
+        .find_nodes_by_qualified_name(NodeType::DataModel, "db::Person")
+        .pop()
+        .expect("db::Person not found");
+    assert_eq!(person.name, "Person");
+    let person_new = graph
+        .find_nodes_by_qualified_name(NodeType::Function, "db::Person::new")
+        .pop()
+        .expect("db::Person::new not found");
+    let database_new = graph
+        .find_nodes_by_qualified_name(NodeType::Function, "db::Database::new")
+        .pop()
+        .expect("db::Database::new not found");
+    assert_ne!(person_new.start, database_new.start);
+
+    // both `new`s are in db.rs, the operand picks the right one
+    let calls =
+        graph.find_nodes_with_edge_type(NodeType::Function, NodeType::Function, EdgeType::Calls);
+    let init_db_calls = calls
+        .iter()
+        .filter(|(src, _)| src.name == "init_db")
+        .map(|(_, dst)| dst)
+        .collect::<Vec<_>>();
+    assert!(
+        init_db_calls.iter().any(|f| f.start == database_new.start),
+        "init_db should call Database::new"
+    );
+    assert!(
+        !init_db_calls.iter().any(|f| f.start == person_new.start),
+        "init_db should not call Person::new"
+    );
 
     Ok(())
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_rust() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_rust_generic::<ArrayGraph>().await.unwrap();
     test_rust_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_rust_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_rust_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
+
+// prefixes from axum nest, nested and sibling actix scopes and rocket mounts
+pub async fn test_rust_routers_generic<G: Graph>() -> Result<(), anyhow::Error> {
+    let repo = Repo::new(
+        "src/testing/rust_routers",
+        Lang::from_str("rust").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+    .unwrap();
+
+    let graph = repo.build_graph_inner::<G>().await?;
+
+    let endpoints = graph.find_nodes_by_type(NodeType::Endpoint);
+    assert_eq!(endpoints.len(), 7, "Expected 7 endpoints");
+
+    // the router is built in axum_routes.rs and nested in main.rs
+    find_endpoint(&endpoints, "/v1/users/:id", "GET", "axum_routes.rs");
+    // a scope inside a scope
+    find_endpoint(&endpoints, "/api/v2/items/{id}", "GET", "actix_routes.rs");
+    find_endpoint(&endpoints, "/api/v2/items", "POST", "actix_routes.rs");
+    find_endpoint(&endpoints, "/api/health", "GET", "actix_routes.rs");
+    // a second scope on the same config
+    find_endpoint(&endpoints, "/shop/orders", "GET", "actix_routes.rs");
+    // two mounts on one rocket
+    find_endpoint(&endpoints, "/stores/<id>", "GET", "rocket_routes.rs");
+    find_endpoint(&endpoints, "/", "GET", "rocket_routes.rs");
+
+    let handler_edges = graph.count_edges_of_type(EdgeType::Handler);
+    assert_eq!(handler_edges, 7, "Expected 7 handler edges");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_rust_routers() {
+    test_all_graphs!(test_rust_routers_generic);
+}
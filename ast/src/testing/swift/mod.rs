@@ -83,16 +83,16 @@ pub async fn test_swift_generic<G: Graph>() -> Result<(), anyhow::Error> {
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_swift() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_swift_generic::<ArrayGraph>().await.unwrap();
     test_swift_generic::<BTreeMapGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_swift_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_swift_generic::<Neo4jStagingGraph>().await.unwrap();
     }
 }
@@ -20,11 +20,11 @@ pub async fn test_typescript_generic<G: Graph>() -> Result<(), anyhow::Error> {
 
     let (num_nodes, num_edges) = graph.get_graph_size();
     if use_lsp {
-        assert_eq!(num_nodes, 49, "Expected 49 nodes");
-        assert!(num_edges >= 72 && num_edges <= 74, "Expected 72 edges");
+        assert_eq!(num_nodes, 63, "Expected 63 nodes");
+        assert!(num_edges >= 87 && num_edges <= 89, "Expected 87 edges");
     } else {
-        assert_eq!(num_nodes, 46, "Expected 46 nodes");
-        assert_eq!(num_edges, 66, "Expected 66 edges");
+        assert_eq!(num_nodes, 60, "Expected 60 nodes");
+        assert_eq!(num_edges, 81, "Expected 81 edges");
     }
 
     fn normalize_path(path: &str) -> String {
@@ -96,20 +96,48 @@ import {{ sequelize }} from "./config.js";"#
     assert_eq!(calls_edges_count, 2, "Expected 2 calls edges");
 
     let data_models = graph.find_nodes_by_type(NodeType::DataModel);
-    assert_eq!(data_models.len(), 4, "Expected 4 data models");
+    assert_eq!(data_models.len(), 2, "Expected 2 data models");
+
+    let interfaces = graph.find_nodes_by_type(NodeType::Interface);
+    assert_eq!(interfaces.len(), 2, "Expected 2 interfaces");
+    let implements =
+        graph.find_nodes_with_edge_type(NodeType::Class, NodeType::Interface, EdgeType::Implements);
+    assert_eq!(implements.len(), 1, "Expected 1 implements edge");
+    assert_eq!(implements[0].0.name, "SequelizePerson");
+    assert_eq!(implements[0].1.name, "PersonAttributes");
+
+    let enums = graph.find_nodes_by_type(NodeType::Enum);
+    assert_eq!(enums.len(), 1, "Expected 1 enum");
+    assert_eq!(enums[0].name, "PersonRole");
+    assert_eq!(
+        enums[0].meta.get("variants"),
+        Some(&"Admin,Member".to_string())
+    );
+
+    let get_person_by_id = graph
+        .find_nodes_by_name(NodeType::Function, "getPersonById")
+        .pop()
+        .expect("getPersonById not found");
+    assert_eq!(
+        get_person_by_id.docs,
+        Some(
+            "Finds a person by primary key.\nResolves to null when there is no match.".to_string()
+        )
+    );
 
     let variables = graph.find_nodes_by_type(NodeType::Var);
     assert_eq!(variables.len(), 4, "Expected 4 variables");
 
+    // the file also contains the PersonRole enum
     let contains = graph.count_edges_of_type(EdgeType::Contains);
 
     if use_lsp {
         assert!(
-            contains >= 48 && contains <= 50,
-            "Expected 48 contains edges"
+            contains >= 62 && contains <= 64,
+            "Expected 62 contains edges"
         );
     } else {
-        assert_eq!(contains, 50, "Expected 50 contains edges");
+        assert_eq!(contains, 64, "Expected 64 contains edges");
     }
 
     let import_edges_count = graph.count_edges_of_type(EdgeType::Imports);
@@ -124,16 +152,18 @@ import {{ sequelize }} from "./config.js";"#
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_typescript() {
-    #[cfg(feature = "neo4j")]
-    use crate::lang::graphs::Neo4jGraph;
     use crate::lang::graphs::{ArrayGraph, BTreeMapGraph};
+    #[cfg(feature = "neo4j")]
+    use crate::lang::graphs::{Neo4jGraph, Neo4jStagingGraph};
     test_typescript_generic::<BTreeMapGraph>().await.unwrap();
     test_typescript_generic::<ArrayGraph>().await.unwrap();
 
     #[cfg(feature = "neo4j")]
     {
         let mut graph = Neo4jGraph::default();
-        graph.clear();
-        test_typescript_generic::<Neo4jGraph>().await.unwrap();
+        graph.clear().await.unwrap();
+        test_typescript_generic::<Neo4jStagingGraph>()
+            .await
+            .unwrap();
     }
 }
@@ -2,6 +2,11 @@ import DataTypes, { Model } from "sequelize";
 import { Entity, Column, PrimaryGeneratedColumn } from "typeorm";
 import { sequelize } from "./config.js";
 
+export enum PersonRole {
+  Admin = "admin",
+  Member = "member",
+}
+
 interface PersonAttributes {
   id?: number;
   name: string;
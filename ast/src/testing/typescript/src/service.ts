@@ -6,6 +6,10 @@ export interface PersonData {
   email: string;
 }
 
+/**
+ * Finds a person by primary key.
+ * Resolves to null when there is no match.
+ */
 export async function getPersonById(id: number): Promise<PersonData | null> {
   const person = await SequelizePerson.findByPk(id);
   if (!person) {
@@ -1,5 +1,9 @@
+use crate::lang::asg::NodeData;
+use crate::lang::{Graph, Lang};
+use crate::repo::Repo;
 use std::fmt;
 use std::io::Error;
+use std::str::FromStr;
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -70,3 +74,32 @@ impl From<anyhow::Error> for TestResult {
         TestResult::failure("Error", "An error occurred", &err.to_string())
     }
 }
+
+// the endpoint with this path and verb, in a file ending with `file`
+pub fn find_endpoint<'a>(
+    endpoints: &'a [NodeData],
+    name: &str,
+    verb: &str,
+    file: &str,
+) -> &'a NodeData {
+    endpoints
+        .iter()
+        .find(|e| {
+            e.name == name
+                && e.meta.get("verb").map(|v| v.as_str()) == Some(verb)
+                && e.file.ends_with(&format!("/{}", file))
+        })
+        .unwrap_or_else(|| panic!("{} {} endpoint not found in {}", verb, name, file))
+}
+
+// the graph of src/testing/rust, which the analysis tests all run against
+pub async fn rust_fixture_graph<G: Graph>() -> anyhow::Result<G> {
+    let repo = Repo::new(
+        "src/testing/rust",
+        Lang::from_str("rust").unwrap(),
+        false,
+        Vec::new(),
+        Vec::new(),
+    )?;
+    repo.build_graph_inner::<G>().await
+}
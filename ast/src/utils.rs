@@ -1,55 +1,37 @@
-use std::any::Any;
 use std::env;
 
-use crate::lang::graphs::{ArrayGraph, Node};
-use crate::lang::{BTreeMapGraph, Graph, NodeRef};
+use crate::export::{export_graph, ExportFormat};
+use crate::lang::graphs::Node;
+use crate::lang::{Graph, NodeRef};
 use anyhow::Result;
 use serde::Serialize;
 use std::fs::File;
 use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::warn;
 use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::EnvFilter;
+// OUTPUT_FORMAT picks one of the export formats (jsonl by default, json if unknown),
+// OUTPUT_PATH overrides where it's written and DOT_CLUSTER=true groups dot by directory
 pub fn print_json<G: Graph + Serialize + 'static>(graph: &G, name: &str) -> Result<()> {
-    use serde_jsonlines::write_json_lines;
-    match std::env::var("OUTPUT_FORMAT")
-        .unwrap_or_else(|_| "jsonl".to_string())
-        .as_str()
+    let format = env::var("OUTPUT_FORMAT").unwrap_or_else(|_| "jsonl".to_string());
+    let mut format = ExportFormat::from_str(&format).unwrap_or_else(|e| {
+        warn!("{}, writing pretty json instead", e);
+        ExportFormat::Json
+    });
+    if let ExportFormat::Dot {
+        cluster_by_directory,
+    } = &mut format
     {
-        "jsonl" => {
-            if let Some(array_graph) = as_array_graph(graph) {
-                let nodepath = format!("ast/examples/{}-nodes.jsonl", name);
-                write_json_lines(nodepath, &array_graph.nodes)?;
-                let edgepath = format!("ast/examples/{}-edges.jsonl", name);
-                write_json_lines(edgepath, &array_graph.edges)?;
-            } else if let Some(btreemap_graph) = as_btreemap_graph(graph) {
-                let nodepath = format!("ast/examples/{}-nodes.jsonl", name);
-                let node_values: Vec<_> = btreemap_graph.nodes.values().collect();
-                write_json_lines(nodepath, &node_values)?;
-                let edgepath = format!("ast/examples/{}-edges.jsonl", name);
-                let edge_values = btreemap_graph.to_array_graph_edges();
-                write_json_lines(edgepath, &edge_values)?;
-            } else {
-                //seriolize the whole graph otherwise
-                let pretty = serde_json::to_string_pretty(&graph)?;
-                let path = format!("ast/examples/{}.json", name);
-                std::fs::write(path, pretty)?;
-            }
-        }
-        _ => {
-            let pretty = serde_json::to_string_pretty(&graph)?;
-            let path = format!("ast/examples/{}.json", name);
-            std::fs::write(path, pretty)?;
-        }
+        *cluster_by_directory = env::var("DOT_CLUSTER").is_ok_and(|v| v == "true");
     }
-    Ok(())
-}
-
-fn as_array_graph<G: Graph + Serialize + 'static>(graph: &G) -> Option<&ArrayGraph> {
-    (graph as &dyn Any).downcast_ref::<ArrayGraph>()
-}
-
-fn as_btreemap_graph<G: Graph + Serialize + 'static>(graph: &G) -> Option<&BTreeMapGraph> {
-    (graph as &dyn Any).downcast_ref::<BTreeMapGraph>()
+    let path = env::var("OUTPUT_PATH")
+        .ok()
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| format.default_path(name));
+    export_graph(graph, &format, &path)
 }
 
 pub fn logger() {
@@ -68,6 +50,7 @@ pub fn create_node_key(node: &Node) -> String {
     let name = &node_data.name;
     let file = &node_data.file;
     let start = node_data.start.to_string();
+    let start_col = node_data.start_col.to_string();
     let meta = &node_data.meta;
 
     let mut result = String::new();
@@ -79,6 +62,8 @@ pub fn create_node_key(node: &Node) -> String {
     result.push_str(&sanitize_string(file));
     result.push('-');
     result.push_str(&sanitize_string(&start));
+    result.push('-');
+    result.push_str(&sanitize_string(&start_col));
 
     if let Some(v) = meta.get("verb") {
         result.push('-');
@@ -87,6 +72,25 @@ pub fn create_node_key(node: &Node) -> String {
     result
 }
 
+// "type-name-file-", the part of a node key without its position. edges to
+// calls that weren't resolved to a definition only agree with the node on this
+pub fn node_key_prefix(key: &str) -> Option<String> {
+    let mut parts = key.splitn(4, '-');
+    let (nt, name, file) = (parts.next()?, parts.next()?, parts.next()?);
+    Some(format!("{}-{}-{}-", nt, name, file))
+}
+
+// the key a node would get once `root` is put in front of its file
+pub fn rooted_node_key(key: &str, root: &str) -> String {
+    let mut parts = key.splitn(4, '-').collect::<Vec<_>>();
+    if parts.len() < 3 {
+        return key.to_string();
+    }
+    let file = format!("{}{}", sanitize_string(root), parts[2]);
+    parts[2] = &file;
+    parts.join("-")
+}
+
 pub fn get_use_lsp() -> bool {
     println!("===-==> Getting use LSP");
     env::set_var("LSP_SKIP_POST_CLONE", "true");
@@ -98,6 +102,19 @@ pub fn get_use_lsp() -> bool {
     false
 }
 
+pub fn get_parse_threads() -> usize {
+    if let Ok(threads) = env::var("PARSE_THREADS") {
+        if let Ok(n) = threads.parse::<usize>() {
+            if n > 0 {
+                return n;
+            }
+        }
+    }
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
 fn delete_react_testing_node_modules() -> std::io::Result<()> {
     let path = std::path::Path::new("src/testing/react/node_modules");
     if path.exists() {
@@ -110,6 +127,7 @@ pub fn create_node_key_from_ref(node_ref: &NodeRef) -> String {
     let name = &node_ref.node_data.name;
     let file = &node_ref.node_data.file;
     let start = &node_ref.node_data.start.to_string();
+    let start_col = node_ref.node_data.start_col.to_string();
 
     let mut result = String::new();
 
@@ -120,6 +138,8 @@ pub fn create_node_key_from_ref(node_ref: &NodeRef) -> String {
     result.push_str(&sanitize_string(file));
     result.push('-');
     result.push_str(&sanitize_string(&start));
+    result.push('-');
+    result.push_str(&sanitize_string(&start_col));
 
     if let Some(v) = &node_ref.node_data.verb {
         result.push('-');
@@ -17,9 +17,12 @@ pub enum Language {
     Svelte,
     Angular,
     Cpp,
+    CSharp,
+    C,
+    Php,
 }
 
-pub const PROGRAMMING_LANGUAGES: [Language; 12] = [
+pub const PROGRAMMING_LANGUAGES: [Language; 15] = [
     Language::Rust,
     Language::Go,
     Language::Typescript,
@@ -32,6 +35,9 @@ pub const PROGRAMMING_LANGUAGES: [Language; 12] = [
     Language::Svelte,
     Language::Angular,
     Language::Cpp,
+    Language::CSharp,
+    Language::C,
+    Language::Php,
 ];
 
 impl Language {
@@ -56,6 +62,9 @@ impl Language {
             Self::Svelte => vec!["package.json"],
             Self::Angular => vec!["package.json"],
             Self::Cpp => vec!["CMakeLists.txt"],
+            Self::CSharp => vec![".csproj", ".sln"],
+            Self::C => vec!["Makefile", "meson.build"],
+            Self::Php => vec!["composer.json"],
         }
     }
 
@@ -76,6 +85,10 @@ impl Language {
             Self::Svelte => vec!["svelte", "ts", "js"],
             Self::Angular => vec!["ts", "js"],
             Self::Cpp => vec!["cpp", "h"],
+            Self::CSharp => vec!["cs"],
+            // .h is shared with Cpp, see owns_headers in ast/src/repo.rs
+            Self::C => vec!["c", "h"],
+            Self::Php => vec!["php"],
         }
     }
 
@@ -104,6 +117,9 @@ impl Language {
             Self::Svelte => vec![".git", " node_modules"],
             Self::Angular => vec![".git", " node_modules"],
             Self::Cpp => vec![".git", "build", "out", "CMakeFiles"],
+            Self::CSharp => vec![".git", "bin", "obj", ".vs"],
+            Self::C => vec![".git", "build", "builddir"],
+            Self::Php => vec![".git", "vendor", "storage", "node_modules"],
         }
     }
 
@@ -131,6 +147,9 @@ impl Language {
             Self::Svelte => Vec::new(),
             Self::Angular => Vec::new(),
             Self::Cpp => Vec::new(),
+            Self::CSharp => Vec::new(),
+            Self::C => Vec::new(),
+            Self::Php => Vec::new(),
         }
     }
 
@@ -155,6 +174,9 @@ impl Language {
             Self::Svelte => false,
             Self::Angular => false,
             Self::Cpp => false,
+            Self::CSharp => false,
+            Self::C => false,
+            Self::Php => false,
         }
     }
 
@@ -173,6 +195,9 @@ impl Language {
             Self::Svelte => "svelte-language-server",
             Self::Angular => "angular-language-server",
             Self::Cpp => "",
+            Self::CSharp => "",
+            Self::C => "",
+            Self::Php => "",
         }
         .to_string()
     }
@@ -192,6 +217,9 @@ impl Language {
             Self::Svelte => "--version",
             Self::Angular => "--version",
             Self::Cpp => "--version",
+            Self::CSharp => "--version",
+            Self::C => "--version",
+            Self::Php => "--version",
         }
         .to_string()
     }
@@ -211,6 +239,9 @@ impl Language {
             Self::Svelte => Vec::new(),
             Self::Angular => Vec::new(),
             Self::Cpp => Vec::new(),
+            Self::CSharp => Vec::new(),
+            Self::C => Vec::new(),
+            Self::Php => Vec::new(),
         }
     }
 
@@ -230,6 +261,9 @@ impl Language {
             Self::Svelte => "svelte",
             Self::Angular => "angular",
             Self::Cpp => "cpp",
+            Self::CSharp => "csharp",
+            Self::C => "c",
+            Self::Php => "php",
         }
         .to_string()
     }
@@ -254,6 +288,9 @@ impl Language {
             Self::Svelte => Vec::new(),
             Self::Angular => Vec::new(),
             Self::Cpp => Vec::new(),
+            Self::CSharp => Vec::new(),
+            Self::C => Vec::new(),
+            Self::Php => Vec::new(),
         }
     }
 
@@ -312,6 +349,14 @@ impl FromStr for Language {
             "Cpp" => Ok(Language::Cpp),
             "c++" => Ok(Language::Cpp),
             "C++" => Ok(Language::Cpp),
+            "csharp" => Ok(Language::CSharp),
+            "CSharp" => Ok(Language::CSharp),
+            "c#" => Ok(Language::CSharp),
+            "C#" => Ok(Language::CSharp),
+            "c" => Ok(Language::C),
+            "C" => Ok(Language::C),
+            "php" => Ok(Language::Php),
+            "PHP" => Ok(Language::Php),
 
             _ => Err(anyhow::anyhow!("unsupported language")),
         }
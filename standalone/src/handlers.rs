@@ -1,4 +1,4 @@
-use crate::types::{AppError, ProcessBody, ProcessResponse, Result};
+use crate::types::{AppError, DiffSummary, ProcessBody, ProcessResponse, Result};
 use ast::lang::graphs::graph_ops::GraphOps;
 use ast::repo::Repo;
 use axum::Json;
@@ -6,76 +6,89 @@ use lsp::git::{get_commit_hash, git_pull_or_clone};
 use std::time::Instant;
 use tracing::info;
 
-// #[axum::debug_handler]
-// pub async fn process(body: Json<ProcessBody>) -> Result<Json<ProcessResponse>> {
-//     let (final_repo_path, final_repo_url, need_clone, username, pat) = resolve_repo(&body)?;
-
-//     clone_repo(
-//         need_clone,
-//         &final_repo_url,
-//         &final_repo_path,
-//         username.clone(),
-//         pat.clone(),
-//     )
-//     .await?;
-
-//     let repo_path = &final_repo_path;
-//     let repo_url = &final_repo_url;
-
-//     let current_hash = match get_commit_hash(&repo_path).await {
-//         Ok(hash) => hash,
-//         Err(e) => {
-//             return Err(AppError::Anyhow(anyhow::anyhow!(
-//                 "Could not get current hash: {}",
-//                 e
-//             )))
-//         }
-//     };
-
-//     let mut graph_ops = GraphOps::new();
-//     graph_ops.connect().await?;
-
-//     let stored_hash = match graph_ops.graph.get_repository_hash(&repo_url).await {
-//         Ok(hash) => Some(hash),
-//         Err(_) => None,
-//     };
-
-//     info!(
-//         "Current hash: {} | Stored hash: {:?}",
-//         current_hash, stored_hash
-//     );
-
-//     if let Some(hash) = &stored_hash {
-//         if hash == &current_hash {
-//             let (nodes, edges) = graph_ops.graph.get_graph_size().await?;
-//             return Ok(Json(ProcessResponse {
-//                 status: "success".to_string(),
-//                 message: "Repository already processed".to_string(),
-//                 nodes: nodes as usize,
-//                 edges: edges as usize,
-//             }));
-//         }
-//     }
-
-//     let (nodes, edges) = if let Some(hash) = stored_hash {
-//         info!("Updating repository hash from {} to {}", hash, current_hash);
-//         graph_ops
-//             .update_incremental(&repo_url, &repo_path, &current_hash, &hash)
-//             .await?
-//     } else {
-//         info!("Adding new repository hash: {}", current_hash);
-//         graph_ops
-//             .update_full(&repo_url, &repo_path, &current_hash)
-//             .await?
-//     };
-
-//     Ok(Json(ProcessResponse {
-//         status: "success".to_string(),
-//         message: "Repository processed successfully".to_string(),
-//         nodes: nodes as usize,
-//         edges: edges as usize,
-//     }))
-// }
+#[axum::debug_handler]
+pub async fn process(body: Json<ProcessBody>) -> Result<Json<ProcessResponse>> {
+    let (final_repo_path, final_repo_url, need_clone, username, pat) = resolve_repo(&body)?;
+
+    clone_repo(
+        need_clone,
+        &final_repo_url,
+        &final_repo_path,
+        username.clone(),
+        pat.clone(),
+    )
+    .await?;
+
+    let repo_path = &final_repo_path;
+    let repo_url = &final_repo_url;
+
+    let current_hash = match get_commit_hash(&repo_path).await {
+        Ok(hash) => hash,
+        Err(e) => {
+            return Err(AppError::Anyhow(anyhow::anyhow!(
+                "Could not get current hash: {}",
+                e
+            )))
+        }
+    };
+
+    let mut graph_ops = GraphOps::new();
+    graph_ops.connect().await?;
+
+    let stored_hash = match graph_ops.graph.get_repository_hash(&repo_url).await {
+        Ok(hash) if !hash.is_empty() => Some(hash),
+        _ => None,
+    };
+
+    info!(
+        "Current hash: {} | Stored hash: {:?}",
+        current_hash, stored_hash
+    );
+
+    if let Some(hash) = &stored_hash {
+        if hash == &current_hash {
+            let (nodes, edges) = graph_ops.graph.get_graph_size().await?;
+            return Ok(Json(ProcessResponse {
+                status: "success".to_string(),
+                message: "Repository already processed".to_string(),
+                nodes: nodes as usize,
+                edges: edges as usize,
+                diff: None,
+            }));
+        }
+    }
+
+    let (message, diff) = if let Some(hash) = stored_hash {
+        info!("Updating repository hash from {} to {}", hash, current_hash);
+        let summary = graph_ops
+            .update_incremental_summary(&repo_url, &repo_path, &current_hash, &hash)
+            .await?;
+        let diff = DiffSummary {
+            files_reparsed: summary.files,
+            files_deleted: summary.files_deleted,
+            files_renamed: summary.files_renamed,
+            nodes_added: summary.nodes_added as usize,
+            nodes_removed: summary.nodes_removed as usize,
+            edges_relinked: summary.edges_relinked as usize,
+        };
+        ("Repository updated incrementally", Some(diff))
+    } else {
+        info!("Adding new repository hash: {}", current_hash);
+        graph_ops
+            .update_full(&repo_url, &repo_path, &current_hash)
+            .await?;
+        ("Repository processed fully", None)
+    };
+
+    let (nodes, edges) = graph_ops.graph.get_graph_size().await?;
+    Ok(Json(ProcessResponse {
+        status: "success".to_string(),
+        message: message.to_string(),
+        nodes: nodes as usize,
+        edges: edges as usize,
+        diff,
+    }))
+}
 
 pub async fn clear_graph() -> Result<Json<ProcessResponse>> {
     let mut graph_ops = GraphOps::new();
@@ -86,6 +99,7 @@ pub async fn clear_graph() -> Result<Json<ProcessResponse>> {
         message: "Graph cleared".to_string(),
         nodes: nodes as usize,
         edges: edges as usize,
+        diff: None,
     }))
 }
 
@@ -147,6 +161,7 @@ pub async fn ingest(body: Json<ProcessBody>) -> Result<Json<ProcessResponse>> {
         message: "Repository ingested fully".to_string(),
         nodes: nodes as usize,
         edges: edges as usize,
+        diff: None,
     }))
 }
 
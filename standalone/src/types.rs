@@ -19,6 +19,17 @@ pub struct ProcessResponse {
     pub message: String,
     pub nodes: usize,
     pub edges: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<DiffSummary>,
+}
+#[derive(Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub files_reparsed: Vec<String>,
+    pub files_deleted: Vec<String>,
+    pub files_renamed: Vec<(String, String)>,
+    pub nodes_added: usize,
+    pub nodes_removed: usize,
+    pub edges_relinked: usize,
 }
 
 #[derive(Debug)]